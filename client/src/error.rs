@@ -1,5 +1,6 @@
 use bitcoin::{consensus, hex::HexToArrayError};
 use thiserror::Error;
+use titan_types::{ErrorCode, ErrorResponse};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -17,4 +18,35 @@ pub enum Error {
 
     #[error("bitcoin consensus error: {0}")]
     BitcoinConsensusError(#[from] consensus::encode::Error),
+
+    #[error("API key is not a valid header value")]
+    InvalidApiKey,
+}
+
+impl Error {
+    /// HTTP status returned by the server, if the request got a response.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Error::TitanError(status, _) => Some(*status),
+            Error::ReqwestError(error) => error.status(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(reqwest::StatusCode::NOT_FOUND)
+    }
+
+    /// Error body returned by the server, if it sent a structured one.
+    pub fn response(&self) -> Option<ErrorResponse> {
+        match self {
+            Error::TitanError(_, body) => serde_json::from_str(body).ok(),
+            _ => None,
+        }
+    }
+
+    /// Machine-readable error code returned by the server.
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.response().map(|response| response.code)
+    }
 }
@@ -1,5 +1,9 @@
-use bitcoin::{OutPoint, Txid};
-use reqwest::{header::HeaderMap, Client as AsyncReqwestClient};
+use bitcoin::{OutPoint, ScriptBuf, Txid, Wtxid};
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    Client as AsyncReqwestClient, Response,
+};
+use serde::de::DeserializeOwned;
 use std::{collections::HashMap, str::FromStr};
 use titan_types::*;
 
@@ -24,47 +28,67 @@ impl AsyncClient {
         }
     }
 
-    async fn call_text(&self, path: &str) -> Result<String, Error> {
-        let url = format!("{}{}", self.base_url, path);
-        let response = self.http_client.get(&url).send().await?;
+    /// Creates a new `AsyncClient` that sends `api_key` as a bearer token, for
+    /// servers started with `--api-keys`.
+    pub fn with_api_key(base_url: &str, api_key: &str) -> Result<Self, Error> {
+        Ok(Self {
+            http_client: AsyncReqwestClient::builder()
+                .default_headers(api_key_headers(api_key)?)
+                .build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// `response` if it's 2xx, or else `Error::TitanError` with its body.
+    async fn success(response: Response) -> Result<Response, Error> {
         if response.status().is_success() {
-            Ok(response.text().await?)
+            Ok(response)
         } else {
             Err(Error::TitanError(response.status(), response.text().await?))
         }
     }
 
+    /// Decodes a JSON body, or returns `Error::TitanError` for non-2xx responses.
+    async fn parse_json<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+        Ok(Self::success(response).await?.json().await?)
+    }
+
+    async fn call_text(&self, path: &str) -> Result<String, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self.http_client.get(&url).send().await?;
+        Ok(Self::success(response).await?.text().await?)
+    }
+
     async fn call_bytes(&self, path: &str) -> Result<Vec<u8>, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send().await?;
-        if response.status().is_success() {
-            Ok(response.bytes().await?.to_vec())
-        } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
-        }
+        Ok(Self::success(response).await?.bytes().await?.to_vec())
     }
 
     async fn post_text(&self, path: &str, body: String) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.post(&url).body(body).send().await?;
-        if response.status().is_success() {
-            Ok(response.text().await?)
-        } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
-        }
+        Ok(Self::success(response).await?.text().await?)
     }
 
     async fn delete(&self, path: &str) -> Result<(), Error> {
         let url = format!("{}{}", self.base_url, path);
-        let response = self.http_client.delete(&url).send().await?;
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(Error::TitanError(response.status(), response.text().await?))
-        }
+        Self::success(self.http_client.delete(&url).send().await?).await?;
+        Ok(())
     }
 }
 
+/// `Authorization: Bearer <api_key>`, marked sensitive so it isn't logged.
+pub(super) fn api_key_headers(api_key: &str) -> Result<HeaderMap, Error> {
+    let mut value =
+        HeaderValue::from_str(&format!("Bearer {api_key}")).map_err(|_| Error::InvalidApiKey)?;
+    value.set_sensitive(true);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::AUTHORIZATION, value);
+    Ok(headers)
+}
+
 #[async_trait::async_trait]
 impl TitanApiAsync for AsyncClient {
     async fn get_status(&self) -> Result<Status, Error> {
@@ -72,6 +96,13 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_status_expecting(&self, chain: &str) -> Result<Status, Error> {
+        let text = self
+            .call_text(&format!("/status?expect_chain={}", chain))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_tip(&self) -> Result<BlockTip, Error> {
         let text = self.call_text("/tip").await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -86,13 +117,93 @@ impl TitanApiAsync for AsyncClient {
         self.call_text(&format!("/block/{}/hash", height)).await
     }
 
-    async fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error> {
+    async fn get_block_txids(&self, query: &query::Block) -> Result<BlockTxids, Error> {
         let text = self.call_text(&format!("/block/{}/txids", query)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
-    async fn get_address(&self, address: &str) -> Result<AddressData, Error> {
-        let text = self.call_text(&format!("/address/{}", address)).await?;
+    async fn get_block_runes(&self, query: &query::Block) -> Result<BlockRunes, Error> {
+        let text = self.call_text(&format!("/block/{}/runes", query)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_sat(&self, sat: u64) -> Result<SatResponse, Error> {
+        let text = self.call_text(&format!("/sat/{}", sat)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, Error> {
+        let text = self
+            .call_text(&format!("/sat/{}/inscriptions", sat))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<AddressData, Error> {
+        let mut path = format!("/address/{}", address);
+        if let Some(p) = pagination {
+            path = format!("{}?{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_balance(&self, address: &str) -> Result<AddressBalance, Error> {
+        let text = self
+            .call_text(&format!("/address/{}/balance", address))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_runes(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<AddressRune>, Error> {
+        let mut path = format!("/address/{}/runes", address);
+        if let Some(p) = pagination {
+            path = format!("{}?{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error> {
+        let mut path = format!(
+            "/address/{}/utxos?include_mempool={}",
+            address, include_mempool
+        );
+        if let Some(p) = pagination {
+            path = format!("{}&{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_script_outputs(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error> {
+        let mut path = format!(
+            "/script/{}/outputs?include_mempool={}",
+            script_pubkey.to_hex_string(),
+            include_mempool
+        );
+        if let Some(p) = pagination {
+            path = format!("{}&{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
@@ -101,6 +212,28 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_transaction_with_prevouts(
+        &self,
+        txid: &Txid,
+    ) -> Result<TransactionWithPrevouts, Error> {
+        let text = self
+            .call_text(&format!("/tx/{}?prevouts=true", txid))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_transaction_by_wtxid(&self, wtxid: &Wtxid) -> Result<Transaction, Error> {
+        let text = self.call_text(&format!("/tx/wtxid/{}", wtxid)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_transactions(&self, txids: &[Txid]) -> Result<TransactionsResponse, Error> {
+        let text = self
+            .post_text("/txs", serde_json::to_string(txids)?)
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
         self.call_bytes(&format!("/tx/{}/raw", txid)).await
     }
@@ -109,44 +242,173 @@ impl TitanApiAsync for AsyncClient {
         self.call_text(&format!("/tx/{}/hex", txid)).await
     }
 
-    async fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error> {
+    async fn get_transaction_status(
+        &self,
+        txid: &Txid,
+    ) -> Result<TransactionStatusResponse, Error> {
         let text = self.call_text(&format!("/tx/{}/status", txid)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_transaction_runestone(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<RunestoneResponse>, Error> {
+        let text = self.call_text(&format!("/tx/{}/runestone", txid)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_transaction_data(&self, txid: &Txid) -> Result<TransactionData, Error> {
+        let text = self.call_text(&format!("/tx/{}/data", txid)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
         let text = self.post_text("/tx/broadcast", tx_hex).await?;
         Txid::from_str(&text).map_err(Error::from)
     }
 
-    async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error> {
+    async fn decode_transaction(&self, raw_tx: String) -> Result<DecodedTransaction, Error> {
+        let text = self.post_text("/tx/decode", raw_tx).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn test_accept_transaction(&self, tx_hex: String) -> Result<TestAcceptResponse, Error> {
+        let text = self.post_text("/tx/test-accept", tx_hex).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutResponse, Error> {
         let text = self.call_text(&format!("/output/{}", outpoint)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_output_inscriptions(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<InscriptionId>, Error> {
+        let text = self
+            .call_text(&format!("/output/{}/inscriptions", outpoint))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<OutputsResponse, Error> {
+        let text = self
+            .post_text("/outputs", serde_json::to_string(outpoints)?)
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
     ) -> Result<(HeaderMap, Vec<u8>), Error> {
         let url = format!("{}/inscription/{}", self.base_url, inscription_id);
-        let resp = self.http_client.get(&url).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(Error::TitanError(status, body));
-        }
+        let resp = Self::success(self.http_client.get(&url).send().await?).await?;
         let headers = resp.headers().clone();
         let bytes = resp.bytes().await?.to_vec();
         Ok((headers, bytes))
     }
 
+    async fn get_inscription_metadata(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<InscriptionMetadata, Error> {
+        let text = self
+            .call_text(&format!("/inscription/{}/metadata", inscription_id))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_inscription_parents(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<Vec<InscriptionId>, Error> {
+        let text = self
+            .call_text(&format!("/inscription/{}/parents", inscription_id))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_inscription_children(
+        &self,
+        inscription_id: &InscriptionId,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionId>, Error> {
+        let mut path = format!("/inscription/{}/children", inscription_id);
+        if let Some(p) = pagination {
+            path = format!("{}?{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionMetadata>, Error> {
+        let url = format!("{}/inscriptions", self.base_url);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send().await?;
+        Self::parse_json(resp).await
+    }
+
     async fn get_runes(
         &self,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error> {
         let mut path = "/runes".to_string();
         if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
+            path = format!("{}?{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_runes_sorted(
+        &self,
+        query: &RunesQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error> {
+        let url = format!("{}/runes", self.base_url);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send().await?;
+        Self::parse_json(resp).await
+    }
+
+    async fn get_runes_after(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<RuneResponse>, Error> {
+        let url = format!("{}/runes", self.base_url);
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(&[("cursor", cursor.unwrap_or_default())]);
+        if let Some(limit) = limit {
+            req = req.query(&[("limit", limit)]);
+        }
+        let resp = req.send().await?;
+        Self::parse_json(resp).await
+    }
+
+    async fn search_runes(
+        &self,
+        query: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<RuneResponse>, Error> {
+        let mut path = format!("/runes/search?q={}", query);
+        if let Some(limit) = limit {
+            path = format!("{}&limit={}", path, limit);
         }
         let text = self.call_text(&path).await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -157,6 +419,39 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_rune_mints(&self, rune: &query::Rune) -> Result<RuneMintsResponse, Error> {
+        let text = self.call_text(&format!("/rune/{}/mints", rune)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_rune_etching(&self, rune: &query::Rune) -> Result<RuneEtchingResponse, Error> {
+        let text = self.call_text(&format!("/rune/{}/etching", rune)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_rune_chart(
+        &self,
+        rune: &query::Rune,
+        query: &RuneChartQuery,
+    ) -> Result<RuneChartResponse, Error> {
+        let url = format!("{}/rune/{}/chart", self.base_url, rune);
+        let resp = self.http_client.get(&url).query(query).send().await?;
+        Self::parse_json(resp).await
+    }
+
+    async fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error> {
+        let mut path = format!("/rune/{}/holders", rune);
+        if let Some(p) = pagination {
+            path = format!("{}?{}", path, p.to_query());
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_rune_transactions(
         &self,
         rune: &query::Rune,
@@ -164,17 +459,67 @@ impl TitanApiAsync for AsyncClient {
     ) -> Result<PaginationResponse<Txid>, Error> {
         let mut path = format!("/rune/{}/transactions", rune);
         if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
+            path = format!("{}?{}", path, p.to_query());
         }
         let text = self.call_text(&path).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_rune_transactions_filtered(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<Txid>, Error> {
+        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send().await?;
+        Self::parse_json(resp).await
+    }
+
+    async fn get_rune_transactions_after(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<Txid>, Error> {
+        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(query)
+            .query(&[("cursor", cursor.unwrap_or_default())]);
+        if let Some(limit) = limit {
+            req = req.query(&[("limit", limit)]);
+        }
+        let resp = req.send().await?;
+        Self::parse_json(resp).await
+    }
+
     async fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error> {
         let text = self.call_text("/mempool/txids").await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_mempool_txids_since(
+        &self,
+        since_seq: u64,
+    ) -> Result<(Vec<Txid>, Option<u64>), Error> {
+        let url = format!("{}/mempool/txids", self.base_url);
+        let resp = self
+            .http_client
+            .get(&url)
+            .query(&[("since_seq", since_seq)])
+            .send()
+            .await?;
+        let next_seq = super::mempool_seq(resp.headers());
+        Ok((Self::parse_json(resp).await?, next_seq))
+    }
+
     async fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error> {
         let text = self.call_text(&format!("/mempool/entry/{}", txid)).await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -195,6 +540,11 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_fees(&self) -> Result<FeeEstimates, Error> {
+        let text = self.call_text("/fees").await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
         let text = self.call_text(&format!("/subscription/{}", id)).await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -212,7 +562,26 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn add_subscriptions(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<Vec<SubscriptionBatchResult>, Error> {
+        let text = self
+            .post_text(
+                "/subscriptions/batch",
+                serde_json::to_string(subscriptions)?,
+            )
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn delete_subscription(&self, id: &str) -> Result<(), Error> {
         self.delete(&format!("/subscription/{}", id)).await
     }
+
+    async fn get_events(&self, query: &EventsQuery) -> Result<EventsResponse, Error> {
+        let url = format!("{}/events", self.base_url);
+        let resp = self.http_client.get(&url).query(query).send().await?;
+        Self::parse_json(resp).await
+    }
 }
@@ -1,11 +1,15 @@
-use bitcoin::{OutPoint, Txid};
-use reqwest::{blocking::Client as BlockingReqwestClient, header::HeaderMap};
+use bitcoin::{OutPoint, ScriptBuf, Txid, Wtxid};
+use reqwest::{
+    blocking::{Client as BlockingReqwestClient, Response},
+    header::HeaderMap,
+};
+use serde::de::DeserializeOwned;
 use std::{collections::HashMap, str::FromStr};
 use titan_types::*;
 
 use crate::Error;
 
-use super::TitanApiSync;
+use super::{client_async_impl::api_key_headers, TitanApiSync};
 
 #[derive(Clone)]
 pub struct SyncClient {
@@ -24,6 +28,26 @@ impl SyncClient {
         }
     }
 
+    /// Creates a new `SyncClient` that sends `api_key` as a bearer token, for
+    /// servers started with `--api-keys`.
+    pub fn with_api_key(base_url: &str, api_key: &str) -> Result<Self, Error> {
+        Ok(Self {
+            http_client: BlockingReqwestClient::builder()
+                .default_headers(api_key_headers(api_key)?)
+                .build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Decodes a JSON body, or returns `Error::TitanError` for non-2xx responses.
+    fn parse_json<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+        if response.status().is_success() {
+            Ok(response.json()?)
+        } else {
+            Err(Error::TitanError(response.status(), response.text()?))
+        }
+    }
+
     fn call_text(&self, path: &str) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send()?;
@@ -73,6 +97,11 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_status_expecting(&self, chain: &str) -> Result<Status, Error> {
+        let text = self.call_text(&format!("/status?expect_chain={}", chain))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_tip(&self) -> Result<BlockTip, Error> {
         let text = self.call_text("/tip")?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -87,21 +116,120 @@ impl TitanApiSync for SyncClient {
         self.call_text(&format!("/block/{}/hash", height))
     }
 
-    fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error> {
+    fn get_block_txids(&self, query: &query::Block) -> Result<BlockTxids, Error> {
         let text = self.call_text(&format!("/block/{}/txids", query))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
-    fn get_address(&self, address: &str) -> Result<AddressData, Error> {
-        let text = self.call_text(&format!("/address/{}", address))?;
+    fn get_block_runes(&self, query: &query::Block) -> Result<BlockRunes, Error> {
+        let text = self.call_text(&format!("/block/{}/runes", query))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_sat(&self, sat: u64) -> Result<SatResponse, Error> {
+        let text = self.call_text(&format!("/sat/{}", sat))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, Error> {
+        let text = self.call_text(&format!("/sat/{}/inscriptions", sat))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_address(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<AddressData, Error> {
+        let url = format!("{}/address/{}", self.base_url, address);
+        let mut req = self.http_client.get(&url);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_address_balance(&self, address: &str) -> Result<AddressBalance, Error> {
+        let url = format!("{}/address/{}/balance", self.base_url, address);
+        let resp = self.http_client.get(&url).send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_address_runes(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<AddressRune>, Error> {
+        let url = format!("{}/address/{}/runes", self.base_url, address);
+        let mut req = self.http_client.get(&url);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error> {
+        let url = format!("{}/address/{}/utxos", self.base_url, address);
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(&[("include_mempool", include_mempool)]);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_script_outputs(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error> {
+        let url = format!(
+            "{}/script/{}/outputs",
+            self.base_url,
+            script_pubkey.to_hex_string()
+        );
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(&[("include_mempool", include_mempool)]);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
         let text = self.call_text(&format!("/tx/{}", txid))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_transaction_with_prevouts(&self, txid: &Txid) -> Result<TransactionWithPrevouts, Error> {
+        let text = self.call_text(&format!("/tx/{}?prevouts=true", txid))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_transaction_by_wtxid(&self, wtxid: &Wtxid) -> Result<Transaction, Error> {
+        let text = self.call_text(&format!("/tx/wtxid/{}", wtxid))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_transactions(&self, txids: &[Txid]) -> Result<TransactionsResponse, Error> {
+        let text = self.post_text("/txs", serde_json::to_string(txids)?)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
         self.call_bytes(&format!("/tx/{}/raw", txid))
     }
@@ -110,21 +238,51 @@ impl TitanApiSync for SyncClient {
         self.call_text(&format!("/tx/{}/hex", txid))
     }
 
-    fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error> {
+    fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatusResponse, Error> {
         let text = self.call_text(&format!("/tx/{}/status", txid))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_transaction_runestone(&self, txid: &Txid) -> Result<Option<RunestoneResponse>, Error> {
+        let text = self.call_text(&format!("/tx/{}/runestone", txid))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_transaction_data(&self, txid: &Txid) -> Result<TransactionData, Error> {
+        let text = self.call_text(&format!("/tx/{}/data", txid))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
         let text = self.post_text("/tx/broadcast", tx_hex)?;
         Txid::from_str(&text).map_err(Error::from)
     }
 
-    fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error> {
+    fn decode_transaction(&self, raw_tx: String) -> Result<DecodedTransaction, Error> {
+        let text = self.post_text("/tx/decode", raw_tx)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn test_accept_transaction(&self, tx_hex: String) -> Result<TestAcceptResponse, Error> {
+        let text = self.post_text("/tx/test-accept", tx_hex)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutResponse, Error> {
         let text = self.call_text(&format!("/output/{}", outpoint))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_output_inscriptions(&self, outpoint: &OutPoint) -> Result<Vec<InscriptionId>, Error> {
+        let text = self.call_text(&format!("/output/{}/inscriptions", outpoint))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<OutputsResponse, Error> {
+        let text = self.post_text("/outputs", serde_json::to_string(outpoints)?)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
@@ -141,6 +299,50 @@ impl TitanApiSync for SyncClient {
         Ok((headers, bytes))
     }
 
+    fn get_inscription_metadata(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<InscriptionMetadata, Error> {
+        let text = self.call_text(&format!("/inscription/{}/metadata", inscription_id))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_inscription_parents(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<Vec<InscriptionId>, Error> {
+        let text = self.call_text(&format!("/inscription/{}/parents", inscription_id))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_inscription_children(
+        &self,
+        inscription_id: &InscriptionId,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionId>, Error> {
+        let url = format!("{}/inscription/{}/children", self.base_url, inscription_id);
+        let mut req = self.http_client.get(&url);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionMetadata>, Error> {
+        let url = format!("{}/inscriptions", self.base_url);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
     fn get_runes(
         &self,
         pagination: Option<Pagination>,
@@ -148,16 +350,93 @@ impl TitanApiSync for SyncClient {
         let url = format!("{}/runes", self.base_url);
         let mut req = self.http_client.get(&url);
         if let Some(ref p) = pagination {
-            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
+            req = req.query(p);
         }
         let resp = req.send()?;
-        Ok(resp.json()?)
+        Self::parse_json(resp)
+    }
+
+    fn get_runes_sorted(
+        &self,
+        query: &RunesQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error> {
+        let url = format!("{}/runes", self.base_url);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_runes_after(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<RuneResponse>, Error> {
+        let url = format!("{}/runes", self.base_url);
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(&[("cursor", cursor.unwrap_or_default())]);
+        if let Some(limit) = limit {
+            req = req.query(&[("limit", limit)]);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn search_runes(&self, query: &str, limit: Option<u64>) -> Result<Vec<RuneResponse>, Error> {
+        let url = format!("{}/runes/search", self.base_url);
+        let mut req = self.http_client.get(&url).query(&[("q", query)]);
+        if let Some(limit) = limit {
+            req = req.query(&[("limit", limit)]);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
     }
 
     fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error> {
         let url = format!("{}/rune/{}", self.base_url, rune);
         let resp = self.http_client.get(&url).send()?;
-        Ok(resp.json()?)
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_mints(&self, rune: &query::Rune) -> Result<RuneMintsResponse, Error> {
+        let url = format!("{}/rune/{}/mints", self.base_url, rune);
+        let resp = self.http_client.get(&url).send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_etching(&self, rune: &query::Rune) -> Result<RuneEtchingResponse, Error> {
+        let url = format!("{}/rune/{}/etching", self.base_url, rune);
+        let resp = self.http_client.get(&url).send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_chart(
+        &self,
+        rune: &query::Rune,
+        query: &RuneChartQuery,
+    ) -> Result<RuneChartResponse, Error> {
+        let url = format!("{}/rune/{}/chart", self.base_url, rune);
+        let resp = self.http_client.get(&url).query(query).send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error> {
+        let url = format!("{}/rune/{}/holders", self.base_url, rune);
+        let mut req = self.http_client.get(&url);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
     }
 
     fn get_rune_transactions(
@@ -168,10 +447,45 @@ impl TitanApiSync for SyncClient {
         let url = format!("{}/rune/{}/transactions", self.base_url, rune);
         let mut req = self.http_client.get(&url);
         if let Some(ref p) = pagination {
-            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
+            req = req.query(p);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_transactions_filtered(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<Txid>, Error> {
+        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
+        let mut req = self.http_client.get(&url).query(query);
+        if let Some(ref p) = pagination {
+            req = req.query(p);
         }
         let resp = req.send()?;
-        Ok(resp.json()?)
+        Self::parse_json(resp)
+    }
+
+    fn get_rune_transactions_after(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<Txid>, Error> {
+        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
+        let mut req = self
+            .http_client
+            .get(&url)
+            .query(query)
+            .query(&[("cursor", cursor.unwrap_or_default())]);
+        if let Some(limit) = limit {
+            req = req.query(&[("limit", limit)]);
+        }
+        let resp = req.send()?;
+        Self::parse_json(resp)
     }
 
     fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error> {
@@ -179,6 +493,17 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_mempool_txids_since(&self, since_seq: u64) -> Result<(Vec<Txid>, Option<u64>), Error> {
+        let url = format!("{}/mempool/txids", self.base_url);
+        let resp = self
+            .http_client
+            .get(&url)
+            .query(&[("since_seq", since_seq)])
+            .send()?;
+        let next_seq = super::mempool_seq(resp.headers());
+        Ok((Self::parse_json(resp)?, next_seq))
+    }
+
     fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error> {
         let text = self.call_text(&format!("/mempool/entry/{}", txid))?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -201,6 +526,11 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_fees(&self) -> Result<FeeEstimates, Error> {
+        let text = self.call_text("/fees")?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
         let text = self.call_text(&format!("/subscription/{}", id))?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -216,7 +546,24 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn add_subscriptions(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<Vec<SubscriptionBatchResult>, Error> {
+        let text = self.post_text(
+            "/subscriptions/batch",
+            serde_json::to_string(subscriptions)?,
+        )?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn delete_subscription(&self, id: &str) -> Result<(), Error> {
         self.delete(&format!("/subscription/{}", id))
     }
+
+    fn get_events(&self, query: &EventsQuery) -> Result<EventsResponse, Error> {
+        let url = format!("{}/events", self.base_url);
+        let resp = self.http_client.get(&url).query(query).send()?;
+        Self::parse_json(resp)
+    }
 }
@@ -2,12 +2,17 @@ use std::collections::HashMap;
 
 use crate::Error;
 use async_trait::async_trait;
-use bitcoin::{OutPoint, Txid};
+use bitcoin::{OutPoint, ScriptBuf, Txid, Wtxid};
 use reqwest::header::HeaderMap;
 use titan_types::{
-    query, AddressData, Block, BlockTip, InscriptionId, MempoolEntry, Pagination,
-    PaginationResponse, RuneResponse, Status, Subscription, Transaction, TransactionStatus,
-    TxOutEntry,
+    query, AddressBalance, AddressData, AddressRune, AddressUtxo, Block, BlockRunes, BlockTip,
+    BlockTxids, CursorPage, DecodedTransaction, EventsQuery, EventsResponse, FeeEstimates,
+    InscriptionId, InscriptionMetadata, InscriptionsQuery, MempoolEntry, OutputsResponse,
+    Pagination, PaginationResponse, RuneChartQuery, RuneChartResponse, RuneEtchingResponse,
+    RuneHolder, RuneMintsResponse, RuneResponse, RuneTransactionsQuery, RunesQuery,
+    RunestoneResponse, SatResponse, Status, Subscription, SubscriptionBatchResult,
+    TestAcceptResponse, Transaction, TransactionData, TransactionStatusResponse,
+    TransactionWithPrevouts, TransactionsResponse, TxOutResponse,
 };
 
 /// Trait for all **async** methods.
@@ -16,6 +21,10 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns the node's status (e.g., network info, block height).
     async fn get_status(&self) -> Result<Status, Error>;
 
+    /// Like `get_status`, but fails with a 409 `wrong_network` error if the
+    /// server doesn't index `chain` (e.g. "mainnet", "testnet4").
+    async fn get_status_expecting(&self, chain: &str) -> Result<Status, Error>;
+
     /// Returns the current best block tip (height + hash).
     async fn get_tip(&self) -> Result<BlockTip, Error>;
 
@@ -25,15 +34,67 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Given a block height, returns the block hash.
     async fn get_block_hash_by_height(&self, height: u64) -> Result<String, Error>;
 
-    /// Returns a list of transaction IDs in a particular block.
-    async fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error>;
+    /// Returns the transaction IDs in a particular block, with its height and hash.
+    async fn get_block_txids(&self, query: &query::Block) -> Result<BlockTxids, Error>;
+
+    /// Counts the rune etchings, mints, transfers and burns in a block.
+    async fn get_block_runes(&self, query: &query::Block) -> Result<BlockRunes, Error>;
+
+    /// Returns the rarity, name and other properties of a sat by its ordinal number.
+    async fn get_sat(&self, sat: u64) -> Result<SatResponse, Error>;
+
+    /// Returns the inscriptions whose genesis sat is `sat`. Needs `--index-sats`.
+    async fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Fetches address data (balance, transactions, etc.), paginating the outputs.
+    async fn get_address(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<AddressData, Error>;
+
+    /// Returns the confirmed and unconfirmed value and the rune balances of an address, without its outputs.
+    async fn get_address_balance(&self, address: &str) -> Result<AddressBalance, Error>;
+
+    /// Lists the runes an address holds with their name, symbol and divisibility, ordered by rune id.
+    async fn get_address_runes(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<AddressRune>, Error>;
 
-    /// Fetches address data (balance, transactions, etc.).
-    async fn get_address(&self, address: &str) -> Result<AddressData, Error>;
+    /// Returns the unspent outputs of an address. Outputs spent by a mempool transaction are flagged as `pending_spent`, or left out when `include_mempool` is `false`.
+    async fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error>;
+
+    /// Like `get_address_utxos`, for the outputs of a raw `script_pubkey`, e.g.
+    /// bare multisig or other scripts without an address.
+    async fn get_script_outputs(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error>;
 
     /// Returns a higher-level transaction object (including Runes info) by `txid`.
     async fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns the transaction with the output spent by each input and the fee.
+    async fn get_transaction_with_prevouts(
+        &self,
+        txid: &Txid,
+    ) -> Result<TransactionWithPrevouts, Error>;
+
+    /// Returns the same transaction object as `get_transaction`, looked up by `wtxid`.
+    async fn get_transaction_by_wtxid(&self, wtxid: &Wtxid) -> Result<Transaction, Error>;
+
+    /// Returns multiple transactions by their `txid`s in a single request.
+    async fn get_transactions(&self, txids: &[Txid]) -> Result<TransactionsResponse, Error>;
+
     /// Returns raw transaction bytes (binary).
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -41,13 +102,38 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     async fn get_transaction_hex(&self, txid: &Txid) -> Result<String, Error>;
 
     /// Returns the status of a transaction by `txid`.
-    async fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
+    async fn get_transaction_status(&self, txid: &Txid)
+        -> Result<TransactionStatusResponse, Error>;
+
+    /// Returns the runestone or cenotaph in a transaction, or `None` if it has none.
+    async fn get_transaction_runestone(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<RunestoneResponse>, Error>;
+
+    /// Returns the raw inscription envelopes and `OP_RETURN` data of a transaction, for custom parsers.
+    async fn get_transaction_data(&self, txid: &Txid) -> Result<TransactionData, Error>;
 
     /// Broadcasts a transaction (raw hex) to the network and returns the resulting `Txid`.
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
+    /// Decodes a raw transaction (hex or base64) and previews the runes it would move, without broadcasting it.
+    async fn decode_transaction(&self, raw_tx: String) -> Result<DecodedTransaction, Error>;
+
+    /// Asks the node whether it would accept a raw-hex transaction into its mempool, without broadcasting it.
+    async fn test_accept_transaction(&self, tx_hex: String) -> Result<TestAcceptResponse, Error>;
+
     /// Fetches a specific output by outpoint (`<txid>:<vout>`).
-    async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error>;
+    async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutResponse, Error>;
+
+    /// Returns the inscriptions an output holds, by offset into it.
+    async fn get_output_inscriptions(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Fetches multiple outputs in a single request.
+    async fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<OutputsResponse, Error>;
 
     /// Returns `(HTTP Headers, Bytes)` for an inscription by its `inscription_id`.
     async fn get_inscription(
@@ -55,15 +141,85 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
         inscription_id: &InscriptionId,
     ) -> Result<(HeaderMap, Vec<u8>), Error>;
 
+    /// Returns metadata for an inscription by its `inscription_id`, without its content.
+    async fn get_inscription_metadata(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<InscriptionMetadata, Error>;
+
+    /// Returns the parents named by an inscription.
+    async fn get_inscription_parents(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Returns the inscriptions naming `inscription_id` as their parent.
+    async fn get_inscription_children(
+        &self,
+        inscription_id: &InscriptionId,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionId>, Error>;
+
+    /// Lists inscriptions, newest first, filtered by content type prefix and genesis height.
+    async fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionMetadata>, Error>;
+
     /// Lists existing runes, supporting pagination.
     async fn get_runes(
         &self,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
+    /// Like `get_runes`, but sorted by `query.sort` in `query.order`, e.g. by
+    /// supply for a leaderboard.
+    async fn get_runes_sorted(
+        &self,
+        query: &RunesQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error>;
+
+    /// Like `get_runes`, but paginated by key: pass `None` for the first page
+    /// and then the previous page's `next_cursor`.
+    async fn get_runes_after(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<RuneResponse>, Error>;
+
+    /// Searches runes by name (case-insensitive, spacers ignored). Prefix matches come first.
+    async fn search_runes(
+        &self,
+        query: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<RuneResponse>, Error>;
+
     /// Fetches data about a specific rune.
     async fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Returns the minting progress of a `rune` and whether it can be minted in the next block.
+    async fn get_rune_mints(&self, rune: &query::Rune) -> Result<RuneMintsResponse, Error>;
+
+    /// Returns what a `rune` was etched with: the etching transaction, premine, terms and spacers.
+    async fn get_rune_etching(&self, rune: &query::Rune) -> Result<RuneEtchingResponse, Error>;
+
+    /// Returns the mints, transfers and receiving addresses of a `rune`
+    /// bucketed by block time.
+    async fn get_rune_chart(
+        &self,
+        rune: &query::Rune,
+        query: &RuneChartQuery,
+    ) -> Result<RuneChartResponse, Error>;
+
+    /// Returns holders of a `rune` grouped by address, sorted by amount descending.
+    async fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error>;
+
     /// Returns a paginated list of `Txid` for all transactions involving a given `rune`.
     async fn get_rune_transactions(
         &self,
@@ -71,9 +227,36 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error>;
 
+    /// Like `get_rune_transactions`, but only the transactions of the kinds in
+    /// `query`, in the given order.
+    async fn get_rune_transactions_filtered(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<Txid>, Error>;
+
+    /// Like `get_rune_transactions_filtered`, but paginated by key: pass
+    /// `None` for the first page and then the previous page's `next_cursor`.
+    async fn get_rune_transactions_after(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<Txid>, Error>;
+
     /// Returns a list of all txids currently in the mempool.
     async fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error>;
 
+    /// Returns the mempool txids indexed since the cursor `since_seq`, oldest
+    /// first, and the cursor to pass next time. Start with 0. The cursor is
+    /// `None` if the server doesn't send one.
+    async fn get_mempool_txids_since(
+        &self,
+        since_seq: u64,
+    ) -> Result<(Vec<Txid>, Option<u64>), Error>;
+
     /// Returns a single mempool entry by `txid`.
     async fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error>;
 
@@ -86,6 +269,10 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns all mempool entries.
     async fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns the node's fee rate estimates and a histogram of the mempool's
+    /// fee rates. The server caches them for a few seconds.
+    async fn get_fees(&self) -> Result<FeeEstimates, Error>;
+
     /// Fetches a single subscription by `id`.
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
@@ -95,8 +282,18 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Adds (creates) a subscription.
     async fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error>;
 
+    /// Adds several subscriptions in one request. One failing doesn't affect
+    /// the others, so check each result.
+    async fn add_subscriptions(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<Vec<SubscriptionBatchResult>, Error>;
+
     /// Deletes a subscription by `id`.
     async fn delete_subscription(&self, id: &str) -> Result<(), Error>;
+
+    /// Long-polls for events since `query.from`, waiting up to `query.wait` seconds.
+    async fn get_events(&self, query: &EventsQuery) -> Result<EventsResponse, Error>;
 }
 
 /// Trait for all **blocking** (synchronous) methods.
@@ -104,6 +301,9 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns the node's status in a **blocking** manner.
     fn get_status(&self) -> Result<Status, Error>;
 
+    /// Like `get_status`, but fails if the server doesn't index `chain`.
+    fn get_status_expecting(&self, chain: &str) -> Result<Status, Error>;
+
     /// Returns the block tip in a **blocking** manner.
     fn get_tip(&self) -> Result<BlockTip, Error>;
 
@@ -114,14 +314,62 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     fn get_block_hash_by_height(&self, height: u64) -> Result<String, Error>;
 
     /// Returns txids for a block in a **blocking** manner.
-    fn get_block_txids(&self, query: &query::Block) -> Result<Vec<String>, Error>;
+    fn get_block_txids(&self, query: &query::Block) -> Result<BlockTxids, Error>;
+
+    /// Counts the rune etchings, mints, transfers and burns in a block.
+    fn get_block_runes(&self, query: &query::Block) -> Result<BlockRunes, Error>;
+
+    /// Returns the properties of a sat in a **blocking** manner.
+    fn get_sat(&self, sat: u64) -> Result<SatResponse, Error>;
+
+    /// Returns the inscriptions on a sat in a **blocking** manner.
+    fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Returns address data in a **blocking** manner, paginating the outputs.
+    fn get_address(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<AddressData, Error>;
 
-    /// Returns address data in a **blocking** manner.
-    fn get_address(&self, address: &str) -> Result<AddressData, Error>;
+    /// Returns the balance of an address in a **blocking** manner.
+    fn get_address_balance(&self, address: &str) -> Result<AddressBalance, Error>;
+
+    /// Lists the runes an address holds in a **blocking** manner.
+    fn get_address_runes(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<AddressRune>, Error>;
+
+    /// Returns the unspent outputs of an address in a **blocking** manner.
+    fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error>;
+
+    /// Returns the unspent outputs of a raw script in a **blocking** manner.
+    fn get_script_outputs(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Option<Pagination>,
+        include_mempool: bool,
+    ) -> Result<PaginationResponse<AddressUtxo>, Error>;
 
     /// Returns a transaction (with runic info) by `txid` in a **blocking** manner.
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns a transaction with its prevouts and fee in a **blocking** manner.
+    fn get_transaction_with_prevouts(&self, txid: &Txid) -> Result<TransactionWithPrevouts, Error>;
+
+    /// Returns a transaction by `wtxid` in a **blocking** manner.
+    fn get_transaction_by_wtxid(&self, wtxid: &Wtxid) -> Result<Transaction, Error>;
+
+    /// Returns multiple transactions by their `txid`s in a **blocking** manner.
+    fn get_transactions(&self, txids: &[Txid]) -> Result<TransactionsResponse, Error>;
+
     /// Returns raw tx bytes in a **blocking** manner.
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -129,13 +377,31 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     fn get_transaction_hex(&self, txid: &Txid) -> Result<String, Error>;
 
     /// Returns the status of a transaction by `txid` in a **blocking** manner.
-    fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
+    fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatusResponse, Error>;
+
+    /// Returns the runestone of a transaction, if any, in a **blocking** manner.
+    fn get_transaction_runestone(&self, txid: &Txid) -> Result<Option<RunestoneResponse>, Error>;
+
+    /// Returns the raw embedded data of a transaction in a **blocking** manner.
+    fn get_transaction_data(&self, txid: &Txid) -> Result<TransactionData, Error>;
 
     /// Broadcasts a raw-hex transaction in a **blocking** manner.
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
+    /// Decodes a raw transaction and previews its rune movements in a **blocking** manner.
+    fn decode_transaction(&self, raw_tx: String) -> Result<DecodedTransaction, Error>;
+
+    /// Checks whether the node would accept a raw-hex transaction in a **blocking** manner.
+    fn test_accept_transaction(&self, tx_hex: String) -> Result<TestAcceptResponse, Error>;
+
     /// Fetches a specific output (outpoint) in a **blocking** manner.
-    fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutEntry, Error>;
+    fn get_output(&self, outpoint: &OutPoint) -> Result<TxOutResponse, Error>;
+
+    /// Returns the inscriptions an output holds in a **blocking** manner.
+    fn get_output_inscriptions(&self, outpoint: &OutPoint) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Fetches multiple outputs in a **blocking** manner.
+    fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<OutputsResponse, Error>;
 
     /// Fetches an inscription (headers + bytes) by `inscription_id`, blocking.
     fn get_inscription(
@@ -143,15 +409,78 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
         inscription_id: &InscriptionId,
     ) -> Result<(HeaderMap, Vec<u8>), Error>;
 
+    /// Fetches an inscription's metadata by `inscription_id`, blocking.
+    fn get_inscription_metadata(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<InscriptionMetadata, Error>;
+
+    /// Returns an inscription's parents in a **blocking** manner.
+    fn get_inscription_parents(
+        &self,
+        inscription_id: &InscriptionId,
+    ) -> Result<Vec<InscriptionId>, Error>;
+
+    /// Returns an inscription's children in a **blocking** manner.
+    fn get_inscription_children(
+        &self,
+        inscription_id: &InscriptionId,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionId>, Error>;
+
+    /// Lists inscriptions in a **blocking** manner.
+    fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<InscriptionMetadata>, Error>;
+
     /// Returns paginated runes in a **blocking** manner.
     fn get_runes(
         &self,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
+    /// Returns a sorted page of runes in a **blocking** manner.
+    fn get_runes_sorted(
+        &self,
+        query: &RunesQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneResponse>, Error>;
+
+    /// Returns a page of runes after `cursor` in a **blocking** manner.
+    fn get_runes_after(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<RuneResponse>, Error>;
+
+    /// Searches runes by name in a **blocking** manner.
+    fn search_runes(&self, query: &str, limit: Option<u64>) -> Result<Vec<RuneResponse>, Error>;
+
     /// Fetches data for a specific rune in a **blocking** manner.
     fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Returns the minting progress of a rune in a **blocking** manner.
+    fn get_rune_mints(&self, rune: &query::Rune) -> Result<RuneMintsResponse, Error>;
+
+    /// Returns what a rune was etched with in a **blocking** manner.
+    fn get_rune_etching(&self, rune: &query::Rune) -> Result<RuneEtchingResponse, Error>;
+
+    /// Returns the time-bucketed activity of a rune in a **blocking** manner.
+    fn get_rune_chart(
+        &self,
+        rune: &query::Rune,
+        query: &RuneChartQuery,
+    ) -> Result<RuneChartResponse, Error>;
+
+    /// Returns holders of a rune in a **blocking** manner.
+    fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error>;
+
     /// Returns transactions for a given rune in a **blocking** manner.
     fn get_rune_transactions(
         &self,
@@ -159,9 +488,31 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error>;
 
+    /// Returns filtered transactions for a given rune in a **blocking** manner.
+    fn get_rune_transactions_filtered(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<Txid>, Error>;
+
+    /// Returns a page of filtered transactions for a given rune after
+    /// `cursor` in a **blocking** manner.
+    fn get_rune_transactions_after(
+        &self,
+        rune: &query::Rune,
+        query: &RuneTransactionsQuery,
+        cursor: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<CursorPage<Txid>, Error>;
+
     /// Returns mempool txids in a **blocking** manner.
     fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error>;
 
+    /// Returns the mempool txids indexed since `since_seq` in a **blocking**
+    /// manner, with the cursor to pass next time.
+    fn get_mempool_txids_since(&self, since_seq: u64) -> Result<(Vec<Txid>, Option<u64>), Error>;
+
     /// Returns a single mempool entry by `txid`.
     fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, Error>;
 
@@ -174,6 +525,9 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns all mempool entries.
     fn get_all_mempool_entries(&self) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns fee rate estimates, blocking.
+    fn get_fees(&self) -> Result<FeeEstimates, Error>;
+
     /// Fetches a single subscription by `id`, blocking.
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
@@ -183,6 +537,16 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Adds a new subscription, blocking.
     fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error>;
 
+    /// Adds several subscriptions in one request, blocking.
+    fn add_subscriptions(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<Vec<SubscriptionBatchResult>, Error>;
+
     /// Deletes a subscription by `id`, blocking.
     fn delete_subscription(&self, id: &str) -> Result<(), Error>;
+
+    /// Long-polls for events, blocking. The underlying HTTP client times out
+    /// after 30 seconds, so keep `query.wait` below that.
+    fn get_events(&self, query: &EventsQuery) -> Result<EventsResponse, Error>;
 }
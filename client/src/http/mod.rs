@@ -5,3 +5,8 @@ mod client_trait;
 pub use client_async_impl::*;
 pub use client_sync_impl::*;
 pub use client_trait::*;
+
+/// Cursor the server sends with `/mempool/txids`, for the next `since_seq`.
+fn mempool_seq(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers.get("x-mempool-seq")?.to_str().ok()?.parse().ok()
+}
@@ -13,10 +13,13 @@ pub use tcp::{ReconnectionConfig, ReconnectionManager};
 pub use titan_types::*;
 
 #[cfg(feature = "tcp_client")]
-pub use tcp::{TitanTcpClient, TitanTcpClientConfig, TitanTcpClientError};
+pub use tcp::{subscribe, TitanTcpClient, TitanTcpClientConfig, TitanTcpClientError};
 
 #[cfg(feature = "tcp_client_blocking")]
-pub use tcp::{TitanTcpClientBlocking, TitanTcpClientBlockingConfig, TitanTcpClientBlockingError};
+pub use tcp::{
+    BoundedReceiver, OverflowPolicy, TitanTcpClientBlocking, TitanTcpClientBlockingConfig,
+    TitanTcpClientBlockingError,
+};
 
 #[cfg(test)]
 mod tests {
@@ -46,13 +49,11 @@ mod tests {
         use tokio::time::timeout;
 
         let tcp_addr = "127.0.0.1:8080";
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![
-                EventType::TransactionsAdded,
-                EventType::TransactionsReplaced,
-                EventType::NewBlock,
-            ],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![
+            EventType::TransactionsAdded,
+            EventType::TransactionsReplaced,
+            EventType::NewBlock,
+        ]);
 
         // Connect to the TCP server and subscribe.
         let client = TitanTcpClient::new();
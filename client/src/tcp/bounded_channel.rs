@@ -0,0 +1,244 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{RecvTimeoutError, TryRecvError},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How often a blocked sender checks whether the client was shut down.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the reader thread does when a bounded subscription's channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop reading from the connection until the consumer catches up, so the
+    /// backpressure reaches the server through TCP.
+    Block,
+    /// Discard the oldest queued event to make room. `BoundedReceiver::dropped`
+    /// counts the discarded events.
+    DropOldest,
+}
+
+struct State<T> {
+    items: VecDeque<T>,
+    dropped: u64,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+/// Receiving end of `TcpClient::subscribe_bounded`. Holds at most `capacity`
+/// events.
+pub struct BoundedReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub(super) struct BoundedSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// A channel of at least one slot.
+pub(super) fn bounded<T>(
+    capacity: usize,
+    policy: OverflowPolicy,
+) -> (BoundedSender<T>, BoundedReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::new(),
+            dropped: 0,
+            sender_alive: true,
+            receiver_alive: true,
+        }),
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+        capacity: capacity.max(1),
+        policy,
+    });
+
+    (
+        BoundedSender {
+            shared: shared.clone(),
+        },
+        BoundedReceiver { shared },
+    )
+}
+
+impl<T> BoundedSender<T> {
+    /// Queues `item` according to the policy. Returns false if the receiver
+    /// was dropped, or if `shutdown` was set while blocked.
+    pub(super) fn send(&self, item: T, shutdown: &AtomicBool) -> bool {
+        let mut state = self.shared.state.lock().unwrap();
+
+        while state.items.len() >= self.shared.capacity && state.receiver_alive {
+            match self.shared.policy {
+                OverflowPolicy::DropOldest => {
+                    state.items.pop_front();
+                    state.dropped += 1;
+                }
+                OverflowPolicy::Block => {
+                    if shutdown.load(Ordering::SeqCst) {
+                        return false;
+                    }
+                    state = self
+                        .shared
+                        .not_full
+                        .wait_timeout(state, SHUTDOWN_CHECK_INTERVAL)
+                        .unwrap()
+                        .0;
+                }
+            }
+        }
+
+        if !state.receiver_alive {
+            return false;
+        }
+
+        state.items.push_back(item);
+        self.shared.not_empty.notify_one();
+        true
+    }
+}
+
+impl<T> Drop for BoundedSender<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().sender_alive = false;
+        self.shared.not_empty.notify_all();
+    }
+}
+
+impl<T> BoundedReceiver<T> {
+    fn take(&self, state: &mut State<T>) -> Option<T> {
+        let item = state.items.pop_front()?;
+        self.shared.not_full.notify_one();
+        Some(item)
+    }
+
+    /// Waits for the next event. Returns `None` once the subscription has ended
+    /// and every queued event was received.
+    pub fn recv(&self) -> Option<T> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(item) = self.take(&mut state) {
+                return Some(item);
+            }
+            if !state.sender_alive {
+                return None;
+            }
+            state = self.shared.not_empty.wait(state).unwrap();
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(item) = self.take(&mut state) {
+                return Ok(item);
+            }
+            if !state.sender_alive {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.state.lock().unwrap();
+        match self.take(&mut state) {
+            Some(item) => Ok(item),
+            None if state.sender_alive => Err(TryRecvError::Empty),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Blocking iterator over the events, like `mpsc::Receiver::iter`.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+
+    /// Number of events discarded by `OverflowPolicy::DropOldest` so far.
+    pub fn dropped(&self) -> u64 {
+        self.shared.state.lock().unwrap().dropped
+    }
+}
+
+impl<T> Drop for BoundedReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().receiver_alive = false;
+        self.shared.not_full.notify_all();
+    }
+}
+
+impl<T> IntoIterator for BoundedReceiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { receiver: self }
+    }
+}
+
+pub struct IntoIter<T> {
+    receiver: BoundedReceiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_oldest_counts_discarded_items() {
+        let shutdown = AtomicBool::new(false);
+        let (tx, rx) = bounded(2, OverflowPolicy::DropOldest);
+
+        for i in 0..5 {
+            assert!(tx.send(i, &shutdown));
+        }
+        drop(tx);
+
+        assert_eq!(rx.dropped(), 3);
+        assert_eq!(rx.iter().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn block_waits_for_the_receiver() {
+        let shutdown = AtomicBool::new(false);
+        let (tx, rx) = bounded(1, OverflowPolicy::Block);
+
+        let sender = std::thread::spawn(move || (0..3).all(|i| tx.send(i, &shutdown)));
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok(0));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok(1));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok(2));
+        assert!(sender.join().unwrap());
+        assert_eq!(rx.dropped(), 0);
+    }
+}
@@ -1,12 +1,14 @@
+mod bounded_channel;
 mod connection_status;
 mod reconnection;
 mod tcp_client;
 mod tcp_client_blocking;
 
+pub use bounded_channel::{BoundedReceiver, OverflowPolicy};
 pub use connection_status::{ConnectionStatus, ConnectionStatusTracker};
 pub use reconnection::{ReconnectionConfig, ReconnectionManager};
 pub use tcp_client::{
-    AsyncTcpClient as TitanTcpClient, ReconnectSettings as TitanTcpClientConfig,
+    subscribe, AsyncTcpClient as TitanTcpClient, ReconnectSettings as TitanTcpClientConfig,
     TcpClientError as TitanTcpClientError,
 };
 pub use tcp_client_blocking::{
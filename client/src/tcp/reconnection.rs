@@ -85,22 +85,23 @@ impl ReconnectionManager {
             }
         }
 
-        // Calculate exponential backoff with clamping to max_interval
+        // Calculate exponential backoff with clamping to max_interval.
+        // Work in milliseconds so sub-second base intervals (e.g. 200ms) still back off.
         let exponent = std::cmp::min(self.current_attempt, 10); // Prevent potential overflow
-        let backoff_secs = std::cmp::min(
-            self.config.base_interval.as_secs() * (1 << exponent),
-            self.config.max_interval.as_secs(),
+        let backoff_millis = std::cmp::min(
+            (self.config.base_interval.as_millis() as u64).saturating_mul(1 << exponent),
+            self.config.max_interval.as_millis() as u64,
         );
 
         // Add jitter if configured (to prevent thundering herd problem)
-        let final_secs = if self.config.use_jitter {
-            let jitter = rand::random::<u64>() % (backoff_secs / 4 + 1);
-            backoff_secs + jitter
+        let final_millis = if self.config.use_jitter {
+            let jitter = rand::random::<u64>() % (backoff_millis / 4 + 1);
+            backoff_millis + jitter
         } else {
-            backoff_secs
+            backoff_millis
         };
 
-        let wait_time = Duration::from_secs(final_secs);
+        let wait_time = Duration::from_millis(final_millis);
 
         info!(
             "Reconnection attempt {}/{:?} scheduled in {:?}",
@@ -212,6 +213,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sub_second_backoff() {
+        // The client README's settings.
+        let config = ReconnectionConfig {
+            base_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(30),
+            max_attempts: None,
+            use_jitter: false,
+        };
+
+        let mut manager = ReconnectionManager::new(config);
+
+        // 100ms * 2^1 = 200ms
+        assert_eq!(manager.next_delay().unwrap(), Duration::from_millis(200));
+
+        // 100ms * 2^2 = 400ms
+        assert_eq!(manager.next_delay().unwrap(), Duration::from_millis(400));
+
+        // 100ms * 2^3 = 800ms
+        assert_eq!(manager.next_delay().unwrap(), Duration::from_millis(800));
+
+        // Eventually capped at 30s
+        for _ in 0..10 {
+            manager.next_delay();
+        }
+        assert_eq!(manager.next_delay().unwrap(), Duration::from_secs(30));
+    }
+
     #[test]
     fn test_max_interval() {
         let config = ReconnectionConfig {
@@ -133,6 +133,8 @@ impl AsyncTcpClient {
     ///
     /// This method includes reconnect logic with exponential backoff. If the connection is lost,
     /// the client will automatically try to reconnect using the provided settings.
+    /// If the request sets `from_height`, it is advanced to every `NewBlock` received, so
+    /// reconnects replay the events missed while disconnected.
     ///
     /// If there's already an active subscription task, it will be shut down and a new one will be created.
     pub async fn subscribe(
@@ -188,7 +190,7 @@ impl AsyncTcpClient {
             guard.get_receiver()
         };
         let addr = addr.to_owned();
-        let subscription_request = subscription_request;
+        let mut subscription_request = subscription_request;
         let status_tracker = self.status_tracker.clone();
 
         // Set initial status to Connecting
@@ -282,6 +284,7 @@ impl AsyncTcpClient {
                                         Ok(_n) => {
                                             read_in_progress = false;
                                             let trimmed = line.trim();
+                                            // Empty lines are heartbeats on an idle connection.
                                             if trimmed.is_empty() {
                                                 continue;
                                             }
@@ -298,6 +301,12 @@ impl AsyncTcpClient {
 
                                             match serde_json::from_str::<Event>(trimmed) {
                                                 Ok(event) => {
+                                                    // Resume from the last block seen on reconnect.
+                                                    if let (Some(_), Event::NewBlock { block_height, .. }) =
+                                                        (subscription_request.from_height, &event)
+                                                    {
+                                                        subscription_request.from_height = Some(*block_height);
+                                                    }
                                                     if let Err(e) = tx.send(event).await {
                                                         error!("Failed to send event to channel: {}", e);
                                                         break;
@@ -455,6 +464,77 @@ impl Drop for AsyncTcpClient {
     }
 }
 
+/// Subscribes once to the TCP server at `addr`, without reconnect logic.
+/// Returns a channel receiver for incoming events.
+///
+/// The connection is owned by a background task that stops as soon as the server closes
+/// the connection or the returned receiver is dropped, so no shutdown signal is needed.
+pub async fn subscribe(
+    addr: &str,
+    subscription_request: TcpSubscriptionRequest,
+) -> Result<mpsc::Receiver<Event>, TcpClientError> {
+    info!("Subscribing to {}", addr);
+
+    let stream = TcpStream::connect(addr).await?;
+    let (reader, mut writer) = stream.into_split();
+
+    // Serialize and send the subscription request.
+    let req_json = serde_json::to_string(&subscription_request)?;
+    writer.write_all(req_json.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+
+    // Create a channel to forward events.
+    let (tx, rx) = mpsc::channel::<Event>(100);
+
+    tokio::spawn(async move {
+        // Keep the writer alive so the server doesn't see a half-closed connection.
+        let _writer = writer;
+        let mut lines = BufReader::new(reader).lines();
+
+        loop {
+            tokio::select! {
+                result = lines.next_line() => {
+                    match result {
+                        Ok(Some(line)) => {
+                            let trimmed = line.trim();
+                            // Empty lines are heartbeats on an idle connection.
+                            if trimmed.is_empty() {
+                                continue;
+                            }
+
+                            match serde_json::from_str::<Event>(trimmed) {
+                                Ok(event) => {
+                                    if tx.send(event).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    error!("Failed to parse event: {}. Line: {}", e, trimmed);
+                                }
+                            }
+                        }
+                        Ok(None) => {
+                            warn!("TCP connection closed by server.");
+                            break;
+                        }
+                        Err(e) => {
+                            error!("Error reading from TCP socket: {}", e);
+                            break;
+                        }
+                    }
+                }
+                _ = tx.closed() => {
+                    info!("Event receiver dropped. Exiting TCP subscription task.");
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,6 +658,38 @@ mod tests {
         (handle, addr, shutdown_tx)
     }
 
+    #[tokio::test]
+    async fn test_subscribe_receives_events() {
+        init_test_logger();
+
+        let (server_handle, server_addr, shutdown_tx) = start_async_test_server().await;
+
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
+        let mut rx = subscribe(&server_addr.to_string(), subscription_request)
+            .await
+            .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("timed out waiting for event");
+        assert!(matches!(event, Some(Event::TransactionsAdded { .. })));
+
+        // Dropping the receiver stops the background task.
+        drop(rx);
+
+        let _ = shutdown_tx.send(());
+        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_fails_without_server() {
+        init_test_logger();
+
+        let subscription_request = TcpSubscriptionRequest::new(vec![]);
+        let result = subscribe("127.0.0.1:1", subscription_request).await;
+        assert!(matches!(result, Err(TcpClientError::IOError(_))));
+    }
+
     #[tokio::test]
     async fn test_shutdown_and_join() {
         // Initialize logging for tests
@@ -593,7 +705,7 @@ mod tests {
         });
 
         // Subscribe to a non-existent server - this will keep retrying
-        let subscription_request = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request = TcpSubscriptionRequest::new(vec![]);
         info!("Subscribing to non-existent server to test shutdown");
 
         // We know this will fail to connect, but it starts the background task
@@ -636,7 +748,7 @@ mod tests {
         });
 
         // First subscription
-        let subscription_request1 = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request1 = TcpSubscriptionRequest::new(vec![]);
         info!("Creating first subscription");
         let result1 = client.subscribe("127.0.0.1:1", subscription_request1).await;
         assert!(result1.is_ok());
@@ -649,7 +761,7 @@ mod tests {
         sleep(Duration::from_millis(200)).await;
 
         // Second subscription - should replace the first one
-        let subscription_request2 = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request2 = TcpSubscriptionRequest::new(vec![]);
         info!("Creating second subscription (should replace the first)");
         let result2 = client.subscribe("127.0.0.1:2", subscription_request2).await;
         assert!(result2.is_ok());
@@ -691,9 +803,7 @@ mod tests {
         info!("Initial status: {:?}", client.get_status());
 
         // Subscribe to the server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         info!("Subscribing to test server at {}", server_addr);
         let rx = client
@@ -749,9 +859,7 @@ mod tests {
         });
 
         // Subscribe to the server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         info!("Subscribing to test server at {}", server_addr);
         let mut rx = client
@@ -838,9 +946,7 @@ mod tests {
         info!("Initial status: {:?}", client.get_status());
 
         // Try to connect to a non-existent server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         info!("Subscribing to non-existent server to test error handling");
         let rx = client
@@ -888,9 +994,7 @@ mod tests {
         });
 
         // Subscribe to a non-existent server to trigger reconnection attempts
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         info!("Subscribing to non-existent server to trigger reconnection");
         let rx = client
@@ -1010,9 +1114,7 @@ mod tests {
         });
 
         // Subscribe to the server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         info!("Subscribing to server with buffer size limit test");
         let _rx = client
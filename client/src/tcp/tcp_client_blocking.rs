@@ -17,6 +17,7 @@ use tracing::{error, info, warn};
 use crate::tcp::reconnection::ReconnectionManager;
 
 use super::{
+    bounded_channel::{self, BoundedReceiver, BoundedSender, OverflowPolicy},
     connection_status::{ConnectionStatus, ConnectionStatusTracker},
     reconnection,
 };
@@ -138,11 +139,57 @@ impl TcpClient {
     ///
     /// If there's already an active worker thread, it will be shut down and
     /// a new one will be created.
+    ///
+    /// Lines that can't be parsed as an event are logged and skipped. Use
+    /// `subscribe_with_errors` to receive them.
     pub fn subscribe(
         &self,
         addr: String,
         subscription_request: TcpSubscriptionRequest,
     ) -> Result<mpsc::Receiver<Event>, TcpClientError> {
+        let (tx, rx) = mpsc::channel();
+        self.start(addr, subscription_request, tx, Result::ok)?;
+        Ok(rx)
+    }
+
+    /// Like `subscribe`, but at most `capacity` events are queued, so a slow
+    /// consumer can't make the client run out of memory. `policy` decides
+    /// what happens to further events while the queue is full.
+    pub fn subscribe_bounded(
+        &self,
+        addr: String,
+        subscription_request: TcpSubscriptionRequest,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<BoundedReceiver<Event>, TcpClientError> {
+        let (tx, rx) = bounded_channel::bounded(capacity, policy);
+        self.start(addr, subscription_request, tx, Result::ok)?;
+        Ok(rx)
+    }
+
+    /// Like `subscribe`, but lines that can't be parsed as an event, e.g.
+    /// because the server is a newer version, are sent on the channel as a
+    /// `TcpClientError::SerdeError`. The subscription carries on after an
+    /// error; call `shutdown` to stop it instead.
+    pub fn subscribe_with_errors(
+        &self,
+        addr: String,
+        subscription_request: TcpSubscriptionRequest,
+    ) -> Result<mpsc::Receiver<Result<Event, TcpClientError>>, TcpClientError> {
+        let (tx, rx) = mpsc::channel();
+        self.start(addr, subscription_request, tx, |result| {
+            Some(result.map_err(TcpClientError::from))
+        })?;
+        Ok(rx)
+    }
+
+    fn start<T: Send + 'static>(
+        &self,
+        addr: String,
+        subscription_request: TcpSubscriptionRequest,
+        sink: impl EventSink<T>,
+        forward: fn(Result<Event, serde_json::Error>) -> Option<T>,
+    ) -> Result<(), TcpClientError> {
         // Check if we already have a worker thread running
         let mut worker_lock = self.worker_thread.lock().map_err(|_| {
             TcpClientError::IOError(std::io::Error::new(
@@ -158,19 +205,21 @@ impl TcpClient {
         let config = self.config.clone();
         let status_tracker = self.status_tracker.clone();
 
-        // Call the subscribe function which returns both the receiver and thread handle
-        let (rx, handle) = subscribe(
+        // Call the subscribe function which returns the thread handle
+        let handle = subscribe(
             addr,
             subscription_request,
             shutdown_flag,
             config,
             status_tracker,
+            sink,
+            forward,
         )?;
 
         // Store the thread handle for later joining
         *worker_lock = Some(handle);
 
-        Ok(rx)
+        Ok(())
     }
 
     /// Signals the client to shut down and stop any reconnection attempts.
@@ -241,16 +290,35 @@ impl Drop for TcpClient {
     }
 }
 
-fn subscribe(
+/// Channel the subscription thread forwards to.
+trait EventSink<T>: Send + 'static {
+    /// Returns false once the thread should stop, e.g. because the receiver
+    /// was dropped.
+    fn put(&self, item: T, shutdown_flag: &AtomicBool) -> bool;
+}
+
+impl<T: Send + 'static> EventSink<T> for mpsc::Sender<T> {
+    fn put(&self, item: T, _shutdown_flag: &AtomicBool) -> bool {
+        self.send(item).is_ok()
+    }
+}
+
+impl<T: Send + 'static> EventSink<T> for BoundedSender<T> {
+    fn put(&self, item: T, shutdown_flag: &AtomicBool) -> bool {
+        self.send(item, shutdown_flag)
+    }
+}
+
+/// `forward` turns each parsed line into what's sent to `tx`, if anything.
+fn subscribe<T: Send + 'static>(
     addr: String,
     subscription_request: TcpSubscriptionRequest,
     shutdown_flag: Arc<AtomicBool>,
     config: TcpClientConfig,
     status_tracker: ConnectionStatusTracker,
-) -> Result<(mpsc::Receiver<Event>, JoinHandle<()>), TcpClientError> {
-    // Create a standard mpsc channel to forward events.
-    let (tx, rx) = mpsc::channel::<Event>();
-
+    tx: impl EventSink<T>,
+    forward: fn(Result<Event, serde_json::Error>) -> Option<T>,
+) -> Result<JoinHandle<()>, TcpClientError> {
     let address = addr
         .to_socket_addrs()
         .map_err(|_| TcpClientError::AddrParseError(format!("Invalid address: {}", addr)))?
@@ -373,20 +441,18 @@ fn subscribe(
                             Ok(_) => {
                                 read_in_progress = false;
                                 let trimmed = line.trim();
+                                // Empty lines are heartbeats on an idle connection.
                                 if trimmed.is_empty() {
                                     continue;
                                 }
-                                match serde_json::from_str::<Event>(trimmed) {
-                                    Ok(event) => {
-                                        if tx.send(event).is_err() {
-                                            error!(
-                                                "Receiver dropped. Exiting subscription thread."
-                                            );
-                                            return;
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to parse event: {}. Line: {}", e, trimmed);
+                                let result = serde_json::from_str::<Event>(trimmed);
+                                if let Err(e) = &result {
+                                    error!("Failed to parse event: {}. Line: {}", e, trimmed);
+                                }
+                                if let Some(item) = forward(result) {
+                                    if !tx.put(item, &shutdown_flag) {
+                                        error!("Receiver dropped or client shut down. Exiting subscription thread.");
+                                        return;
                                     }
                                 }
                             }
@@ -456,7 +522,7 @@ fn subscribe(
         update_status(ConnectionStatus::Disconnected);
     });
 
-    Ok((rx, handle))
+    Ok(handle)
 }
 
 #[cfg(test)]
@@ -468,8 +534,18 @@ mod tests {
     use std::time::Duration;
     use titan_types::EventType;
 
+    const TRANSACTIONS_ADDED: &str = r#"{"type":"TransactionsAdded","data": {"txids":["1111111111111111111111111111111111111111111111111111111111111111"]}}"#;
+
     // Helper function to create a test TCP server
     fn start_test_server(ready_tx: std::sync::mpsc::Sender<SocketAddr>) -> JoinHandle<()> {
+        start_test_server_sending(ready_tx, &[TRANSACTIONS_ADDED])
+    }
+
+    // Like `start_test_server`, but sends `lines` instead of a sample event
+    fn start_test_server_sending(
+        ready_tx: std::sync::mpsc::Sender<SocketAddr>,
+        lines: &'static [&'static str],
+    ) -> JoinHandle<()> {
         thread::spawn(move || {
             // Bind to a random available port
             let listener = TcpListener::bind("127.0.0.1:0").unwrap();
@@ -490,10 +566,10 @@ mod tests {
                         // Add a small delay to ensure the client is ready to receive
                         thread::sleep(Duration::from_millis(50));
 
-                        // Send a sample event - using correct format for Event
-                        let event = r#"{"type":"TransactionsAdded","data": {"txids":["1111111111111111111111111111111111111111111111111111111111111111"]}}"#;
-                        stream.write_all(event.as_bytes()).unwrap();
-                        stream.write_all(b"\n").unwrap();
+                        for line in lines {
+                            stream.write_all(line.as_bytes()).unwrap();
+                            stream.write_all(b"\n").unwrap();
+                        }
                         stream.flush().unwrap();
 
                         // Keep the connection open for a while to ensure the client can read the response
@@ -529,9 +605,7 @@ mod tests {
         assert_eq!(client.get_status(), ConnectionStatus::Disconnected);
 
         // Subscribe - this should connect
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         let rx = client
             .subscribe(format!("{}", server_addr), subscription_request)
@@ -574,9 +648,7 @@ mod tests {
         let client = TcpClient::new(config);
 
         // Subscribe to receive events
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         let rx = client
             .subscribe(format!("{}", server_addr), subscription_request)
@@ -607,6 +679,40 @@ mod tests {
         server_handle.join().unwrap();
     }
 
+    #[test]
+    fn test_receive_parse_errors() {
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let server_handle = start_test_server_sending(
+            ready_tx,
+            &[r#"{"type":"FromTheFuture","data":{}}"#, TRANSACTIONS_ADDED],
+        );
+        let server_addr = ready_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        let client = TcpClient::new(TcpClientConfig {
+            connection_timeout: Duration::from_secs(1),
+            max_reconnect_attempts: Some(1),
+            base_reconnect_interval: Duration::from_millis(100),
+            ..TcpClientConfig::default()
+        });
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
+
+        let rx = client
+            .subscribe_with_errors(format!("{}", server_addr), subscription_request)
+            .unwrap();
+
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_secs(2)),
+            Ok(Err(TcpClientError::SerdeError(_)))
+        ));
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_secs(2)),
+            Ok(Ok(Event::TransactionsAdded { .. }))
+        ));
+
+        client.shutdown_and_join();
+        server_handle.join().unwrap();
+    }
+
     #[test]
     fn test_connection_error_handling() {
         // Create a client with short timeout
@@ -622,9 +728,7 @@ mod tests {
         assert_eq!(client.get_status(), ConnectionStatus::Disconnected);
 
         // Try to connect to a non-existent server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         let rx = client
             .subscribe("127.0.0.1:1".to_string(), subscription_request)
@@ -654,9 +758,7 @@ mod tests {
         let client = TcpClient::new(TcpClientConfig::default());
 
         // Subscribe to a non-existent server
-        let subscription_request = TcpSubscriptionRequest {
-            subscribe: vec![EventType::TransactionsAdded],
-        };
+        let subscription_request = TcpSubscriptionRequest::new(vec![EventType::TransactionsAdded]);
 
         let rx = client
             .subscribe("127.0.0.1:1".to_string(), subscription_request)
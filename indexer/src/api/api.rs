@@ -1,21 +1,39 @@
 use {
     super::{
         content::{content_response, AcceptEncoding, ContentError},
+        cursor::{Cursor, InvalidCursor},
         query::{to_hash, to_rune_id},
     },
     crate::{
-        bitcoin_rpc::PooledClient,
-        index::{Index, IndexError},
+        bitcoin_rpc::{is_not_found_rpc_error, PooledClient},
+        index::{Chain, Index, IndexError, RawEnvelope, StoreError},
+        models::Inscription,
         subscription::{self, WebhookSubscriptionManager},
     },
-    bitcoin::{consensus, Address, OutPoint, Txid},
-    bitcoincore_rpc::RpcApi,
+    bitcoin::{
+        consensus, constants::genesis_block, opcodes, Address, Network, OutPoint, ScriptBuf, Txid,
+        Wtxid,
+    },
+    bitcoincore_rpc::{Client, RpcApi},
     http::HeaderMap,
-    std::{collections::HashMap, sync::Arc},
+    ordinals::{Artifact, Rune, RuneId, Runestone, Sat},
+    std::{
+        collections::{hash_map::Entry, BTreeSet, HashMap, HashSet},
+        ops::Range,
+        str::FromStr,
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
     titan_types::{
-        query, AddressData, Block, BlockTip, InscriptionId, MempoolEntry, Pagination,
-        PaginationResponse, RuneResponse, Status, Subscription, Transaction, TransactionStatus,
-        TxOutEntry,
+        query, AddressBalance, AddressData, AddressRune, AddressUtxo, Block, BlockRunes, BlockTip,
+        BlockTxids, CursorPage, DecodedTransaction, EnvelopeData, FeeEstimate, FeeEstimates,
+        FeeHistogramBucket, IndexStats, InscriptionId, InscriptionMetadata, InscriptionsQuery,
+        MempoolEntry, OpReturnData, Order, OutputsResponse, Pagination, PaginationResponse,
+        Prevout, ReindexRange, RuneAmount, RuneBalance, RuneChartQuery, RuneChartResponse,
+        RuneEtchingResponse, RuneHolder, RuneMintsResponse, RuneResponse, RuneTransactionKind,
+        RunesQuery, RunestoneResponse, SatResponse, SpentStatus, Status, Subscription,
+        TestAcceptResponse, Transaction, TransactionData, TransactionStatus,
+        TransactionStatusResponse, TransactionWithPrevouts, TransactionsResponse, TxOutResponse,
     },
     tracing::error,
     uuid::Uuid,
@@ -35,10 +53,32 @@ pub enum ApiError {
     HexError(#[from] hex::FromHexError),
     #[error("consensus error: {0}")]
     ConsensusError(#[from] consensus::encode::Error),
+    #[error("at most {0} subscriptions can be registered")]
+    SubscriptionLimit(usize),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    InvalidCursor(#[from] InvalidCursor),
+    #[error("{0}")]
+    IdempotencyKeyReused(#[from] subscription::IdempotencyKeyReused),
 }
 
 pub type Result<T> = std::result::Result<T, ApiError>;
 
+pub const MAX_TRANSACTIONS_BATCH_SIZE: usize = 100;
+
+pub const MAX_OUTPUTS_BATCH_SIZE: usize = 1000;
+
+pub const MAX_SUBSCRIPTIONS_BATCH_SIZE: usize = 100;
+
+/// Confirmation targets of `/fees`, in blocks.
+const FEE_ESTIMATE_TARGETS: [u16; 4] = [1, 3, 6, 25];
+
+/// Lower bounds of the `/fees` mempool histogram buckets, in sat/vB.
+const FEE_HISTOGRAM_BUCKETS: [u64; 20] = [
+    0, 1, 2, 3, 4, 5, 6, 8, 10, 12, 15, 20, 30, 50, 75, 100, 150, 250, 500, 1000,
+];
+
 pub fn tip(index: Arc<Index>) -> Result<BlockTip> {
     let block_count = index.get_block_count()?;
     let height = block_count.saturating_sub(1);
@@ -49,19 +89,88 @@ pub fn tip(index: Arc<Index>) -> Result<BlockTip> {
     })
 }
 
-pub fn status(index: Arc<Index>) -> Result<Status> {
+pub fn status(index: Arc<Index>, chain: Chain) -> Result<Status> {
     let block_count = index.get_block_count()?;
     let block_hash = index.get_block_hash(block_count - 1)?;
-    Ok(Status {
-        block_tip: BlockTip {
+    Ok(Status::new(
+        chain.to_string(),
+        genesis_block(chain.network()).block_hash().to_string(),
+        BlockTip {
             height: block_count - 1,
             hash: block_hash.to_string(),
         },
-        runes_count: index.get_runes_count()?,
-        mempool_tx_count: index.get_mempool_txids()?.len() as u64,
+        index.get_runes_count()?,
+        index.get_mempool_txids()?.len() as u64,
+    ))
+}
+
+pub fn fee_estimates(index: Arc<Index>, client: &Client) -> Result<FeeEstimates> {
+    let estimates = FEE_ESTIMATE_TARGETS
+        .into_iter()
+        .map(|target_blocks| {
+            let estimate = client.estimate_smart_fee(target_blocks, None)?;
+            Ok(FeeEstimate {
+                target_blocks,
+                // The node answers in BTC/kvB.
+                sat_per_vbyte: estimate
+                    .fee_rate
+                    .map(|fee_rate| fee_rate.to_sat() as f64 / 1000.0),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let mut buckets = [(0, 0); FEE_HISTOGRAM_BUCKETS.len()];
+    for entry in index.get_all_mempool_entries()?.values() {
+        if entry.vsize == 0 {
+            continue;
+        }
+        let fee_rate = entry.fees.base / entry.vsize;
+        let bucket = FEE_HISTOGRAM_BUCKETS.partition_point(|min| *min <= fee_rate) - 1;
+        buckets[bucket].0 += 1;
+        buckets[bucket].1 += entry.vsize;
+    }
+
+    Ok(FeeEstimates {
+        estimates,
+        mempool_histogram: FEE_HISTOGRAM_BUCKETS
+            .into_iter()
+            .zip(buckets)
+            .filter(|(_, (count, _))| *count > 0)
+            .map(|(min_sat_per_vbyte, (count, vsize))| FeeHistogramBucket {
+                min_sat_per_vbyte,
+                count,
+                vsize,
+            })
+            .collect(),
     })
 }
 
+pub fn reindex(index: Arc<Index>, range: ReindexRange) -> Result<()> {
+    Ok(index.request_reindex(range)?)
+}
+
+pub fn index_stats(index: Arc<Index>) -> Result<IndexStats> {
+    Ok(IndexStats {
+        indexed_height: index.get_block_count()?.checked_sub(1),
+        reorg_count: index.get_reorg_count(),
+        last_flush_epoch_secs: index.get_last_flush_epoch_secs(),
+        disk_size_bytes: index.get_disk_size()?,
+        column_families: index.get_column_family_stats()?,
+    })
+}
+
+pub fn sat(index: Arc<Index>, sat: Sat) -> Result<SatResponse> {
+    let mut response = SatResponse::from(sat);
+
+    response.block_hash = match index.get_block_hash(sat.height().0.into()) {
+        Ok(block_hash) => Some(block_hash),
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => None,
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(response)
+}
+
 pub fn block(index: Arc<Index>, block: &query::Block) -> Result<Block> {
     let hash = to_hash(block, &index)?;
     Ok(index.get_block_by_hash(&hash)?)
@@ -72,14 +181,135 @@ pub fn block_hash_by_height(index: Arc<Index>, height: u64) -> Result<String> {
     Ok(hash.to_string())
 }
 
-pub fn block_txids(index: Arc<Index>, block: &query::Block) -> Result<Vec<String>> {
+fn block_txids_of(block: &Block) -> Result<Vec<Txid>> {
+    Ok(block
+        .tx_ids
+        .iter()
+        .map(|txid| Txid::from_str(txid).map_err(|err| IndexError::from(StoreError::from(err))))
+        .collect::<std::result::Result<Vec<Txid>, IndexError>>()?)
+}
+
+pub fn block_txids(index: Arc<Index>, block: &query::Block) -> Result<BlockTxids> {
     let hash = to_hash(block, &index)?;
     let block = index.get_block_by_hash(&hash)?;
-    Ok(block.tx_ids)
+    let txids = block_txids_of(&block)?;
+
+    Ok(BlockTxids {
+        height: block.height,
+        hash,
+        txids,
+    })
 }
 
-pub fn output(index: Arc<Index>, outpoint: &OutPoint) -> Result<TxOutEntry> {
-    Ok(index.get_tx_out(outpoint)?)
+pub fn block_runes(index: Arc<Index>, block: &query::Block) -> Result<BlockRunes> {
+    let hash = to_hash(block, &index)?;
+    let block = index.get_block_by_hash(&hash)?;
+    let state_changes = index.get_txs_state_changes(&block_txids_of(&block)?)?;
+
+    let mut block_runes = BlockRunes {
+        height: block.height,
+        hash,
+        etchings: 0,
+        mints: 0,
+        transfers: 0,
+        burns: 0,
+        rune_ids: Vec::new(),
+    };
+    let mut rune_ids = BTreeSet::new();
+
+    for state_change in state_changes.values() {
+        // Only the premine of a rune etched by the transaction itself counts
+        // as created by it.
+        let etched = match state_change.etched {
+            Some((rune_id, _)) => Some((rune_id, index.get_rune(&rune_id)?.premine)),
+            None => None,
+        };
+
+        let tx_rune_ids = state_change.rune_ids();
+        let transfers = tx_rune_ids.iter().any(|rune_id| {
+            let premine = etched
+                .filter(|(etched_id, _)| etched_id == rune_id)
+                .map_or(0, |(_, premine)| premine);
+            state_change
+                .rune_transaction_kinds(rune_id, premine)
+                .contains(&RuneTransactionKind::Transfer)
+        });
+
+        block_runes.etchings += u64::from(etched.is_some());
+        block_runes.mints += u64::from(state_change.minted.is_some());
+        block_runes.transfers += u64::from(transfers);
+        block_runes.burns += u64::from(!state_change.burned.is_empty());
+        rune_ids.extend(tx_rune_ids);
+    }
+
+    block_runes.rune_ids = rune_ids.into_iter().collect();
+    Ok(block_runes)
+}
+
+pub fn output(index: Arc<Index>, outpoint: &OutPoint) -> Result<TxOutResponse> {
+    // Spent outputs keep their rune contents, so this also works for historical outputs.
+    let tx_out = index.get_tx_out(outpoint)?;
+
+    let mut rune_entries = HashMap::new();
+    for rune in tx_out.runes.iter().chain(tx_out.risky_runes.iter()) {
+        if let Entry::Vacant(entry) = rune_entries.entry(rune.rune_id) {
+            entry.insert(index.get_rune(&rune.rune_id)?);
+        }
+    }
+
+    let to_rune_balance = |rune: &RuneAmount| {
+        let rune_entry = &rune_entries[&rune.rune_id];
+        RuneBalance {
+            rune_id: rune.rune_id,
+            amount: rune.amount,
+            divisibility: rune_entry.divisibility,
+            symbol: rune_entry.symbol,
+        }
+    };
+
+    let mut response = TxOutResponse::new(
+        tx_out.runes.iter().map(to_rune_balance).collect(),
+        tx_out.risky_runes.iter().map(to_rune_balance).collect(),
+        tx_out.value,
+        tx_out.spent.clone(),
+    );
+
+    if let SpentStatus::Spent(spender) = &tx_out.spent {
+        response.spending_txid = Some(spender.txid);
+        response.spending_height = index
+            .get_transaction_status(&spender.txid)
+            .ok()
+            .and_then(|status| status.block_height);
+        response.spent_in_mempool = index.is_tx_in_mempool(&spender.txid)?;
+    }
+
+    Ok(response)
+}
+
+pub fn outputs(index: Arc<Index>, outpoints: &Vec<OutPoint>) -> Result<OutputsResponse> {
+    let mut response = OutputsResponse::default();
+    let mut seen = HashSet::with_capacity(outpoints.len());
+
+    for outpoint in outpoints {
+        if !seen.insert(*outpoint) {
+            continue;
+        }
+
+        match output(index.clone(), outpoint) {
+            Ok(output) => {
+                response.outputs.insert(*outpoint, output);
+            }
+            Err(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(_)))) => {
+                response.missing.push(*outpoint);
+            }
+            Err(e) => {
+                error!("failed to fetch output {}: {}", outpoint, e);
+                response.errors.insert(*outpoint, e.to_string());
+            }
+        }
+    }
+
+    Ok(response)
 }
 
 pub fn inscription_content(
@@ -90,10 +320,101 @@ pub fn inscription_content(
     decompress: bool,
 ) -> Result<Option<(HeaderMap, Vec<u8>)>> {
     let inscription = index.get_inscription(inscription_id)?;
-    let content_response = content_response(inscription, accept_encoding, csp_origin, decompress)?;
+    let content_response = content_response(
+        inscription_id,
+        inscription,
+        accept_encoding,
+        csp_origin,
+        decompress,
+    )?;
     Ok(content_response)
 }
 
+pub fn inscription_metadata(
+    index: Arc<Index>,
+    inscription_id: &InscriptionId,
+) -> Result<InscriptionMetadata> {
+    let inscription = index.get_inscription(inscription_id)?;
+
+    // Only available when bitcoin transactions are indexed.
+    let genesis_height = index
+        .get_transaction_status(&inscription_id.txid)
+        .ok()
+        .and_then(|status| status.block_height);
+
+    Ok(to_inscription_metadata(
+        inscription_id.clone(),
+        &inscription,
+        genesis_height,
+    ))
+}
+
+/// Parents named by the inscription's envelope, which aren't necessarily
+/// indexed themselves.
+pub fn inscription_parents(
+    index: Arc<Index>,
+    inscription_id: &InscriptionId,
+) -> Result<Vec<InscriptionId>> {
+    Ok(index.get_inscription(inscription_id)?.parents)
+}
+
+pub fn inscription_children(
+    index: Arc<Index>,
+    inscription_id: &InscriptionId,
+    pagination: Pagination,
+) -> Result<PaginationResponse<InscriptionId>> {
+    // 404 for unknown inscriptions rather than an empty page.
+    index.get_inscription(inscription_id)?;
+    Ok(index.get_inscription_children(inscription_id, pagination)?)
+}
+
+pub fn output_inscriptions(index: Arc<Index>, outpoint: &OutPoint) -> Result<Vec<InscriptionId>> {
+    // 404 for outputs the index doesn't have, like `output`.
+    Ok(index.get_output_inscriptions(outpoint)?)
+}
+
+pub fn sat_inscriptions(index: Arc<Index>, sat: Sat) -> Result<Vec<InscriptionId>> {
+    Ok(index.get_sat_inscriptions(sat.n())?)
+}
+
+pub fn inscriptions(
+    index: Arc<Index>,
+    query: &InscriptionsQuery,
+    pagination: Pagination,
+) -> Result<PaginationResponse<InscriptionMetadata>> {
+    let inscriptions = index.get_inscriptions(query, pagination)?;
+
+    Ok(PaginationResponse {
+        items: inscriptions
+            .items
+            .into_iter()
+            .map(|(id, inscription, genesis_height)| {
+                to_inscription_metadata(id, &inscription, Some(genesis_height))
+            })
+            .collect(),
+        offset: inscriptions.offset,
+    })
+}
+
+fn to_inscription_metadata(
+    id: InscriptionId,
+    inscription: &Inscription,
+    genesis_height: Option<u64>,
+) -> InscriptionMetadata {
+    InscriptionMetadata {
+        id,
+        content_type: inscription.content_type().map(str::to_string),
+        content_encoding: inscription
+            .content_encoding
+            .as_deref()
+            .and_then(|encoding| std::str::from_utf8(encoding).ok())
+            .map(str::to_string),
+        content_length: inscription.body.as_ref().map_or(0, Vec::len) as u64,
+        genesis_height,
+        parents: inscription.parents.clone(),
+    }
+}
+
 pub fn rune(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneResponse> {
     let rune_id = to_rune_id(rune_query, &index)?;
     let block_count = index.get_block_count()?;
@@ -103,11 +424,25 @@ pub fn rune(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneResponse>
     Ok(rune_response)
 }
 
+/// Mint progress of a rune. Mintability is checked against the height of the
+/// next block, the earliest a mint broadcast now could confirm.
+pub fn rune_mints(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneMintsResponse> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let block_count = index.get_block_count()?;
+    Ok(index.get_rune(&rune_id)?.to_mints_response(block_count))
+}
+
+pub fn rune_etching(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneEtchingResponse> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    Ok(index.get_rune(&rune_id)?.to_etching_response(rune_id))
+}
+
 pub fn runes(
     index: Arc<Index>,
+    query: RunesQuery,
     pagination: Pagination,
 ) -> Result<PaginationResponse<RuneResponse>> {
-    let rune_entries = index.get_runes(pagination)?;
+    let rune_entries = index.get_runes(query, pagination)?;
     let block_count = index.get_block_count()?;
     let rune_responses: Vec<RuneResponse> = rune_entries
         .items
@@ -121,14 +456,321 @@ pub fn runes(
     })
 }
 
-pub fn last_rune_transactions(
+/// Runes by etching number, oldest first, as `/runes` serves them at
+/// `block_count`.
+pub fn runes_by_numbers(
+    index: Arc<Index>,
+    numbers: Range<u64>,
+    block_count: u64,
+) -> Result<Vec<RuneResponse>> {
+    Ok(index
+        .get_runes_by_numbers(numbers)?
+        .into_iter()
+        .map(|(rune_id, rune_entry)| rune_entry.to_rune_response(rune_id, block_count))
+        .collect())
+}
+
+/// Like `runes`, newest first, but continues after `cursor` instead of
+/// skipping entries.
+pub fn runes_after(
+    index: Arc<Index>,
+    cursor: Option<Cursor>,
+    limit: u64,
+) -> Result<CursorPage<RuneResponse>> {
+    let runes_count = index.get_runes_count()?;
+    let end = match cursor {
+        Some(cursor) => cursor.position_up_to(runes_count)?,
+        None => runes_count,
+    };
+    let start = end.saturating_sub(limit);
+    let block_count = index.get_block_count()?;
+
+    let mut items = runes_by_numbers(index, start..end, block_count)?;
+    items.reverse();
+
+    Ok(CursorPage {
+        next_cursor: (start > 0 && items.len() as u64 == limit).then(|| {
+            Cursor {
+                mempool: false,
+                position: start,
+            }
+            .to_string()
+        }),
+        items,
+    })
+}
+
+pub fn search_runes(index: Arc<Index>, rune: &Rune, limit: u64) -> Result<Vec<RuneResponse>> {
+    let rune_entries = index.search_runes(rune, limit)?;
+    let block_count = index.get_block_count()?;
+    Ok(rune_entries
+        .into_iter()
+        .map(|(rune_id, rune_entry)| rune_entry.to_rune_response(rune_id, block_count))
+        .collect())
+}
+
+pub fn rune_holders(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    pagination: Pagination,
+) -> Result<PaginationResponse<RuneHolder>> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let holders: Vec<RuneHolder> = index
+        .get_rune_holders(&rune_id)?
+        .into_iter()
+        .skip(pagination.skip as usize)
+        .take(pagination.limit as usize)
+        .collect();
+
+    Ok(PaginationResponse {
+        offset: pagination.skip + holders.len() as u64,
+        items: holders,
+    })
+}
+
+/// Most buckets `/rune/{rune}/chart` returns.
+pub const MAX_CHART_BUCKETS: u64 = 10_000;
+
+/// `from` defaults to the rune's etching and `to` to now. The range is widened
+/// to whole buckets.
+pub fn rune_chart(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    chart_query: &RuneChartQuery,
+) -> Result<RuneChartResponse> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let rune_entry = index.get_rune(&rune_id)?;
+    let interval = chart_query.interval.as_secs();
+
+    let from = chart_query.from.unwrap_or(rune_entry.timestamp);
+    let to = match chart_query.to {
+        Some(to) => to,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+    if to < from {
+        return Err(ApiError::BadRequest(
+            "`to` must not be before `from`".to_string(),
+        ));
+    }
+
+    let from = from / interval * interval;
+    let to = to
+        .div_ceil(interval)
+        .checked_mul(interval)
+        .ok_or_else(|| ApiError::BadRequest("`to` is out of range".to_string()))?;
+    if (to - from) / interval > MAX_CHART_BUCKETS {
+        return Err(ApiError::BadRequest(format!(
+            "at most {MAX_CHART_BUCKETS} buckets can be returned, use a larger interval or a shorter range"
+        )));
+    }
+
+    Ok(RuneChartResponse {
+        interval: chart_query.interval,
+        buckets: index.get_rune_chart(&rune_id, rune_entry.premine, interval, from, to)?,
+    })
+}
+
+/// Number of rune transactions classified per store read when filtering by
+/// kind.
+const RUNE_TRANSACTIONS_SCAN_CHUNK: u64 = 500;
+
+/// Kinds and address `/rune/{rune}/transactions` is filtered by. The store
+/// doesn't know them, so transactions are checked one by one.
+struct RuneTransactionFilter<'a> {
+    index: &'a Index,
+    rune_id: RuneId,
+    premine: u128,
+    kinds: &'a [RuneTransactionKind],
+    address_txids: Option<HashSet<Txid>>,
+}
+
+impl<'a> RuneTransactionFilter<'a> {
+    fn new(
+        index: &'a Index,
+        rune_id: RuneId,
+        kinds: &'a [RuneTransactionKind],
+        address: Option<&Address>,
+    ) -> Result<Self> {
+        Ok(Self {
+            index,
+            rune_id,
+            premine: index.get_rune(&rune_id)?.premine,
+            kinds,
+            address_txids: address
+                .map(|address| index.get_address_txids(address))
+                .transpose()?,
+        })
+    }
+
+    fn matches(&self, txid: &Txid) -> Result<bool> {
+        if self
+            .address_txids
+            .as_ref()
+            .is_some_and(|address_txids| !address_txids.contains(txid))
+        {
+            return Ok(false);
+        }
+
+        if self.kinds.is_empty() {
+            return Ok(true);
+        }
+
+        let state_change = match self.index.get_tx_state_changes(txid) {
+            Ok(state_change) => state_change,
+            Err(IndexError::StoreError(StoreError::NotFound(_))) => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(state_change
+            .rune_transaction_kinds(&self.rune_id, self.premine)
+            .iter()
+            .any(|kind| self.kinds.contains(kind)))
+    }
+}
+
+pub fn rune_transactions(
     index: Arc<Index>,
     rune_query: &query::Rune,
     pagination: Option<Pagination>,
+    order: Order,
+    kinds: &[RuneTransactionKind],
+    address: Option<&Address>,
 ) -> Result<PaginationResponse<Txid>> {
     let rune_id = to_rune_id(rune_query, &index)?;
-    let transactions = index.get_last_rune_transactions(&rune_id, pagination, None)?;
-    Ok(transactions)
+    if kinds.is_empty() && address.is_none() {
+        let transactions = index.get_rune_transactions(&rune_id, pagination, None, order)?;
+        return Ok(transactions);
+    }
+
+    // Scan the transactions in order and apply the pagination to the ones
+    // that match.
+    let pagination = pagination.unwrap_or_default();
+    let filter = RuneTransactionFilter::new(&index, rune_id, kinds, address)?;
+    let mut matched = 0;
+    let mut scanned = 0;
+    let mut items = Vec::new();
+
+    while (items.len() as u64) < pagination.limit {
+        let chunk = index.get_rune_transactions(
+            &rune_id,
+            Some(Pagination {
+                skip: scanned,
+                limit: RUNE_TRANSACTIONS_SCAN_CHUNK,
+            }),
+            None,
+            order,
+        )?;
+        let chunk_len = chunk.items.len() as u64;
+        scanned += chunk_len;
+
+        for txid in chunk.items {
+            if !filter.matches(&txid)? {
+                continue;
+            }
+
+            matched += 1;
+            if matched > pagination.skip {
+                items.push(txid);
+                if items.len() as u64 == pagination.limit {
+                    break;
+                }
+            }
+        }
+
+        if chunk_len < RUNE_TRANSACTIONS_SCAN_CHUNK {
+            break;
+        }
+    }
+
+    Ok(PaginationResponse {
+        offset: pagination.skip + items.len() as u64,
+        items,
+    })
+}
+
+/// Like `rune_transactions`, but continues after `cursor` instead of skipping
+/// entries. Descending pages list the mempool's transactions before the
+/// confirmed ones, ascending pages after them.
+pub fn rune_transactions_after(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    cursor: Option<Cursor>,
+    limit: u64,
+    order: Order,
+    kinds: &[RuneTransactionKind],
+    address: Option<&Address>,
+) -> Result<CursorPage<Txid>> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let filter = RuneTransactionFilter::new(&index, rune_id, kinds, address)?;
+    let chunk_len = if kinds.is_empty() && address.is_none() {
+        limit
+    } else {
+        limit.max(RUNE_TRANSACTIONS_SCAN_CHUNK)
+    };
+
+    let sections = match order {
+        Order::Desc => [true, false],
+        Order::Asc => [false, true],
+    };
+    let first_section = cursor.map_or(0, |cursor| usize::from(cursor.mempool != sections[0]));
+
+    let mut items = Vec::new();
+    for mempool in sections.into_iter().skip(first_section) {
+        if items.len() as u64 >= limit {
+            break;
+        }
+
+        let last_index = index.get_rune_transactions_last_index(&rune_id, mempool)?;
+        let after = match cursor.filter(|cursor| cursor.mempool == mempool) {
+            Some(cursor) => Some(cursor.position_up_to(last_index.saturating_add(1))?),
+            None => None,
+        };
+        let mut indexes: Box<dyn Iterator<Item = u64>> = match order {
+            Order::Desc => {
+                let end = match after {
+                    Some(after) => after.checked_sub(1).ok_or(InvalidCursor)?,
+                    None => last_index,
+                };
+                Box::new((1..=end).rev())
+            }
+            Order::Asc => {
+                let start = match after {
+                    Some(after) => after.checked_add(1).ok_or(InvalidCursor)?,
+                    None => 1,
+                };
+                Box::new(start..=last_index)
+            }
+        };
+
+        loop {
+            let chunk: Vec<u64> = indexes.by_ref().take(chunk_len as usize).collect();
+            if chunk.is_empty() {
+                break;
+            }
+
+            for (position, txid) in index.get_rune_transactions_at(&rune_id, &chunk, mempool)? {
+                if !filter.matches(&txid)? {
+                    continue;
+                }
+
+                items.push(txid);
+                if items.len() as u64 == limit {
+                    return Ok(CursorPage {
+                        items,
+                        next_cursor: Some(Cursor { mempool, position }.to_string()),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(CursorPage {
+        items,
+        next_cursor: None,
+    })
 }
 
 pub fn broadcast_transaction(index: Arc<Index>, client: PooledClient, hex: &str) -> Result<Txid> {
@@ -180,12 +822,220 @@ pub fn bitcoin_transaction_hex(
     Ok(hex::encode(transaction))
 }
 
+/// The runestone or cenotaph in a transaction, deciphered with the same
+/// parser the indexer uses. `None` if the transaction has no runestone.
+pub fn transaction_runestone(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &Txid,
+) -> Result<Option<RunestoneResponse>> {
+    let raw_tx = bitcoin_transaction_raw(index, client, txid)?;
+    let transaction: bitcoin::Transaction = consensus::deserialize(&raw_tx)?;
+
+    Ok(Runestone::decipher(&transaction).map(RunestoneResponse::from))
+}
+
+pub fn transaction_data(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &Txid,
+) -> Result<TransactionData> {
+    let raw_tx = bitcoin_transaction_raw(index, client, txid)?;
+    let transaction: bitcoin::Transaction = consensus::deserialize(&raw_tx)?;
+
+    let envelopes = RawEnvelope::from_transaction(&transaction)
+        .into_iter()
+        .map(|envelope| EnvelopeData {
+            input: envelope.input,
+            offset: envelope.offset,
+            payload: envelope.payload.iter().map(hex::encode).collect(),
+        })
+        .collect();
+
+    let mut runestone_found = false;
+    let op_returns = transaction
+        .output
+        .iter()
+        .enumerate()
+        .filter(|(_, output)| output.script_pubkey.is_op_return())
+        .map(|(vout, output)| {
+            let script = output.script_pubkey.as_bytes();
+            let runestone =
+                !runestone_found && script.get(1) == Some(&opcodes::all::OP_PUSHNUM_13.to_u8());
+            runestone_found |= runestone;
+
+            OpReturnData {
+                vout: vout as u32,
+                data: hex::encode(&script[1..]),
+                runestone,
+            }
+        })
+        .collect();
+
+    Ok(TransactionData {
+        envelopes,
+        op_returns,
+    })
+}
+
 pub fn transaction(index: Arc<Index>, client: PooledClient, txid: &Txid) -> Result<Transaction> {
+    transaction_with_client(&index, &client, txid)
+}
+
+pub fn transaction_by_wtxid(
+    index: Arc<Index>,
+    client: PooledClient,
+    wtxid: &Wtxid,
+) -> Result<Transaction> {
+    let txid = index.get_txid_by_wtxid(wtxid)?;
+    transaction_with_client(&index, &client, &txid)
+}
+
+pub fn decode_transaction(
+    index: Arc<Index>,
+    transaction: bitcoin::Transaction,
+) -> Result<DecodedTransaction> {
+    let artifact = Runestone::decipher(&transaction);
+    let state_change = index.preview_transaction(&transaction)?;
+
+    let mut input_runes: HashMap<RuneId, u128> = HashMap::new();
+    for tx_out in index.get_tx_outs(&state_change.inputs)?.values() {
+        for rune_amount in tx_out.runes.iter().chain(&tx_out.risky_runes) {
+            *input_runes.entry(rune_amount.rune_id).or_default() += rune_amount.amount;
+        }
+    }
+
+    let mut decoded = Transaction::from((transaction, TransactionStatus::unconfirmed()));
+    for (output, tx_out) in decoded.output.iter_mut().zip(state_change.outputs) {
+        output.runes = tx_out.runes;
+        output.risky_runes = tx_out.risky_runes;
+    }
+
+    Ok(DecodedTransaction {
+        transaction: decoded,
+        artifact,
+        input_runes: sorted_rune_amounts(input_runes),
+        minted: state_change.minted,
+        burned: sorted_rune_amounts(
+            state_change
+                .burned
+                .into_iter()
+                .map(|(rune_id, amount)| (rune_id, amount.n())),
+        ),
+    })
+}
+
+/// Asks the node whether it would accept `transaction`, and warns about what
+/// it would do to runes, which the node doesn't know about.
+pub fn test_accept_transaction(
+    index: Arc<Index>,
+    client: PooledClient,
+    transaction: bitcoin::Transaction,
+) -> Result<TestAcceptResponse> {
+    let result = client
+        .test_mempool_accept(&[&transaction])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            bitcoincore_rpc::Error::ReturnedError("testmempoolaccept returned no result".into())
+        })?;
+
+    let artifact = Runestone::decipher(&transaction);
+    let state_change = index.preview_transaction(&transaction)?;
+    let tx_outs = index.get_tx_outs(&state_change.inputs)?;
+
+    let mut rune_warnings = Vec::new();
+    for outpoint in &state_change.inputs {
+        if !tx_outs.contains_key(outpoint) {
+            rune_warnings.push(format!(
+                "input {outpoint} is not indexed, so its runes are unknown"
+            ));
+        }
+    }
+    match &artifact {
+        Some(Artifact::Cenotaph(cenotaph)) => rune_warnings.push(match cenotaph.flaw {
+            Some(flaw) => {
+                format!("runestone is a cenotaph ({flaw}), so all input runes are burned")
+            }
+            None => "runestone is a cenotaph, so all input runes are burned".into(),
+        }),
+        Some(Artifact::Runestone(runestone)) => {
+            if let (Some(rune_id), None) = (runestone.mint, &state_change.minted) {
+                rune_warnings.push(format!("mint of {rune_id} is not possible"));
+            }
+        }
+        None => {}
+    }
+    for burned in sorted_rune_amounts(
+        state_change
+            .burned
+            .iter()
+            .map(|(rune_id, amount)| (*rune_id, amount.n())),
+    ) {
+        rune_warnings.push(format!("{} of {} is burned", burned.amount, burned.rune_id));
+    }
+
+    Ok(TestAcceptResponse {
+        txid: result.txid,
+        allowed: result.allowed,
+        reject_reason: result.reject_reason,
+        vsize: result.vsize,
+        fee: result.fees.map(|fees| fees.base.to_sat()),
+        rune_warnings,
+    })
+}
+
+fn sorted_rune_amounts(amounts: impl IntoIterator<Item = (RuneId, u128)>) -> Vec<RuneAmount> {
+    let mut amounts: Vec<RuneAmount> = amounts
+        .into_iter()
+        .map(|(rune_id, amount)| RuneAmount { rune_id, amount })
+        .collect();
+    amounts.sort_by_key(|rune_amount| rune_amount.rune_id);
+    amounts
+}
+
+pub fn transactions(
+    index: Arc<Index>,
+    client: PooledClient,
+    txids: &[Txid],
+) -> Result<TransactionsResponse> {
+    let mut response = TransactionsResponse::default();
+    let mut seen = HashSet::with_capacity(txids.len());
+
+    for txid in txids {
+        if !seen.insert(*txid) {
+            continue;
+        }
+
+        match transaction_with_client(&index, &client, txid) {
+            Ok(transaction) => {
+                response.transactions.insert(*txid, transaction);
+            }
+            Err(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(_)))) => {
+                response.missing.push(*txid);
+            }
+            Err(ApiError::RpcError(err)) if is_not_found_rpc_error(&err) => {
+                response.missing.push(*txid);
+            }
+            Err(e) => {
+                error!("failed to fetch transaction {}: {}", txid, e);
+                response.errors.insert(*txid, e.to_string());
+            }
+        }
+    }
+
+    Ok(response)
+}
+
+pub fn transaction_with_client(index: &Index, client: &Client, txid: &Txid) -> Result<Transaction> {
     let transaction = if index.is_indexing_bitcoin_transactions() {
         index.get_transaction(txid)?
     } else {
+        // The node first, so a transaction it doesn't know isn't reported as
+        // missing from the index.
+        let raw_transaction = client.get_raw_transaction(txid, None)?;
         let status = index.get_transaction_status(txid)?;
-        let mut transaction = Transaction::from((client.get_raw_transaction(txid, None)?, status));
+        let mut transaction = Transaction::from((raw_transaction, status));
 
         let outpoints = transaction
             .output
@@ -215,12 +1065,122 @@ pub fn transaction(index: Arc<Index>, client: PooledClient, txid: &Txid) -> Resu
     Ok(transaction)
 }
 
-pub fn transaction_status(index: Arc<Index>, txid: &Txid) -> Result<TransactionStatus> {
-    Ok(index.get_transaction_status(txid)?)
+/// `transaction_with_client` with the output spent by each input inlined.
+/// Prevouts are read from their transactions, rune balances from the index.
+/// A prevout that can't be fetched is left out instead of failing the request.
+pub fn transaction_with_prevouts(
+    index: &Index,
+    client: &Client,
+    txid: &Txid,
+    network: Network,
+) -> Result<TransactionWithPrevouts> {
+    let transaction = transaction_with_client(index, client, txid)?;
+
+    let outpoints: Vec<OutPoint> = transaction
+        .input
+        .iter()
+        .map(|tx_in| tx_in.previous_output)
+        .filter(|outpoint| !outpoint.is_null())
+        .collect();
+
+    let mut previous_transactions = HashMap::new();
+    for outpoint in &outpoints {
+        if let Entry::Vacant(entry) = previous_transactions.entry(outpoint.txid) {
+            let previous_transaction = if index.is_indexing_bitcoin_transactions() {
+                index
+                    .get_transaction_raw(&outpoint.txid)
+                    .ok()
+                    .and_then(|raw_tx| consensus::deserialize(&raw_tx).ok())
+            } else {
+                client.get_raw_transaction(&outpoint.txid, None).ok()
+            };
+            entry.insert(previous_transaction);
+        }
+    }
+
+    let tx_outs = index.get_tx_outs(&outpoints)?;
+
+    let prevouts: Vec<Option<Prevout>> = transaction
+        .input
+        .iter()
+        .map(|tx_in| {
+            let outpoint = tx_in.previous_output;
+            let previous_transaction: &bitcoin::Transaction =
+                previous_transactions.get(&outpoint.txid)?.as_ref()?;
+            let output = previous_transaction.output.get(outpoint.vout as usize)?;
+            let tx_out = tx_outs.get(&outpoint);
+
+            Some(Prevout {
+                value: output.value.to_sat(),
+                address: Address::from_script(&output.script_pubkey, network)
+                    .ok()
+                    .map(|address| address.to_string()),
+                script_pubkey: output.script_pubkey.clone(),
+                runes: tx_out
+                    .map(|tx_out| tx_out.runes.clone())
+                    .unwrap_or_default(),
+                risky_runes: tx_out
+                    .map(|tx_out| tx_out.risky_runes.clone())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    let fee = prevouts
+        .iter()
+        .map(|prevout| prevout.as_ref().map(|prevout| prevout.value))
+        .sum::<Option<u64>>()
+        .and_then(|input_value| {
+            input_value.checked_sub(transaction.output.iter().map(|output| output.value).sum())
+        });
+
+    Ok(TransactionWithPrevouts {
+        transaction,
+        prevouts,
+        fee,
+    })
+}
+
+/// Only reads the index, and reports an unknown transaction as unconfirmed and
+/// not in the mempool instead of failing, so polling clients can keep polling.
+pub fn transaction_status(index: Arc<Index>, txid: &Txid) -> Result<TransactionStatusResponse> {
+    let status = match index.get_transaction_status(txid) {
+        Ok(status) => status,
+        Err(IndexError::StoreError(StoreError::NotFound(_))) => TransactionStatus::unconfirmed(),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(TransactionStatusResponse {
+        in_mempool: !status.confirmed && index.is_tx_in_mempool(txid)?,
+        status,
+    })
 }
 
-pub fn mempool_txids(index: Arc<Index>) -> Result<Vec<Txid>> {
-    Ok(index.get_mempool_txids()?)
+/// Mempool txids in the order they were indexed, optionally only those from
+/// sequence number `since_seq` on, and the sequence number to continue from.
+pub fn mempool_txids(
+    index: Arc<Index>,
+    address: Option<&Address>,
+    rune: Option<&query::Rune>,
+    since_seq: Option<u64>,
+) -> Result<(Vec<Txid>, u64)> {
+    let (mut txids, next_seq) = index.get_mempool_txids_since(since_seq.unwrap_or_default())?;
+
+    if let Some(address) = address {
+        let address_txids = index.get_address_txids(address)?;
+        txids.retain(|txid| address_txids.contains(txid));
+    }
+
+    if let Some(rune) = rune {
+        let rune_id = to_rune_id(rune, &index)?;
+        let rune_txids: HashSet<Txid> = index
+            .get_mempool_rune_txids(&rune_id)?
+            .into_iter()
+            .collect();
+        txids.retain(|txid| rune_txids.contains(txid));
+    }
+
+    Ok((txids, next_seq))
 }
 
 pub fn mempool_tx(index: Arc<Index>, txid: &Txid) -> Result<MempoolEntry> {
@@ -238,22 +1198,170 @@ pub fn mempool_entries_all(index: Arc<Index>) -> Result<HashMap<Txid, MempoolEnt
     Ok(index.get_all_mempool_entries()?)
 }
 
-pub fn address(index: Arc<Index>, address: &Address) -> Result<AddressData> {
-    let outpoints = index.get_script_pubkey_outpoints(&address)?;
+pub fn address(
+    index: Arc<Index>,
+    address: &Address,
+    pagination: Pagination,
+) -> Result<AddressData> {
+    let outpoints = index.get_script_pubkey_outpoints(address, pagination)?;
     Ok(outpoints)
 }
 
+pub fn address_balance(index: Arc<Index>, address: &Address) -> Result<AddressBalance> {
+    Ok(index.get_address_balance(address)?)
+}
+
+/// Runes the address holds, ordered by rune id, with the page's rune entries
+/// looked up for their name and divisibility.
+pub fn address_runes(
+    index: Arc<Index>,
+    address: &Address,
+    pagination: Pagination,
+) -> Result<PaginationResponse<AddressRune>> {
+    let balance = index.get_address_balance(address)?;
+    let runes = sorted_rune_amounts(
+        balance
+            .runes
+            .into_iter()
+            .map(|rune_amount| (rune_amount.rune_id, rune_amount.amount)),
+    );
+
+    let items = runes
+        .into_iter()
+        .skip(pagination.skip as usize)
+        .take(pagination.limit as usize)
+        .map(|rune_amount| {
+            let rune_entry = index.get_rune(&rune_amount.rune_id)?;
+            Ok(AddressRune {
+                rune_id: rune_amount.rune_id,
+                name: rune_entry.spaced_rune,
+                symbol: rune_entry.symbol,
+                divisibility: rune_entry.divisibility,
+                amount: rune_amount.amount,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginationResponse {
+        offset: pagination.skip + items.len() as u64,
+        items,
+    })
+}
+
+pub fn address_utxos(
+    index: Arc<Index>,
+    address: &Address,
+    include_mempool: bool,
+    pagination: Pagination,
+) -> Result<PaginationResponse<AddressUtxo>> {
+    Ok(index.get_address_utxos(address, include_mempool, pagination)?)
+}
+
+pub fn script_outputs(
+    index: Arc<Index>,
+    script_pubkey: &ScriptBuf,
+    include_mempool: bool,
+    pagination: Pagination,
+) -> Result<PaginationResponse<AddressUtxo>> {
+    Ok(index.get_script_pubkey_utxos(script_pubkey, include_mempool, pagination)?)
+}
+
 pub fn subscriptions(
     subscription_manager: Arc<WebhookSubscriptionManager>,
 ) -> Result<Vec<Subscription>> {
-    Ok(subscription_manager.get_subscriptions()?)
+    Ok(subscription_manager
+        .get_subscriptions()?
+        .into_iter()
+        .map(without_secret)
+        .collect())
 }
 
+/// Webhook secrets are write-only.
+fn without_secret(mut subscription: Subscription) -> Subscription {
+    subscription.secret = None;
+    subscription
+}
+
+/// Registers `subscription`, unless `max_subscriptions` other ones are already
+/// registered. Replacing an existing subscription always succeeds and keeps its
+/// creation time. A nil id gets a new one. Returns the subscription as stored,
+/// without its secret. A repeated `idempotency_key` returns the first result
+/// instead, unless it came with another subscription.
 pub fn add_subscription(
     subscription_manager: Arc<WebhookSubscriptionManager>,
     subscription: Subscription,
-) -> Result<()> {
-    Ok(subscription_manager.add_subscription(&subscription)?)
+    max_subscriptions: usize,
+    idempotency_key: Option<&str>,
+) -> Result<Subscription> {
+    add_subscriptions(
+        subscription_manager,
+        vec![subscription],
+        max_subscriptions,
+        idempotency_key,
+    )?
+    .pop()
+    .expect("one result per subscription")
+}
+
+/// Registers each subscription like `add_subscription`, in order. No other
+/// request adds one in between, so once the limit is reached every remaining
+/// new subscription fails with `SubscriptionLimit`. A repeated
+/// `idempotency_key` returns the subscriptions the first request added and
+/// retries only the ones that failed.
+pub fn add_subscriptions(
+    subscription_manager: Arc<WebhookSubscriptionManager>,
+    subscriptions: Vec<Subscription>,
+    max_subscriptions: usize,
+    idempotency_key: Option<&str>,
+) -> Result<Vec<Result<Subscription>>> {
+    let add = |subscriptions: Vec<Subscription>| {
+        let _writes = subscription_manager.lock_writes();
+        subscriptions
+            .into_iter()
+            .map(|subscription| {
+                store_subscription(&subscription_manager, subscription, max_subscriptions)
+            })
+            .collect()
+    };
+
+    match idempotency_key {
+        Some(key) => subscription_manager.add_subscriptions_once(key, subscriptions, add),
+        None => Ok(add(subscriptions)),
+    }
+}
+
+fn store_subscription(
+    subscription_manager: &WebhookSubscriptionManager,
+    mut subscription: Subscription,
+    max_subscriptions: usize,
+) -> Result<Subscription> {
+    if subscription.id.is_nil() {
+        subscription.id = Uuid::new_v4();
+    }
+
+    let subscriptions = subscription_manager.get_subscriptions()?;
+    match subscriptions
+        .iter()
+        .find(|existing| existing.id == subscription.id)
+    {
+        Some(existing) => {
+            subscription.created_at_epoch_secs = existing.created_at_epoch_secs;
+            subscription.last_success_epoch_secs = existing.last_success_epoch_secs;
+        }
+        None if subscriptions.len() >= max_subscriptions => {
+            return Err(ApiError::SubscriptionLimit(max_subscriptions));
+        }
+        None => {
+            subscription.created_at_epoch_secs = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            subscription.last_success_epoch_secs = 0;
+        }
+    }
+
+    subscription_manager.add_subscription(&subscription)?;
+    Ok(without_secret(subscription))
 }
 
 pub fn delete_subscription(
@@ -267,5 +1375,5 @@ pub fn get_subscription(
     subscription_manager: Arc<WebhookSubscriptionManager>,
     id: Uuid,
 ) -> Result<Subscription> {
-    Ok(subscription_manager.get_subscription(&id)?)
+    Ok(without_secret(subscription_manager.get_subscription(&id)?))
 }
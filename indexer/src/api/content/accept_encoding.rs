@@ -1,6 +1,6 @@
 use http::HeaderValue;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct AcceptEncoding(pub Option<String>);
 
 impl AcceptEncoding {
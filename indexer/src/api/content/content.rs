@@ -5,14 +5,19 @@ use {
     http::{header, HeaderMap, HeaderValue},
     std::io::Read,
     thiserror::Error,
+    titan_types::InscriptionId,
 };
 
 #[derive(Debug, Error)]
 pub enum ContentError {
     #[error("invalid CSP origin: {0}")]
     InvalidCspOrigin(String),
+    #[error("invalid ETag: {0}")]
+    InvalidETag(String),
     #[error("brotli error: {0}")]
     BrotliError(String),
+    #[error("compression error: {0}")]
+    CompressionError(#[from] std::io::Error),
     #[error("not acceptable")]
     NotAcceptable {
         accept_encoding: AcceptEncoding,
@@ -21,6 +26,7 @@ pub enum ContentError {
 }
 
 pub fn content_response(
+    inscription_id: &InscriptionId,
     inscription: Inscription,
     accept_encoding: AcceptEncoding,
     csp_origin: Option<String>,
@@ -56,6 +62,12 @@ pub fn content_response(
         HeaderValue::from_static("public, max-age=1209600, immutable"),
     );
 
+    // Ranges are of the representation served, encoded or not.
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    // Inscription content never changes, so the id is enough for a strong ETag.
+    headers.insert(header::ETAG, etag(inscription_id, false)?);
+
     headers.insert(
         header::CONTENT_TYPE,
         inscription
@@ -65,6 +77,9 @@ pub fn content_response(
     );
 
     if let Some(content_encoding) = inscription.content_encoding() {
+        // Which representation is served depends on `Accept-Encoding`.
+        headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+
         if accept_encoding.is_acceptable(&content_encoding) {
             headers.insert(header::CONTENT_ENCODING, content_encoding);
         } else if decompress && content_encoding == "br" {
@@ -78,6 +93,11 @@ pub fn content_response(
                 .read_to_end(&mut decompressed)
                 .map_err(|err| ContentError::BrotliError(err.to_string()))?;
 
+            // Decompressed bytes are a different representation and need their own ETag.
+            headers.insert(header::ETAG, etag(inscription_id, true)?);
+            headers.insert(header::CONTENT_LENGTH, decompressed.len().into());
+            headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+
             return Ok(Some((headers, decompressed)));
         } else {
             return Err(ContentError::NotAcceptable {
@@ -91,5 +111,116 @@ pub fn content_response(
         return Ok(None);
     };
 
+    headers.insert(header::CONTENT_LENGTH, body.len().into());
+
     Ok(Some((headers, body)))
 }
+
+fn etag(inscription_id: &InscriptionId, decompressed: bool) -> Result<HeaderValue, ContentError> {
+    let etag = if decompressed {
+        format!("\"{inscription_id}-decompressed\"")
+    } else {
+        format!("\"{inscription_id}\"")
+    };
+
+    HeaderValue::from_str(&etag).map_err(|err| ContentError::InvalidETag(err.to_string()))
+}
+
+/// Bytes a `Range` header asks for out of a body of `len` bytes.
+#[derive(Debug, PartialEq)]
+pub enum ByteRange {
+    /// `start..=end`, with `end` clamped to the last byte.
+    Satisfiable { start: usize, end: usize },
+    /// Starts past the end of the body.
+    Unsatisfiable,
+}
+
+/// Parses a `Range` header with a single `bytes` range. Other units, several
+/// ranges and malformed headers give `None`, and the whole body is served,
+/// which RFC 9110 allows.
+pub fn byte_range(range: &HeaderValue, len: usize) -> Option<ByteRange> {
+    let (start, end) = range
+        .to_str()
+        .ok()?
+        .trim()
+        .strip_prefix("bytes=")?
+        .split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let (start, end) = match (start.is_empty(), end.is_empty()) {
+        // The last `end` bytes.
+        (true, false) => {
+            let suffix = end.parse::<usize>().ok()?;
+            if suffix == 0 || len == 0 {
+                return Some(ByteRange::Unsatisfiable);
+            }
+            (len.saturating_sub(suffix), len - 1)
+        }
+        (false, true) => (start.parse().ok()?, usize::MAX),
+        (false, false) => {
+            let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+            if end < start {
+                return None;
+            }
+            (start, end)
+        }
+        (true, true) => return None,
+    };
+
+    if start >= len {
+        return Some(ByteRange::Unsatisfiable);
+    }
+
+    Some(ByteRange::Satisfiable {
+        start,
+        end: end.min(len - 1),
+    })
+}
+
+/// Returns true if an `If-None-Match` header value matches `etag`.
+pub fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let (Ok(if_none_match), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+        return false;
+    };
+
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_matches_if_none_match() {
+        let etag = HeaderValue::from_static("\"abc\"");
+
+        assert!(etag_matches(&HeaderValue::from_static("\"abc\""), &etag));
+        assert!(etag_matches(&HeaderValue::from_static("W/\"abc\""), &etag));
+        assert!(etag_matches(
+            &HeaderValue::from_static("\"def\", \"abc\""),
+            &etag
+        ));
+        assert!(etag_matches(&HeaderValue::from_static("*"), &etag));
+        assert!(!etag_matches(&HeaderValue::from_static("\"def\""), &etag));
+        assert!(!etag_matches(&HeaderValue::from_static("abc"), &etag));
+    }
+
+    #[test]
+    fn parses_byte_ranges() {
+        let range = |range| byte_range(&HeaderValue::from_static(range), 10);
+        let satisfiable = |start, end| Some(ByteRange::Satisfiable { start, end });
+
+        assert_eq!(range("bytes=2-5"), satisfiable(2, 5));
+        assert_eq!(range("bytes=2-"), satisfiable(2, 9));
+        assert_eq!(range("bytes=5-100"), satisfiable(5, 9));
+        assert_eq!(range("bytes=-3"), satisfiable(7, 9));
+        assert_eq!(range("bytes=-30"), satisfiable(0, 9));
+        assert_eq!(range("bytes=10-"), Some(ByteRange::Unsatisfiable));
+        assert_eq!(range("bytes=-0"), Some(ByteRange::Unsatisfiable));
+        assert_eq!(range("bytes=5-2"), None);
+        assert_eq!(range("bytes=0-1,4-5"), None);
+        assert_eq!(range("items=0-1"), None);
+    }
+}
@@ -1,6 +1,6 @@
 pub use {
     accept_encoding::AcceptEncoding,
-    content::{content_response, ContentError},
+    content::{byte_range, content_response, etag_matches, ByteRange, ContentError},
 };
 
 mod accept_encoding;
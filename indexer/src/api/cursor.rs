@@ -0,0 +1,91 @@
+use {
+    base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine},
+    std::{fmt, str::FromStr},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+#[error("invalid cursor")]
+pub struct InvalidCursor;
+
+/// Position of the last item of a page paginated with `?cursor=`, sent to
+/// clients as an opaque string. `position` is a rune number or the index of a
+/// rune transaction, in the mempool's transactions if `mempool` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub mempool: bool,
+    pub position: u64,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bytes = [0; 9];
+        bytes[0] = self.mempool.into();
+        bytes[1..].copy_from_slice(&self.position.to_be_bytes());
+        f.write_str(&BASE64_URL_SAFE_NO_PAD.encode(bytes))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = InvalidCursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| InvalidCursor)?;
+        let [mempool, position @ ..] = <[u8; 9]>::try_from(bytes).map_err(|_| InvalidCursor)?;
+
+        Ok(Self {
+            mempool: match mempool {
+                0 => false,
+                1 => true,
+                _ => return Err(InvalidCursor),
+            },
+            position: u64::from_be_bytes(position),
+        })
+    }
+}
+
+impl Cursor {
+    /// `position`, if it's between 1 and `max`. Cursors come from clients, so
+    /// one pointing past the items there are is rejected instead of scanned.
+    pub fn position_up_to(self, max: u64) -> Result<u64, InvalidCursor> {
+        if (1..=max).contains(&self.position) {
+            Ok(self.position)
+        } else {
+            Err(InvalidCursor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_strings() {
+        let cursor = Cursor {
+            mempool: true,
+            position: 840_000,
+        };
+        assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+
+        assert!("".parse::<Cursor>().is_err());
+        assert!("AgAAAAAADNBA".parse::<Cursor>().is_err());
+        assert!("not a cursor".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn rejects_positions_out_of_range() {
+        let cursor = |position| Cursor {
+            mempool: false,
+            position,
+        };
+        assert_eq!(cursor(1).position_up_to(10).unwrap(), 1);
+        assert_eq!(cursor(10).position_up_to(10).unwrap(), 10);
+        assert!(cursor(0).position_up_to(10).is_err());
+        assert!(cursor(11).position_up_to(10).is_err());
+        assert!(cursor(u64::MAX).position_up_to(10).is_err());
+        assert!(cursor(u64::MAX).position_up_to(u64::MAX).is_ok());
+    }
+}
@@ -2,5 +2,6 @@ pub use api::*;
 
 mod api;
 pub mod content;
+pub mod cursor;
 pub mod query;
 mod re;
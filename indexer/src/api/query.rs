@@ -1,4 +1,5 @@
 use {
+    super::ApiError,
     crate::index::{Index, IndexError},
     bitcoin::BlockHash,
     ordinals::RuneId,
@@ -6,10 +7,19 @@ use {
     titan_types::query,
 };
 
-pub fn to_hash(block: &query::Block, index: &Arc<Index>) -> Result<BlockHash, IndexError> {
+pub fn to_hash(block: &query::Block, index: &Arc<Index>) -> Result<BlockHash, ApiError> {
     match block {
-        query::Block::Height(height) => index.get_block_hash(*height),
+        query::Block::Height(height) => Ok(index.get_block_hash(*height)?),
         query::Block::Hash(hash) => Ok(*hash),
+        query::Block::FromTip(offset) => {
+            let block_count = index.get_block_count()?;
+            let height = block_count.checked_sub(*offset).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "block -{offset} is before genesis, there are only {block_count} blocks"
+                ))
+            })?;
+            Ok(index.get_block_hash(height)?)
+        }
     }
 }
 
@@ -4,4 +4,4 @@ mod result;
 
 pub use pool::{PooledClient, RpcClientPool, RpcClientPoolError};
 pub use provider::{validate_rpc_connection, RpcClientError, RpcClientProvider};
-pub use result::BitcoinCoreRpcResultExt;
+pub use result::{is_not_found_rpc_error, is_transient_rpc_error, BitcoinCoreRpcResultExt};
@@ -82,6 +82,15 @@ impl RpcClientPool {
         })
     }
 
+    /// A newly created client instead of an idle one from the pool, e.g. to
+    /// retry after the pooled client's connection failed.
+    pub fn get_new(&self) -> Result<PooledClient, RpcClientPoolError> {
+        Ok(PooledClient {
+            client: Some(self.provider.get_new_rpc_client()?),
+            pool: Arc::new(self.clone()),
+        })
+    }
+
     fn release(&self, client: Client) -> Result<(), RpcClientPoolError> {
         let mut clients = self
             .clients
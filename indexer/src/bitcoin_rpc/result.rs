@@ -1,4 +1,3 @@
-
 use {
     bitcoincore_rpc::{Client, RpcApi},
     std::{thread, time::Duration},
@@ -13,18 +12,93 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
     fn into_option(self) -> Result<Option<T>, bitcoincore_rpc::Error> {
         match self {
             Ok(ok) => Ok(Some(ok)),
-            Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
-                bitcoincore_rpc::jsonrpc::error::RpcError { code: -8, .. },
-            ))) => Ok(None),
-            Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
-                bitcoincore_rpc::jsonrpc::error::RpcError {
-                    code: -5, message, ..
-                },
-            ))) if message.starts_with("No such mempool or blockchain transaction") => Ok(None),
-            Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
-                bitcoincore_rpc::jsonrpc::error::RpcError { message, .. },
-            ))) if message.ends_with("not found") => Ok(None),
-            Err(err) => Err(err.into()),
+            Err(err) if is_not_found_rpc_error(&err) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 }
+
+/// Whether the node answered that what `error` was requested for doesn't
+/// exist.
+pub fn is_not_found_rpc_error(error: &bitcoincore_rpc::Error) -> bool {
+    match error {
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+            bitcoincore_rpc::jsonrpc::error::RpcError { code: -8, .. },
+        )) => true,
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+            bitcoincore_rpc::jsonrpc::error::RpcError {
+                code: -5, message, ..
+            },
+        )) if message.starts_with("No such mempool or blockchain transaction") => true,
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+            bitcoincore_rpc::jsonrpc::error::RpcError { message, .. },
+        )) => message.ends_with("not found"),
+        _ => false,
+    }
+}
+
+/// Whether `error` came from failing to reach the node, or from the node still
+/// warming up, rather than from the request itself. Such requests can be
+/// retried.
+pub fn is_transient_rpc_error(error: &bitcoincore_rpc::Error) -> bool {
+    match error {
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Transport(_))
+        | bitcoincore_rpc::Error::Io(_) => true,
+        // RPC_IN_WARMUP
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::error::Error::Rpc(
+            bitcoincore_rpc::jsonrpc::error::RpcError { code: -28, .. },
+        )) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoincore_rpc::jsonrpc::error::{Error as JsonRpcError, RpcError},
+    };
+
+    fn rpc_error(code: i32, message: &str) -> bitcoincore_rpc::Error {
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }))
+    }
+
+    #[test]
+    fn only_warmup_and_connection_errors_are_transient() {
+        assert!(is_transient_rpc_error(&rpc_error(
+            -28,
+            "Loading block index..."
+        )));
+        assert!(is_transient_rpc_error(&bitcoincore_rpc::Error::Io(
+            std::io::ErrorKind::ConnectionRefused.into()
+        )));
+        assert!(!is_transient_rpc_error(&rpc_error(
+            -5,
+            "No such mempool or blockchain transaction"
+        )));
+    }
+
+    #[test]
+    fn recognizes_missing_transactions() {
+        assert!(is_not_found_rpc_error(&rpc_error(
+            -5,
+            "No such mempool or blockchain transaction. Use gettransaction for wallet transactions."
+        )));
+        assert!(is_not_found_rpc_error(&rpc_error(
+            -8,
+            "Block height out of range"
+        )));
+        assert!(!is_not_found_rpc_error(&rpc_error(
+            -5,
+            "Invalid or non-wallet transaction id"
+        )));
+        assert!(!is_not_found_rpc_error(&rpc_error(
+            -28,
+            "Loading block index..."
+        )));
+    }
+}
@@ -1,5 +1,8 @@
 use {
-    crate::models::{BlockId, Inscription, RuneEntry, TransactionStateChange, TxRuneIndexRef},
+    crate::models::{
+        BlockId, Inscription, OutputInscription, RuneEntry, SatRange, TransactionStateChange,
+        TxRuneIndexRef,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
     titan_types::{Block, MempoolEntry, SpenderReference, Subscription, TxOutEntry},
 };
@@ -19,6 +22,8 @@ pub trait Entry: Sized + BorshDeserialize + BorshSerialize {
 impl Entry for Block {}
 impl Entry for BlockId {}
 impl Entry for Inscription {}
+impl Entry for Vec<OutputInscription> {}
+impl Entry for Vec<SatRange> {}
 impl Entry for RuneEntry {}
 impl Entry for TxRuneIndexRef {}
 impl Entry for Vec<TxRuneIndexRef> {}
@@ -18,10 +18,16 @@ pub enum RocksDBError {
     InvalidTxid,
     #[error("invalid outpoint")]
     InvalidOutpoint,
+    #[error("invalid inscription id")]
+    InvalidInscriptionId,
+    #[error("invalid rune order key")]
+    InvalidRuneOrderKey,
     #[error("poisoned lock")]
     LockPoisoned,
     #[error("not found: {0}")]
     NotFound(String),
     #[error("overflow")]
     Overflow,
+    #[error("io error: {0}")]
+    Io(String),
 }
@@ -0,0 +1,87 @@
+use {
+    bitcoin::Txid,
+    std::{
+        collections::{BTreeMap, HashMap},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Order in which transactions entered the indexed mempool, for fetching the
+/// ones added since a previous poll. Kept in memory only. Sequence numbers
+/// start at the startup time in microseconds, so a cursor from before a
+/// restart is lower than every current one and gets the whole mempool.
+pub(super) struct MempoolSeq {
+    next: u64,
+    by_txid: HashMap<Txid, u64>,
+    by_seq: BTreeMap<u64, Txid>,
+}
+
+impl MempoolSeq {
+    pub(super) fn new(txids: impl IntoIterator<Item = Txid>) -> Self {
+        let start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let mut mempool_seq = Self::starting_at(start);
+        for txid in txids {
+            mempool_seq.insert(txid);
+        }
+        mempool_seq
+    }
+
+    fn starting_at(start: u64) -> Self {
+        Self {
+            next: start,
+            by_txid: HashMap::new(),
+            by_seq: BTreeMap::new(),
+        }
+    }
+
+    /// Keeps the sequence number of a transaction that's already known.
+    pub(super) fn insert(&mut self, txid: Txid) {
+        if self.by_txid.contains_key(&txid) {
+            return;
+        }
+        self.by_txid.insert(txid, self.next);
+        self.by_seq.insert(self.next, txid);
+        self.next += 1;
+    }
+
+    pub(super) fn remove(&mut self, txid: &Txid) {
+        if let Some(seq) = self.by_txid.remove(txid) {
+            self.by_seq.remove(&seq);
+        }
+    }
+
+    /// Transactions added at or after `seq` that are still in the mempool,
+    /// oldest first, and the cursor to pass next time.
+    pub(super) fn since(&self, seq: u64) -> (Vec<Txid>, u64) {
+        (
+            self.by_seq.range(seq..).map(|(_, txid)| *txid).collect(),
+            self.next,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash};
+
+    #[test]
+    fn returns_transactions_added_since_the_cursor() {
+        let txid = |n| Txid::from_byte_array([n; 32]);
+
+        let mut mempool_seq = MempoolSeq::starting_at(100);
+        mempool_seq.insert(txid(1));
+        mempool_seq.insert(txid(2));
+        let (txids, cursor) = mempool_seq.since(0);
+        assert_eq!(txids, vec![txid(1), txid(2)]);
+        assert_eq!(cursor, 102);
+
+        mempool_seq.insert(txid(1));
+        mempool_seq.insert(txid(3));
+        mempool_seq.remove(&txid(2));
+        assert_eq!(mempool_seq.since(cursor), (vec![txid(3)], 103));
+        assert_eq!(mempool_seq.since(0).0, vec![txid(1), txid(3)]);
+    }
+}
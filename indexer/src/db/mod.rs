@@ -3,6 +3,7 @@ pub use {error::RocksDBError, rocks::RocksDB};
 mod entry;
 mod error;
 mod mapper;
+mod mempool_seq;
 mod rocks;
 mod util;
 mod wrapper;
@@ -3,33 +3,37 @@ use {
         entry::Entry,
         util::{
             parse_outpoint_from_script_pubkey_key, rune_id_from_bytes, rune_index_key,
-            rune_transaction_key, script_pubkey_outpoint_to_bytes, script_pubkey_search_key,
+            rune_number_from_order_key, rune_order_key, rune_transaction_key,
+            script_pubkey_outpoint_to_bytes, script_pubkey_search_key,
         },
         *,
     },
     crate::models::{
-        BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
-        TransactionStateChange, TxRuneIndexRef,
+        BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, OutputInscription,
+        RuneEntry, SatRange, TransactionStateChange, TxRuneIndexRef,
     },
-    bitcoin::{consensus, hashes::Hash, BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
+    bitcoin::{consensus, hashes::Hash, BlockHash, OutPoint, ScriptBuf, Transaction, Txid, Wtxid},
     borsh::BorshDeserialize,
     mapper::DBResultMapper,
+    mempool_seq::MempoolSeq,
     ordinals::RuneId,
     rocksdb::{
-        BlockBasedOptions, BoundColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction,
-        IteratorMode, MultiThreaded, Options, WriteBatch,
+        properties, BlockBasedOptions, BoundColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode,
+        Direction, IteratorMode, MultiThreaded, Options, WriteBatch,
     },
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         sync::{Arc, RwLock},
     },
     titan_types::{
-        Block, InscriptionId, MempoolEntry, Pagination, PaginationResponse, SpenderReference,
-        Subscription, TxOutEntry,
+        Block, ColumnFamilyStats, InscriptionId, MempoolEntry, Order, Pagination,
+        PaginationResponse, RuneSort, SpenderReference, Subscription, TxOutEntry,
     },
+    tracing::info,
     util::{
-        inscription_id_to_bytes, outpoint_to_bytes, rune_id_to_bytes, txid_from_bytes,
-        txid_to_bytes,
+        inscription_child_key, inscription_id_from_bytes, inscription_id_to_bytes,
+        outpoint_from_bytes, outpoint_to_bytes, rune_id_to_bytes, sat_inscription_key,
+        txid_from_bytes, txid_to_bytes, wtxid_to_bytes,
     },
     uuid::Uuid,
     wrapper::RuneIdWrapper,
@@ -38,6 +42,7 @@ use {
 pub struct RocksDB {
     db: DBWithThreadMode<MultiThreaded>,
     mempool_cache: RwLock<HashMap<Txid, MempoolEntry>>,
+    mempool_seq: RwLock<MempoolSeq>,
 }
 
 pub type DBResult<T> = Result<T, RocksDBError>;
@@ -61,8 +66,19 @@ const RUNES_COUNT_KEY: &str = "runes_count";
 const RUNES_CF: &str = "runes";
 const RUNE_IDS_CF: &str = "rune_ids";
 const RUNE_NUMBER_CF: &str = "rune_number";
+const RUNES_BY_SUPPLY_CF: &str = "runes_by_supply";
+const RUNES_BY_ACTIVITY_CF: &str = "runes_by_activity";
+const RUNE_ACTIVITY_CF: &str = "rune_activity";
 
 const INSCRIPTIONS_CF: &str = "inscriptions";
+// Keyed by parent id followed by child id, with empty values.
+const INSCRIPTION_CHILDREN_CF: &str = "inscription_children";
+const OUTPUT_INSCRIPTIONS_CF: &str = "output_inscriptions";
+
+const SAT_RANGES_CF: &str = "sat_ranges";
+// Keyed by sat followed by inscription id, with empty values.
+const SAT_INSCRIPTIONS_CF: &str = "sat_inscriptions";
+const INSCRIPTION_SATS_CF: &str = "inscription_sats";
 
 const SCRIPT_PUBKEYS_CF: &str = "script_pubkeys";
 const SCRIPT_PUBKEYS_MEMPOOL_CF: &str = "script_pubkeys_mempool";
@@ -74,6 +90,9 @@ const SPENT_OUTPOINTS_MEMPOOL_CF: &str = "spent_outpoints_mempool";
 const TRANSACTIONS_CF: &str = "transactions";
 const TRANSACTIONS_MEMPOOL_CF: &str = "transactions_mempool";
 const TRANSACTION_CONFIRMING_BLOCK_CF: &str = "transaction_confirming_block";
+// Only segwit transactions are mapped, for the others the wtxid is the txid.
+const WTXIDS_CF: &str = "wtxids";
+const WTXIDS_MEMPOOL_CF: &str = "wtxids_mempool";
 
 const MEMPOOL_CF: &str = "mempool";
 
@@ -83,8 +102,48 @@ const SETTINGS_CF: &str = "settings";
 
 const SUBSCRIPTIONS_CF: &str = "subscriptions";
 
+const COLUMN_FAMILIES: [&str; 36] = [
+    BLOCKS_CF,
+    BLOCK_HEIGHT_TO_HASH_CF,
+    OUTPOINTS_CF,
+    OUTPOINTS_MEMPOOL_CF,
+    TRANSACTIONS_STATE_CHANGE_CF,
+    TRANSACTIONS_STATE_CHANGE_MEMPOOL_CF,
+    RUNE_TRANSACTIONS_CF,
+    RUNE_TRANSACTIONS_MEMPOOL_CF,
+    TRANSACTION_RUNE_INDEX_CF,
+    TRANSACTION_RUNE_INDEX_MEMPOOL_CF,
+    RUNES_CF,
+    RUNE_IDS_CF,
+    RUNE_NUMBER_CF,
+    RUNES_BY_SUPPLY_CF,
+    RUNES_BY_ACTIVITY_CF,
+    RUNE_ACTIVITY_CF,
+    INSCRIPTIONS_CF,
+    INSCRIPTION_CHILDREN_CF,
+    OUTPUT_INSCRIPTIONS_CF,
+    SAT_RANGES_CF,
+    SAT_INSCRIPTIONS_CF,
+    INSCRIPTION_SATS_CF,
+    SCRIPT_PUBKEYS_CF,
+    SCRIPT_PUBKEYS_MEMPOOL_CF,
+    OUTPOINT_TO_SCRIPT_PUBKEY_CF,
+    OUTPOINT_TO_SCRIPT_PUBKEY_MEMPOOL_CF,
+    SPENT_OUTPOINTS_MEMPOOL_CF,
+    TRANSACTIONS_CF,
+    TRANSACTIONS_MEMPOOL_CF,
+    TRANSACTION_CONFIRMING_BLOCK_CF,
+    WTXIDS_CF,
+    WTXIDS_MEMPOOL_CF,
+    MEMPOOL_CF,
+    STATS_CF,
+    SETTINGS_CF,
+    SUBSCRIPTIONS_CF,
+];
+
 const INDEX_ADDRESSES_KEY: &str = "index_addresses";
 const INDEX_BITCOIN_TRANSACTIONS_KEY: &str = "index_bitcoin_transactions";
+const INDEX_SATS_KEY: &str = "index_sats";
 
 const BLOCK_COUNT_KEY: &str = "block_count";
 const PURGED_BLOCKS_COUNT_KEY: &str = "purged_blocks_count";
@@ -113,8 +172,24 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(RUNE_IDS_CF, cf_opts.clone());
         let rune_number_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(RUNE_NUMBER_CF, cf_opts.clone());
+        let runes_by_supply_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNES_BY_SUPPLY_CF, cf_opts.clone());
+        let runes_by_activity_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNES_BY_ACTIVITY_CF, cf_opts.clone());
+        let rune_activity_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_ACTIVITY_CF, cf_opts.clone());
         let inscriptions_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(INSCRIPTIONS_CF, cf_opts.clone());
+        let inscription_children_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(INSCRIPTION_CHILDREN_CF, cf_opts.clone());
+        let output_inscriptions_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(OUTPUT_INSCRIPTIONS_CF, cf_opts.clone());
+        let sat_ranges_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(SAT_RANGES_CF, cf_opts.clone());
+        let sat_inscriptions_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(SAT_INSCRIPTIONS_CF, cf_opts.clone());
+        let inscription_sats_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(INSCRIPTION_SATS_CF, cf_opts.clone());
         let mempool_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(MEMPOOL_CF, cf_opts.clone());
         let stats_cfd: ColumnFamilyDescriptor =
@@ -143,6 +218,10 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(TRANSACTIONS_MEMPOOL_CF, cf_opts.clone());
         let transaction_confirming_block_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(TRANSACTION_CONFIRMING_BLOCK_CF, cf_opts.clone());
+        let wtxids_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(WTXIDS_CF, cf_opts.clone());
+        let wtxids_mempool_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(WTXIDS_MEMPOOL_CF, cf_opts.clone());
         let settings_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(SETTINGS_CF, cf_opts.clone());
         let subscriptions_cfd: ColumnFamilyDescriptor =
@@ -178,7 +257,15 @@ impl RocksDB {
                 runes_cfd,
                 rune_ids_cfd,
                 rune_number_cfd,
+                runes_by_supply_cfd,
+                runes_by_activity_cfd,
+                rune_activity_cfd,
                 inscriptions_cfd,
+                inscription_children_cfd,
+                output_inscriptions_cfd,
+                sat_ranges_cfd,
+                sat_inscriptions_cfd,
+                inscription_sats_cfd,
                 mempool_cfd,
                 stats_cfd,
                 rune_transactions_cfd,
@@ -193,6 +280,8 @@ impl RocksDB {
                 transactions_cfd,
                 transactions_mempool_cfd,
                 transaction_confirming_block_cfd,
+                wtxids_cfd,
+                wtxids_mempool_cfd,
                 settings_cfd,
                 subscriptions_cfd,
             ],
@@ -201,13 +290,156 @@ impl RocksDB {
         // Load initial state from DB
         let mempool_cache = Self::read_all_mempool_txids(&descriptors)?;
 
+        let mempool_seq = MempoolSeq::new(mempool_cache.keys().copied());
+
         let rocks_db = RocksDB {
             db: descriptors,
             mempool_cache: RwLock::new(mempool_cache),
+            mempool_seq: RwLock::new(mempool_seq),
         };
+
+        if rocks_db.get_runes_count()? > 0 && rocks_db.is_empty(RUNES_BY_SUPPLY_CF)? {
+            rocks_db.index_rune_orders()?;
+        }
+
         Ok(rocks_db)
     }
 
+    fn is_empty(&self, name: &str) -> DBResult<bool> {
+        let cf_handle = self.cf_handle(name)?;
+        Ok(self
+            .db
+            .iterator_cf(&cf_handle, IteratorMode::Start)
+            .next()
+            .transpose()?
+            .is_none())
+    }
+
+    /// Fills the supply and activity orderings of runes indexed before they
+    /// existed.
+    fn index_rune_orders(&self) -> DBResult<()> {
+        info!("Indexing rune supply and activity orderings");
+
+        let runes_cf_handle = self.cf_handle(RUNES_CF)?;
+        let supply_cf_handle = self.cf_handle(RUNES_BY_SUPPLY_CF)?;
+        let by_activity_cf_handle = self.cf_handle(RUNES_BY_ACTIVITY_CF)?;
+        let activity_cf_handle = self.cf_handle(RUNE_ACTIVITY_CF)?;
+
+        let mut batch = WriteBatch::default();
+        for item in self.db.iterator_cf(&runes_cf_handle, IteratorMode::Start) {
+            let (key, value) = item?;
+            let rune_id = rune_id_from_bytes(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+            let rune_entry = RuneEntry::load(value.to_vec());
+
+            batch.put_cf(
+                &supply_cf_handle,
+                rune_order_key(rune_entry.supply(), rune_entry.number),
+                [],
+            );
+
+            if let Some(height) = self.get_last_rune_transaction_height(&rune_id)? {
+                batch.put_cf(
+                    &by_activity_cf_handle,
+                    rune_order_key(height.into(), rune_entry.number),
+                    [],
+                );
+                batch.put_cf(
+                    &activity_cf_handle,
+                    rune_id_to_bytes(&rune_id),
+                    height.to_le_bytes(),
+                );
+            }
+
+            if batch.len() >= 30_000 {
+                self.db.write(std::mem::take(&mut batch))?;
+            }
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Confirming height of the latest confirmed transaction of `rune_id`
+    /// that's still indexed.
+    fn get_last_rune_transaction_height(&self, rune_id: &RuneId) -> DBResult<Option<u64>> {
+        let mut end = self.get_rune_transactions_last_index(rune_id, false)?;
+        while end > 0 {
+            let start = end.saturating_sub(16).max(1);
+            let indexes: Vec<u64> = (start..=end).rev().collect();
+            if let Some((_, txid)) = self
+                .get_rune_transactions_at(rune_id, &indexes, false)?
+                .first()
+            {
+                return match self.get_transaction_confirming_block(txid) {
+                    Ok(block_id) => Ok(Some(block_id.height)),
+                    Err(RocksDBError::NotFound(_)) => Ok(None),
+                    Err(err) => Err(err),
+                };
+            }
+            end = start - 1;
+        }
+
+        Ok(None)
+    }
+
+    fn get_rune_activity(&self, rune_id: &RuneId) -> DBResult<Option<u64>> {
+        let cf_handle = self.cf_handle(RUNE_ACTIVITY_CF)?;
+        self.get_option_vec_data(&cf_handle, rune_id_to_bytes(rune_id))
+            .mapped()
+    }
+
+    /// Numbers of the runes in the ordering of `sort`.
+    pub fn get_rune_numbers_sorted(
+        &self,
+        sort: RuneSort,
+        order: Order,
+        pagination: Pagination,
+    ) -> DBResult<PaginationResponse<u64>> {
+        let cf_handle = match sort {
+            // Runes are numbered in etching order.
+            RuneSort::Etching => {
+                let (skip, limit) = pagination.into();
+                let runes_count = self.get_runes_count()?;
+                let start = skip.min(runes_count);
+                let end = skip.saturating_add(limit).min(runes_count);
+                let numbers: Vec<u64> = match order {
+                    Order::Asc => (start..end).collect(),
+                    Order::Desc => (runes_count - end..runes_count - start).rev().collect(),
+                };
+
+                return Ok(PaginationResponse {
+                    offset: skip + numbers.len() as u64,
+                    items: numbers,
+                });
+            }
+            RuneSort::Supply => self.cf_handle(RUNES_BY_SUPPLY_CF)?,
+            RuneSort::Activity => self.cf_handle(RUNES_BY_ACTIVITY_CF)?,
+        };
+        let mode = match order {
+            Order::Asc => IteratorMode::Start,
+            Order::Desc => IteratorMode::End,
+        };
+
+        let (skip, limit) = pagination.into();
+        let mut numbers = Vec::new();
+        for item in self
+            .db
+            .iterator_cf(&cf_handle, mode)
+            .skip(skip as usize)
+            .take(limit as usize)
+        {
+            let (key, _) = item?;
+            numbers.push(
+                rune_number_from_order_key(&key).map_err(|_| RocksDBError::InvalidRuneOrderKey)?,
+            );
+        }
+
+        Ok(PaginationResponse {
+            offset: skip + numbers.len() as u64,
+            items: numbers,
+        })
+    }
+
     fn cf_handle(&self, name: &str) -> DBResult<Arc<BoundColumnFamily>> {
         match self.db.cf_handle(name) {
             None => Err(RocksDBError::InvalidHandle(name.to_string())),
@@ -264,6 +496,22 @@ impl RocksDB {
         Ok(())
     }
 
+    pub fn is_index_sats(&self) -> DBResult<Option<bool>> {
+        let cf_handle = self.cf_handle(SETTINGS_CF)?;
+        let val: Option<u64> = self
+            .get_option_vec_data(&cf_handle, INDEX_SATS_KEY)
+            .mapped()?;
+
+        Ok(val.map(|v| v == 1))
+    }
+
+    pub fn set_index_sats(&self, value: bool) -> DBResult<()> {
+        let cf_handle = self.cf_handle(SETTINGS_CF)?;
+        self.db
+            .put_cf(&cf_handle, INDEX_SATS_KEY, (value as u64).to_le_bytes())?;
+        Ok(())
+    }
+
     pub fn get_block_count(&self) -> DBResult<u64> {
         let cf_handle = self.cf_handle(STATS_CF)?;
         Ok(self
@@ -349,6 +597,37 @@ impl RocksDB {
         Ok(result)
     }
 
+    /// Scans every rune and returns the ones whose name (without spacers) starts
+    /// with or contains `name`. Prefix matches come first.
+    pub fn search_runes(&self, name: &str, limit: usize) -> DBResult<Vec<(RuneId, RuneEntry)>> {
+        let cf_handle = self.cf_handle(RUNES_CF)?;
+        let iter = self.db.iterator_cf(&cf_handle, IteratorMode::Start);
+
+        let mut prefix_matches = Vec::new();
+        let mut contains_matches = Vec::new();
+        for item in iter {
+            if prefix_matches.len() >= limit {
+                break;
+            }
+
+            let (key, value) = item?;
+            let rune_entry = RuneEntry::load(value.to_vec());
+            let rune_name = rune_entry.spaced_rune.rune.to_string();
+
+            if rune_name.starts_with(name) {
+                let rune_id = rune_id_from_bytes(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+                prefix_matches.push((rune_id, rune_entry));
+            } else if contains_matches.len() < limit && rune_name.contains(name) {
+                let rune_id = rune_id_from_bytes(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+                contains_matches.push((rune_id, rune_entry));
+            }
+        }
+
+        prefix_matches.extend(contains_matches);
+        prefix_matches.truncate(limit);
+        Ok(prefix_matches)
+    }
+
     pub fn get_rune_id_by_number(&self, number: u64) -> DBResult<RuneId> {
         let cf_handle = self.cf_handle(RUNE_NUMBER_CF)?;
         let rune_id_wrapper: RuneIdWrapper = self
@@ -422,6 +701,41 @@ impl RocksDB {
         }
     }
 
+    /// Returns every stored output of the given transactions.
+    pub fn get_tx_outs_by_txids(
+        &self,
+        txids: &[Txid],
+        mempool: bool,
+    ) -> DBResult<HashMap<OutPoint, TxOutEntry>> {
+        let cf_handle = if mempool {
+            self.cf_handle(OUTPOINTS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(OUTPOINTS_CF)?
+        };
+
+        let mut result = HashMap::new();
+        for txid in txids {
+            // Outpoint keys are prefixed by the txid bytes.
+            let prefix = txid_to_bytes(txid);
+            let iter = self
+                .db
+                .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward));
+
+            for item in iter {
+                let (key, value) = item?;
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+
+                let outpoint =
+                    outpoint_from_bytes(&key).map_err(|_| RocksDBError::InvalidOutpoint)?;
+                result.insert(outpoint, TxOutEntry::load(value.to_vec()));
+            }
+        }
+
+        Ok(result)
+    }
+
     fn get_tx_outs_with_mempool(
         &self,
         outpoints: &Vec<OutPoint>,
@@ -532,11 +846,106 @@ impl RocksDB {
         Ok(inscription)
     }
 
-    pub fn get_last_rune_transactions(
+    pub fn get_inscription_children(
+        &self,
+        parent: &InscriptionId,
+        pagination: Pagination,
+    ) -> DBResult<PaginationResponse<InscriptionId>> {
+        let cf_handle = self.cf_handle(INSCRIPTION_CHILDREN_CF)?;
+        let prefix = inscription_id_to_bytes(parent);
+        let iter = self
+            .db
+            .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward));
+
+        let mut children = Vec::new();
+        for item in iter.skip(pagination.skip as usize) {
+            let (key, _) = item?;
+            if !key.starts_with(&prefix) || children.len() as u64 >= pagination.limit {
+                break;
+            }
+
+            children.push(
+                inscription_id_from_bytes(&key[prefix.len()..])
+                    .map_err(|_| RocksDBError::InvalidInscriptionId)?,
+            );
+        }
+
+        Ok(PaginationResponse {
+            offset: pagination.skip + children.len() as u64,
+            items: children,
+        })
+    }
+
+    /// The inscriptions on each of `outpoints` that holds any, by offset.
+    pub fn get_output_inscriptions(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> DBResult<HashMap<OutPoint, Vec<OutputInscription>>> {
+        let cf_handle = self.cf_handle(OUTPUT_INSCRIPTIONS_CF)?;
+        let keys = outpoints
+            .iter()
+            .map(|outpoint| (&cf_handle, outpoint_to_bytes(outpoint)));
+
+        let mut result = HashMap::new();
+        for (outpoint, value) in outpoints.iter().zip(self.db.multi_get_cf(keys)) {
+            if let Some(value) = value? {
+                result.insert(*outpoint, Vec::<OutputInscription>::load(value));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The sat ranges on each of `outpoints` that has any, in order.
+    pub fn get_sat_ranges(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> DBResult<HashMap<OutPoint, Vec<SatRange>>> {
+        let cf_handle = self.cf_handle(SAT_RANGES_CF)?;
+        let keys = outpoints
+            .iter()
+            .map(|outpoint| (&cf_handle, outpoint_to_bytes(outpoint)));
+
+        let mut result = HashMap::new();
+        for (outpoint, value) in outpoints.iter().zip(self.db.multi_get_cf(keys)) {
+            if let Some(value) = value? {
+                result.insert(*outpoint, Vec::<SatRange>::load(value));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The inscriptions whose genesis sat is `sat`.
+    pub fn get_sat_inscriptions(&self, sat: u64) -> DBResult<Vec<InscriptionId>> {
+        let cf_handle = self.cf_handle(SAT_INSCRIPTIONS_CF)?;
+        let prefix = sat.to_be_bytes();
+        let iter = self
+            .db
+            .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward));
+
+        let mut inscriptions = Vec::new();
+        for item in iter {
+            let (key, _) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            inscriptions.push(
+                inscription_id_from_bytes(&key[prefix.len()..])
+                    .map_err(|_| RocksDBError::InvalidInscriptionId)?,
+            );
+        }
+
+        Ok(inscriptions)
+    }
+
+    pub fn get_rune_transactions(
         &self,
         rune_id: &RuneId,
         pagination: Option<Pagination>,
         mempool: bool,
+        order: Order,
     ) -> DBResult<PaginationResponse<Txid>> {
         let cf_handle = if mempool {
             self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
@@ -561,9 +970,7 @@ impl RocksDB {
 
         let (skip, limit) = pagination.unwrap_or_default().into();
 
-        // 2. Compute the relevant range in [start_index ..= end_index], descending
-        let end_index = last_index.saturating_sub(skip);
-        if end_index == 0 {
+        if skip >= last_index {
             // skip is too large, no items
             let offset = skip - last_index;
             return Ok(PaginationResponse {
@@ -572,75 +979,88 @@ impl RocksDB {
             });
         }
 
-        let start_index = end_index.saturating_sub(limit - 1);
-        // If limit > end_index, start_index could go below 1, so clamp to 1 for safety
-        // but actually we can do 0 for convenience, because we won't find negative keys
-        // anyway.
-        // We'll do it cleanly:
-        let start_index = if start_index < 1 { 1 } else { start_index };
-
-        let prefix_bytes = {
-            let rune_id_bytes = rune_id_to_bytes(rune_id);
-            // "rune:<rune_id>:"
-            let mut p = Vec::with_capacity(5 + rune_id_bytes.len() + 1);
-            p.extend_from_slice(b"rune:");
-            p.extend_from_slice(&rune_id_bytes);
-            p.push(b':');
-            p
+        // 2. Compute the relevant range in [start_index ..= end_index]. Descending pages
+        // count back from the last index, ascending pages count forward from the first one.
+        let (start_index, end_index) = match order {
+            Order::Desc => {
+                let end_index = last_index - skip;
+                let start_index = end_index.saturating_sub(limit.saturating_sub(1)).max(1);
+                (start_index, end_index)
+            }
+            Order::Asc => {
+                let start_index = skip + 1;
+                let end_index = start_index
+                    .saturating_add(limit.saturating_sub(1))
+                    .min(last_index);
+                (start_index, end_index)
+            }
         };
 
-        // 3. Construct the 'seek_key' for end_index
-        let seek_key = rune_transaction_key(rune_id, end_index);
-
-        // 4. Reverse iterate
-        let mut iter = self.db.iterator_cf(
-            &cf_handle,
-            IteratorMode::From(&seek_key, Direction::Reverse),
-        );
+        // 3. Read the range by key. Indexes are stored little-endian, so
+        // iterating the column family wouldn't visit them in numeric order.
+        let indexes: Vec<u64> = match order {
+            Order::Desc => (start_index..=end_index).rev().collect(),
+            Order::Asc => (start_index..=end_index).collect(),
+        };
+        let results: Vec<Txid> = self
+            .get_rune_transactions_at(rune_id, &indexes, mempool)?
+            .into_iter()
+            .map(|(_, txid)| txid)
+            .collect();
 
-        let mut results = Vec::new();
+        let offset = skip + results.len() as u64;
 
-        while let Some(Ok((key_bytes, value_bytes))) = iter.next() {
-            // Check prefix: if key_bytes doesn't start with prefix_bytes, break
-            if !key_bytes.starts_with(&prefix_bytes) {
-                break;
-            }
+        Ok(PaginationResponse {
+            items: results,
+            offset,
+        })
+    }
 
-            // Extract the last 8 bytes for the big-endian index
-            if key_bytes.len() < prefix_bytes.len() + 8 {
-                // Malformed key?
-                continue;
-            }
-            let idx_bytes = &key_bytes[key_bytes.len() - 8..];
-            let idx = u64::from_le_bytes(idx_bytes.try_into().unwrap());
+    /// Highest index of the transactions of `rune_id`. They're numbered from 1.
+    pub fn get_rune_transactions_last_index(
+        &self,
+        rune_id: &RuneId,
+        mempool: bool,
+    ) -> DBResult<u64> {
+        let cf_handle = if mempool {
+            self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(RUNE_TRANSACTIONS_CF)?
+        };
 
-            if idx < start_index {
-                // We've gone past the range
-                break;
-            }
-            if idx > end_index {
-                // Shouldn't happen if our iteration is correct, but skip if so
-                continue;
-            }
+        Ok(self
+            .get_option_vec_data(&cf_handle, rune_index_key(rune_id))
+            .mapped()?
+            .unwrap_or(0))
+    }
 
-            // Convert valuecf to Txid
-            let txid: Txid =
-                txid_from_bytes(&value_bytes).map_err(|_| RocksDBError::InvalidTxid)?;
+    /// Transactions of `rune_id` at `indexes`, in that order. Indexes without
+    /// one, like those of transactions that left the mempool, are skipped.
+    pub fn get_rune_transactions_at(
+        &self,
+        rune_id: &RuneId,
+        indexes: &[u64],
+        mempool: bool,
+    ) -> DBResult<Vec<(u64, Txid)>> {
+        let cf_handle = if mempool {
+            self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(RUNE_TRANSACTIONS_CF)?
+        };
 
-            results.push(txid);
+        let keys = indexes
+            .iter()
+            .map(|index| (&cf_handle, rune_transaction_key(rune_id, *index)));
 
-            // Stop if we have limit items
-            if results.len() as u64 >= limit {
-                break;
+        let mut transactions = Vec::new();
+        for (index, value) in indexes.iter().zip(self.db.multi_get_cf(keys)) {
+            if let Some(value) = value? {
+                let txid = txid_from_bytes(&value).map_err(|_| RocksDBError::InvalidTxid)?;
+                transactions.push((*index, txid));
             }
         }
 
-        let offset = skip + results.len() as u64;
-
-        Ok(PaginationResponse {
-            items: results,
-            offset,
-        })
+        Ok(transactions)
     }
 
     /// Batch-add multiple rune transactions.
@@ -807,6 +1227,16 @@ impl RocksDB {
             .clone())
     }
 
+    /// Mempool txids added at or after `seq`, oldest first, and the cursor for
+    /// the next call.
+    pub fn get_mempool_txids_since(&self, seq: u64) -> DBResult<(Vec<Txid>, u64)> {
+        Ok(self
+            .mempool_seq
+            .read()
+            .map_err(|_| RocksDBError::LockPoisoned)?
+            .since(seq))
+    }
+
     pub fn is_tx_in_mempool(&self, txid: &Txid) -> DBResult<bool> {
         let exists = self
             .mempool_cache
@@ -1029,6 +1459,24 @@ impl RocksDB {
         Ok(transaction)
     }
 
+    /// Returns the txid of the segwit transaction with `wtxid`.
+    pub fn get_txid_by_wtxid(&self, wtxid: &Wtxid, mempool: bool) -> DBResult<Txid> {
+        let cf_handle = if mempool {
+            self.cf_handle(WTXIDS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(WTXIDS_CF)?
+        };
+
+        let data = self
+            .get_option_vec_data(&cf_handle, wtxid_to_bytes(wtxid))?
+            .ok_or(RocksDBError::NotFound(format!(
+                "wtxid not found: {}",
+                wtxid
+            )))?;
+
+        txid_from_bytes(&data).map_err(|_| RocksDBError::InvalidTxid)
+    }
+
     pub fn partition_transactions_by_existence<'a, I>(
         &self,
         txids: I,
@@ -1175,12 +1623,21 @@ impl RocksDB {
             }
         }
 
-        // 5. Update runes
+        // 5. Update runes and their supply ordering
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let supply_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNES_BY_SUPPLY_CF)?;
+            let old_runes = self.get_runes_by_ids(&update.runes.keys().copied().collect())?;
 
             for (rune_id, rune) in update.runes.iter() {
                 batch.put_cf(&cf_handle, rune_id_to_bytes(&rune_id), rune.clone().store());
+                self.reorder_rune_supply(
+                    &mut batch,
+                    &supply_cf_handle,
+                    old_runes.get(rune_id),
+                    rune,
+                );
             }
         }
 
@@ -1215,6 +1672,46 @@ impl RocksDB {
                     inscription.clone().store(),
                 );
             }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(INSCRIPTION_CHILDREN_CF)?;
+            for (inscription_id, inscription) in update.inscriptions.iter() {
+                for parent in inscription.parents.iter() {
+                    batch.put_cf(
+                        &cf_handle,
+                        inscription_child_key(parent, inscription_id),
+                        [],
+                    );
+                }
+            }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(OUTPUT_INSCRIPTIONS_CF)?;
+            for (outpoint, inscriptions) in update.output_inscriptions.iter() {
+                batch.put_cf(
+                    &cf_handle,
+                    outpoint_to_bytes(outpoint),
+                    inscriptions.clone().store(),
+                );
+            }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(SAT_INSCRIPTIONS_CF)?;
+            let sats_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(INSCRIPTION_SATS_CF)?;
+            for (inscription_id, sat) in update.inscription_sats.iter() {
+                batch.put_cf(&cf_handle, sat_inscription_key(*sat, inscription_id), []);
+                batch.put_cf(
+                    &sats_cf_handle,
+                    inscription_id_to_bytes(inscription_id),
+                    sat.to_le_bytes(),
+                );
+            }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(SAT_RANGES_CF)?;
+            for (outpoint, sat_ranges) in update.sat_ranges.iter() {
+                batch.put_cf(
+                    &cf_handle,
+                    outpoint_to_bytes(outpoint),
+                    sat_ranges.clone().store(),
+                );
+            }
         }
 
         // 9. Update mempool_txs
@@ -1224,6 +1721,10 @@ impl RocksDB {
                 .mempool_cache
                 .write()
                 .map_err(|_| RocksDBError::LockPoisoned)?;
+            let mut mempool_seq = self
+                .mempool_seq
+                .write()
+                .map_err(|_| RocksDBError::LockPoisoned)?;
             for (txid, mempool_entry) in update.mempool_txs.iter() {
                 batch.put_cf(
                     &cf_handle,
@@ -1232,6 +1733,7 @@ impl RocksDB {
                 );
 
                 mempool_cache.insert(txid.clone(), mempool_entry.clone());
+                mempool_seq.insert(*txid);
             }
         }
 
@@ -1336,6 +1838,19 @@ impl RocksDB {
                     consensus::serialize(&transaction.clone()),
                 );
             }
+
+            let cf_handle: Arc<BoundColumnFamily<'_>> = if mempool {
+                self.cf_handle(WTXIDS_MEMPOOL_CF)?
+            } else {
+                self.cf_handle(WTXIDS_CF)?
+            };
+
+            for (txid, transaction) in update.transactions.iter() {
+                let wtxid = transaction.compute_wtxid();
+                if wtxid.as_raw_hash() != txid.as_raw_hash() {
+                    batch.put_cf(&cf_handle, wtxid_to_bytes(&wtxid), txid_to_bytes(txid));
+                }
+            }
         }
 
         // 17. Update transaction_confirming_block
@@ -1348,6 +1863,46 @@ impl RocksDB {
             }
         }
 
+        // 18. Update rune activity
+        if !mempool {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_ACTIVITY_CF)?;
+            let by_activity_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNES_BY_ACTIVITY_CF)?;
+
+            for (rune_id, txids) in update.rune_transactions.iter() {
+                let Some(height) = txids
+                    .iter()
+                    .filter_map(|txid| update.transaction_confirming_block.get(txid))
+                    .map(|block_id| block_id.height)
+                    .max()
+                else {
+                    continue;
+                };
+
+                let old_height = self.get_rune_activity(rune_id)?;
+                if old_height == Some(height) {
+                    continue;
+                }
+
+                let number = match update.runes.get(rune_id) {
+                    Some(rune) => rune.number,
+                    None => self.get_rune(rune_id)?.number,
+                };
+                if let Some(old_height) = old_height {
+                    batch.delete_cf(
+                        &by_activity_cf_handle,
+                        rune_order_key(old_height.into(), number),
+                    );
+                }
+                batch.put_cf(
+                    &by_activity_cf_handle,
+                    rune_order_key(height.into(), number),
+                    [],
+                );
+                batch.put_cf(&cf_handle, rune_id_to_bytes(rune_id), height.to_le_bytes());
+            }
+        }
+
         // Proceed with the actual write
         self.db.write(batch)?;
 
@@ -1359,6 +1914,27 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Moves a rune whose entry changes from `old` to `new` in the supply
+    /// ordering.
+    fn reorder_rune_supply(
+        &self,
+        batch: &mut WriteBatch,
+        supply_cf_handle: &Arc<BoundColumnFamily<'_>>,
+        old: Option<&RuneEntry>,
+        new: &RuneEntry,
+    ) {
+        let key = rune_order_key(new.supply(), new.number);
+        let old_key = old.map(|old| rune_order_key(old.supply(), old.number));
+        if old_key == Some(key) {
+            return;
+        }
+
+        if let Some(old_key) = old_key {
+            batch.delete_cf(supply_cf_handle, old_key);
+        }
+        batch.put_cf(supply_cf_handle, key, []);
+    }
+
     pub fn batch_delete(&self, delete: &BatchDelete) -> DBResult<()> {
         let mut batch = WriteBatch::default();
 
@@ -1404,6 +1980,22 @@ impl RocksDB {
             }
         }
 
+        // 5. Delete output_inscriptions
+        {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(OUTPUT_INSCRIPTIONS_CF)?;
+            for outpoint in delete.output_inscriptions.iter() {
+                batch.delete_cf(&cf_handle, outpoint_to_bytes(outpoint));
+            }
+        }
+
+        // 6. Delete sat_ranges
+        {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(SAT_RANGES_CF)?;
+            for outpoint in delete.sat_ranges.iter() {
+                batch.delete_cf(&cf_handle, outpoint_to_bytes(outpoint));
+            }
+        }
+
         self.db.write(batch)?;
         Ok(())
     }
@@ -1421,15 +2013,26 @@ impl RocksDB {
             );
         }
 
-        // 2. Update rune_entry
+        // 2. Update rune_entry and the supply ordering
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let supply_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNES_BY_SUPPLY_CF)?;
+            let current_runes =
+                self.get_runes_by_ids(&rollback.rune_entry.keys().copied().collect())?;
+
             for (rune_id, rune_entry) in rollback.rune_entry.iter() {
                 batch.put_cf(
                     &cf_handle,
                     rune_id_to_bytes(&rune_id),
                     rune_entry.clone().store(),
                 );
+                self.reorder_rune_supply(
+                    &mut batch,
+                    &supply_cf_handle,
+                    current_runes.get(rune_id),
+                    rune_entry,
+                );
             }
         }
 
@@ -1487,6 +2090,17 @@ impl RocksDB {
             for outpoint in rollback.outpoints_to_delete.iter() {
                 batch.delete_cf(&cf_handle, outpoint_to_bytes(outpoint));
             }
+
+            // Only confirmed transfers move inscriptions and sats. The spent
+            // inputs keep theirs, so they hold them again.
+            if !mempool {
+                let cf_handle = self.cf_handle(OUTPUT_INSCRIPTIONS_CF)?;
+                let sat_ranges_cf_handle = self.cf_handle(SAT_RANGES_CF)?;
+                for outpoint in rollback.outpoints_to_delete.iter() {
+                    batch.delete_cf(&cf_handle, outpoint_to_bytes(outpoint));
+                    batch.delete_cf(&sat_ranges_cf_handle, outpoint_to_bytes(outpoint));
+                }
+            }
         }
 
         // 6. Script pubkey outpoints
@@ -1511,11 +2125,34 @@ impl RocksDB {
             }
         }
 
-        // 8. Update runes_to_delete
+        // 8. Update runes_to_delete, with their places in the orderings
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
+            let supply_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNES_BY_SUPPLY_CF)?;
+            let by_activity_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNES_BY_ACTIVITY_CF)?;
+            let activity_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(RUNE_ACTIVITY_CF)?;
+            let deleted_runes = self.get_runes_by_ids(&rollback.runes_to_delete)?;
+
             for rune_id in rollback.runes_to_delete.iter() {
                 batch.delete_cf(&cf_handle, rune_id_to_bytes(rune_id));
+
+                let Some(rune_entry) = deleted_runes.get(rune_id) else {
+                    continue;
+                };
+                batch.delete_cf(
+                    &supply_cf_handle,
+                    rune_order_key(rune_entry.supply(), rune_entry.number),
+                );
+                if let Some(height) = self.get_rune_activity(rune_id)? {
+                    batch.delete_cf(
+                        &by_activity_cf_handle,
+                        rune_order_key(height.into(), rune_entry.number),
+                    );
+                    batch.delete_cf(&activity_cf_handle, rune_id_to_bytes(rune_id));
+                }
             }
         }
 
@@ -1538,7 +2175,35 @@ impl RocksDB {
         // 11. Update inscriptions_to_delete
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(INSCRIPTIONS_CF)?;
+            let children_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(INSCRIPTION_CHILDREN_CF)?;
+            let sat_inscriptions_cf_handle: Arc<BoundColumnFamily<'_>> =
+                self.cf_handle(SAT_INSCRIPTIONS_CF)?;
+            let sats_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(INSCRIPTION_SATS_CF)?;
             for inscription_id in rollback.inscriptions_to_delete.iter() {
+                let parents = match self.get_inscription(inscription_id) {
+                    Ok(inscription) => inscription.parents,
+                    Err(RocksDBError::NotFound(_)) => Vec::new(),
+                    Err(err) => return Err(err),
+                };
+                for parent in parents.iter() {
+                    batch.delete_cf(
+                        &children_cf_handle,
+                        inscription_child_key(parent, inscription_id),
+                    );
+                }
+
+                let sat: Option<u64> = self
+                    .get_option_vec_data(&sats_cf_handle, inscription_id_to_bytes(inscription_id))
+                    .mapped()?;
+                if let Some(sat) = sat {
+                    batch.delete_cf(
+                        &sat_inscriptions_cf_handle,
+                        sat_inscription_key(sat, inscription_id),
+                    );
+                    batch.delete_cf(&sats_cf_handle, inscription_id_to_bytes(inscription_id));
+                }
+
                 batch.delete_cf(&cf_handle, inscription_id_to_bytes(inscription_id));
             }
         }
@@ -1571,6 +2236,24 @@ impl RocksDB {
             for txid in rollback.txs_to_delete.iter() {
                 batch.delete_cf(&cf_handle, txid_to_bytes(txid));
             }
+
+            let wtxids_cf_handle: Arc<BoundColumnFamily<'_>> = if mempool {
+                self.cf_handle(WTXIDS_MEMPOOL_CF)?
+            } else {
+                self.cf_handle(WTXIDS_CF)?
+            };
+
+            for txid in rollback.txs_to_delete.iter() {
+                if let Some(transaction) = self
+                    .get_option_vec_data(&cf_handle, txid_to_bytes(txid))?
+                    .and_then(|data| consensus::deserialize::<Transaction>(&data).ok())
+                {
+                    batch.delete_cf(
+                        &wtxids_cf_handle,
+                        wtxid_to_bytes(&transaction.compute_wtxid()),
+                    );
+                }
+            }
         }
 
         // 15. Update tx state changes
@@ -1602,18 +2285,35 @@ impl RocksDB {
                 .mempool_cache
                 .write()
                 .map_err(|_| RocksDBError::LockPoisoned)?;
+            let mut mempool_seq = self
+                .mempool_seq
+                .write()
+                .map_err(|_| RocksDBError::LockPoisoned)?;
 
             for txid in rollback.txs_to_delete.iter() {
                 batch.delete_cf(&cf_handle, txid_to_bytes(txid));
 
                 // Update cache
                 mempool_cache.remove(txid);
+                mempool_seq.remove(txid);
             }
         }
 
         self.db.write(batch)?;
 
+        let rolled_back_runes: HashSet<RuneId> = if mempool {
+            HashSet::new()
+        } else {
+            self.get_txs_index_refs(&rollback.txs_to_delete, mempool)?
+                .into_values()
+                .flatten()
+                .map(|idx_ref| rune_id_from_bytes(&idx_ref.rune_id))
+                .collect::<Result<_, _>>()
+                .map_err(|_| RocksDBError::InvalidRuneId)?
+        };
+
         self.delete_rune_transactions(&rollback.txs_to_delete, mempool)?;
+        self.revert_rune_activity(&rolled_back_runes)?;
 
         // Update runen numbers after revert.
         let total_runes_before_delete =
@@ -1627,6 +2327,50 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Moves runes whose latest transactions were rolled back to the height of
+    /// their latest remaining one in the activity ordering. Deleted runes
+    /// already left it.
+    fn revert_rune_activity(&self, rune_ids: &HashSet<RuneId>) -> DBResult<()> {
+        if rune_ids.is_empty() {
+            return Ok(());
+        }
+
+        let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_ACTIVITY_CF)?;
+        let by_activity_cf_handle: Arc<BoundColumnFamily<'_>> =
+            self.cf_handle(RUNES_BY_ACTIVITY_CF)?;
+        let runes = self.get_runes_by_ids(&rune_ids.iter().copied().collect())?;
+
+        let mut batch = WriteBatch::default();
+        for (rune_id, rune_entry) in runes.iter() {
+            let old_height = self.get_rune_activity(rune_id)?;
+            let height = self.get_last_rune_transaction_height(rune_id)?;
+            if old_height == height {
+                continue;
+            }
+
+            if let Some(old_height) = old_height {
+                batch.delete_cf(
+                    &by_activity_cf_handle,
+                    rune_order_key(old_height.into(), rune_entry.number),
+                );
+            }
+            match height {
+                Some(height) => {
+                    batch.put_cf(
+                        &by_activity_cf_handle,
+                        rune_order_key(height.into(), rune_entry.number),
+                        [],
+                    );
+                    batch.put_cf(&cf_handle, rune_id_to_bytes(rune_id), height.to_le_bytes());
+                }
+                None => batch.delete_cf(&cf_handle, rune_id_to_bytes(rune_id)),
+            }
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
     fn update_rune_numbers_after_revert(
         &self,
         rune_numbers_deleted: &Vec<u64>,
@@ -1667,11 +2411,29 @@ impl RocksDB {
 
         let runes_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_CF)?;
         let rune_number_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_NUMBER_CF)?;
+        let supply_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNES_BY_SUPPLY_CF)?;
+        let by_activity_cf_handle: Arc<BoundColumnFamily<'_>> =
+            self.cf_handle(RUNES_BY_ACTIVITY_CF)?;
 
         for (number, rune_id) in rune_ids_to_update {
             let rune_entry = rune_entries_to_update.get_mut(&rune_id).unwrap();
             let new_number = rune_numbers_to_update.get(&number).unwrap();
+
+            // The orderings break ties by number.
+            let old_entry = rune_entry.clone();
             rune_entry.number = *new_number;
+            self.reorder_rune_supply(&mut batch, &supply_cf_handle, Some(&old_entry), rune_entry);
+            if let Some(height) = self.get_rune_activity(&rune_id)? {
+                batch.delete_cf(
+                    &by_activity_cf_handle,
+                    rune_order_key(height.into(), old_entry.number),
+                );
+                batch.put_cf(
+                    &by_activity_cf_handle,
+                    rune_order_key(height.into(), *new_number),
+                    [],
+                );
+            }
             batch.put_cf(
                 &runes_cf_handle,
                 rune_id_to_bytes(&rune_id),
@@ -1738,6 +2500,45 @@ impl RocksDB {
         self.set_subscription(&sub)
     }
 
+    /// RocksDB's estimates for each column family. Reading them doesn't scan
+    /// the data.
+    pub fn get_column_family_stats(&self) -> DBResult<Vec<ColumnFamilyStats>> {
+        COLUMN_FAMILIES
+            .iter()
+            .map(|name| {
+                let cf_handle = self.cf_handle(name)?;
+                let property = |property| -> DBResult<u64> {
+                    Ok(self
+                        .db
+                        .property_int_value_cf(&cf_handle, property)?
+                        .unwrap_or_default())
+                };
+
+                Ok(ColumnFamilyStats {
+                    name: name.to_string(),
+                    estimated_keys: property(properties::ESTIMATE_NUM_KEYS)?,
+                    sst_files_bytes: property(properties::TOTAL_SST_FILES_SIZE)?,
+                    memtables_bytes: property(properties::SIZE_ALL_MEM_TABLES)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Size of the files in the database directory, which RocksDB keeps flat.
+    pub fn get_disk_size(&self) -> DBResult<u64> {
+        let size = || -> std::io::Result<u64> {
+            let mut size = 0;
+            for entry in std::fs::read_dir(self.db.path())? {
+                let metadata = entry?.metadata()?;
+                if metadata.is_file() {
+                    size += metadata.len();
+                }
+            }
+            Ok(size)
+        };
+        size().map_err(|err| RocksDBError::Io(err.to_string()))
+    }
+
     pub fn flush(&self) -> DBResult<()> {
         self.db.flush()?;
         Ok(())
@@ -1753,3 +2554,243 @@ impl RocksDB {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash, std::path::PathBuf};
+
+    /// Database in a fresh directory, removed again on drop.
+    struct TempDB {
+        db: Option<RocksDB>,
+        path: PathBuf,
+    }
+
+    impl TempDB {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("titan-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            Self {
+                db: Some(RocksDB::open(path.to_str().unwrap()).unwrap()),
+                path,
+            }
+        }
+
+        fn reopen(&mut self) {
+            drop(self.db.take());
+            self.db = Some(RocksDB::open(self.path.to_str().unwrap()).unwrap());
+        }
+    }
+
+    impl std::ops::Deref for TempDB {
+        type Target = RocksDB;
+
+        fn deref(&self) -> &RocksDB {
+            self.db.as_ref().unwrap()
+        }
+    }
+
+    impl Drop for TempDB {
+        fn drop(&mut self) {
+            drop(self.db.take());
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn txid(n: u64) -> Txid {
+        let mut bytes = [0; 32];
+        bytes[..8].copy_from_slice(&n.to_be_bytes());
+        Txid::from_byte_array(bytes)
+    }
+
+    #[test]
+    fn pages_through_more_than_256_rune_transactions_in_order() {
+        let db = TempDB::new("rune-transactions");
+        let rune_id = RuneId {
+            block: 840_000,
+            tx: 1,
+        };
+        let txids: Vec<Txid> = (1..=300).map(txid).collect();
+        db.add_rune_transactions_batch(&HashMap::from([(rune_id, txids.clone())]), false)
+            .unwrap();
+
+        let page = |skip, order| {
+            db.get_rune_transactions(&rune_id, Some(Pagination { skip, limit: 50 }), false, order)
+                .unwrap()
+        };
+
+        let asc = page(250, Order::Asc);
+        assert_eq!(asc.items, txids[250..]);
+        assert_eq!(asc.offset, 300);
+
+        let desc = page(0, Order::Desc);
+        assert_eq!(
+            desc.items,
+            txids[250..].iter().rev().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            page(40, Order::Desc).items,
+            txids[210..260].iter().rev().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rolled_back_transfers_leave_inscriptions_on_the_spent_output() {
+        let db = TempDB::new("output-inscriptions");
+        let inscribed = |offset| {
+            vec![OutputInscription {
+                id: InscriptionId {
+                    txid: txid(1),
+                    index: 0,
+                },
+                offset,
+            }]
+        };
+        let reveal = OutPoint::new(txid(1), 0);
+        let transfer = OutPoint::new(txid(2), 1);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.output_inscriptions.insert(reveal, inscribed(0));
+        update.output_inscriptions.insert(transfer, inscribed(5));
+        db.batch_update(&update, false).unwrap();
+        assert_eq!(
+            db.get_output_inscriptions(&[reveal, transfer, OutPoint::new(txid(3), 0)])
+                .unwrap(),
+            HashMap::from([(reveal, inscribed(0)), (transfer, inscribed(5))])
+        );
+
+        // Mempool transactions don't move inscriptions.
+        let mut rollback = BatchRollback::new(0);
+        rollback.outpoints_to_delete.push(transfer);
+        db.batch_rollback(&rollback, true).unwrap();
+        assert_eq!(db.get_output_inscriptions(&[transfer]).unwrap().len(), 1);
+
+        db.batch_rollback(&rollback, false).unwrap();
+        assert_eq!(
+            db.get_output_inscriptions(&[reveal, transfer]).unwrap(),
+            HashMap::from([(reveal, inscribed(0))])
+        );
+
+        let mut delete = BatchDelete::new();
+        delete.output_inscriptions.insert(reveal);
+        db.batch_delete(&delete).unwrap();
+        assert!(db.get_output_inscriptions(&[reveal]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rolled_back_reveals_leave_their_genesis_sats() {
+        let db = TempDB::new("sat-inscriptions");
+        let inscription_id = |n| InscriptionId {
+            txid: txid(n),
+            index: 0,
+        };
+        let sat_ranges = vec![SatRange {
+            start: 5_000_000_000,
+            end: 5_000_010_000,
+        }];
+        let reveal = OutPoint::new(txid(2), 0);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update
+            .inscription_sats
+            .insert(inscription_id(2), 5_000_000_000);
+        update
+            .inscription_sats
+            .insert(inscription_id(1), 5_000_000_000);
+        update
+            .inscription_sats
+            .insert(inscription_id(3), 5_000_000_001);
+        update.sat_ranges.insert(reveal, sat_ranges.clone());
+        db.batch_update(&update, false).unwrap();
+        assert_eq!(
+            db.get_sat_inscriptions(5_000_000_000).unwrap(),
+            [inscription_id(1), inscription_id(2)]
+        );
+        assert_eq!(
+            db.get_sat_ranges(&[reveal, OutPoint::new(txid(3), 0)])
+                .unwrap(),
+            HashMap::from([(reveal, sat_ranges)])
+        );
+
+        let mut rollback = BatchRollback::new(0);
+        rollback.outpoints_to_delete.push(reveal);
+        rollback.inscriptions_to_delete.push(inscription_id(2));
+        db.batch_rollback(&rollback, false).unwrap();
+        assert_eq!(
+            db.get_sat_inscriptions(5_000_000_000).unwrap(),
+            [inscription_id(1)]
+        );
+        assert_eq!(
+            db.get_sat_inscriptions(5_000_000_001).unwrap(),
+            [inscription_id(3)]
+        );
+        assert!(db.get_sat_ranges(&[reveal]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rolled_back_mints_and_etchings_leave_the_rune_orderings() {
+        let mut db = TempDB::new("rune-orderings");
+        let rune_id = |tx| RuneId { block: 840_000, tx };
+        let rune = |number, mints| RuneEntry {
+            number,
+            mints,
+            ..RuneEntry::fixture()
+        };
+        let confirm = |update: &mut BatchUpdate, rune_id, txid, height| {
+            update
+                .rune_transactions
+                .entry(rune_id)
+                .or_default()
+                .push(txid);
+            update.transaction_confirming_block.insert(
+                txid,
+                BlockId {
+                    hash: BlockHash::all_zeros(),
+                    height,
+                },
+            );
+        };
+        let sorted = |db: &RocksDB, sort, order| {
+            db.get_rune_numbers_sorted(sort, order, Pagination::default())
+                .unwrap()
+                .items
+        };
+
+        // Two runes etched in block 1.
+        let mut update = BatchUpdate::new(2, 0, 0);
+        for (number, (id, mints)) in [(rune_id(0), 2), (rune_id(1), 3)].into_iter().enumerate() {
+            let number = number as u64;
+            update.runes.insert(id, rune(number, mints));
+            update.rune_numbers.insert(number, id);
+            confirm(&mut update, id, txid(number), 1);
+        }
+        db.batch_update(&update, false).unwrap();
+
+        // Block 2 mints the first and etches a third.
+        let mut update = BatchUpdate::new(3, 0, 0);
+        update.runes.insert(rune_id(0), rune(0, 5));
+        confirm(&mut update, rune_id(0), txid(2), 2);
+        update.runes.insert(rune_id(2), rune(2, 0));
+        update.rune_numbers.insert(2, rune_id(2));
+        confirm(&mut update, rune_id(2), txid(3), 2);
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(sorted(&db, RuneSort::Supply, Order::Desc), [0, 1, 2]);
+        assert_eq!(sorted(&db, RuneSort::Activity, Order::Desc), [2, 0, 1]);
+        assert_eq!(sorted(&db, RuneSort::Etching, Order::Desc), [2, 1, 0]);
+
+        let mut rollback = BatchRollback::new(2);
+        rollback.rune_entry.insert(rune_id(0), rune(0, 2));
+        rollback.runes_to_delete.push(rune_id(2));
+        rollback.rune_numbers_to_delete.push(2);
+        rollback.delete_all_rune_transactions.push(rune_id(2));
+        rollback.txs_to_delete.extend([txid(2), txid(3)]);
+        db.batch_rollback(&rollback, false).unwrap();
+        db.reopen();
+
+        assert_eq!(sorted(&db, RuneSort::Supply, Order::Desc), [1, 0]);
+        assert_eq!(sorted(&db, RuneSort::Activity, Order::Desc), [1, 0]);
+        assert_eq!(sorted(&db, RuneSort::Activity, Order::Asc), [0, 1]);
+        assert_eq!(sorted(&db, RuneSort::Etching, Order::Asc), [0, 1]);
+        assert_eq!(db.get_rune_activity(&rune_id(0)).unwrap(), Some(1));
+    }
+}
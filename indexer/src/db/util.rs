@@ -1,4 +1,4 @@
-use bitcoin::{hashes::Hash, OutPoint, ScriptBuf, Txid};
+use bitcoin::{hashes::Hash, OutPoint, ScriptBuf, Txid, Wtxid};
 use ordinals::RuneId;
 use std::convert::TryInto;
 
@@ -18,6 +18,11 @@ pub fn txid_from_bytes(bytes: &[u8]) -> Result<Txid, &'static str> {
     Ok(Txid::from_slice(bytes).unwrap())
 }
 
+/// Converts a `Wtxid` to a 32-byte Vec<u8>.
+pub fn wtxid_to_bytes(wtxid: &Wtxid) -> [u8; 32] {
+    wtxid.as_raw_hash().to_byte_array()
+}
+
 /// Converts an `OutPoint` to a 36-byte Vec<u8>.
 pub fn outpoint_to_bytes(outpoint: &OutPoint) -> Vec<u8> {
     let mut buffer: Vec<u8> = Vec::with_capacity(36);
@@ -61,6 +66,22 @@ pub fn inscription_id_from_bytes(bytes: &[u8]) -> Result<InscriptionId, &'static
     })
 }
 
+/// The sat big-endian followed by the inscription's id, so a sat's
+/// inscriptions share a prefix.
+pub fn sat_inscription_key(sat: u64, inscription_id: &InscriptionId) -> Vec<u8> {
+    let mut key = sat.to_be_bytes().to_vec();
+    key.extend_from_slice(&inscription_id_to_bytes(inscription_id));
+    key
+}
+
+/// The parent's id followed by the child's, so a parent's children share a
+/// prefix.
+pub fn inscription_child_key(parent: &InscriptionId, child: &InscriptionId) -> Vec<u8> {
+    let mut key = inscription_id_to_bytes(parent);
+    key.extend_from_slice(&inscription_id_to_bytes(child));
+    key
+}
+
 /// Converts an `RuneId` to a 12-byte Vec<u8>.
 pub fn rune_id_to_bytes(rune_id: &RuneId) -> Vec<u8> {
     let mut buffer: Vec<u8> = Vec::with_capacity(12);
@@ -111,6 +132,23 @@ pub fn parse_outpoint_from_script_pubkey_key(key: &[u8]) -> Result<OutPoint, &'s
     outpoint_from_bytes(outpoint_bytes)
 }
 
+/// Key of a rune in an ordering of runes by `value`, the rune's number
+/// breaking ties. Big-endian so keys sort like the numbers do.
+pub fn rune_order_key(value: u128, number: u64) -> [u8; 24] {
+    let mut key = [0; 24];
+    key[..16].copy_from_slice(&value.to_be_bytes());
+    key[16..].copy_from_slice(&number.to_be_bytes());
+    key
+}
+
+pub fn rune_number_from_order_key(key: &[u8]) -> Result<u64, &'static str> {
+    let number: [u8; 8] = key
+        .get(16..)
+        .and_then(|number| number.try_into().ok())
+        .ok_or("invalid rune order key")?;
+    Ok(u64::from_be_bytes(number))
+}
+
 pub fn rune_index_key(rune_id: &RuneId) -> Vec<u8> {
     let mut v = Vec::with_capacity(rune_id_to_bytes(rune_id).len() + 10);
     v.extend_from_slice(b"rune_index:");
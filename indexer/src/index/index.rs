@@ -9,12 +9,16 @@ use {
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientPool},
         index::updater::{ReorgError, UpdaterError},
-        models::{block_id_to_transaction_status, Inscription, RuneEntry},
+        models::{block_id_to_transaction_status, Inscription, RuneEntry, TransactionStateChange},
+    },
+    bitcoin::{
+        consensus, Address, BlockHash, OutPoint, ScriptBuf, Transaction as BitcoinTransaction,
+        Txid, Wtxid,
     },
-    bitcoin::{Address, BlockHash, OutPoint, Transaction as BitcoinTransaction, Txid},
     ordinals::{Rune, RuneId},
     std::{
-        collections::HashMap,
+        collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+        ops::Range,
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
@@ -23,8 +27,10 @@ use {
         time::Duration,
     },
     titan_types::{
-        AddressData, AddressTxOut, Block, Event, InscriptionId, MempoolEntry, Pagination,
-        PaginationResponse, RuneAmount, Transaction, TransactionStatus, TxOutEntry,
+        AddressBalance, AddressData, AddressTxOut, AddressUtxo, Block, ColumnFamilyStats, Event,
+        InscriptionId, InscriptionsQuery, MempoolEntry, Order, Pagination, PaginationResponse,
+        ReindexRange, RuneAmount, RuneChartBucket, RuneHolder, RuneTransactionKind, RunesQuery,
+        SpentStatus, Transaction, TransactionStatus, TxOutEntry,
     },
     tokio::{runtime::Runtime, sync::mpsc::Sender},
     tracing::{error, info, warn},
@@ -46,6 +52,9 @@ pub enum IndexError {
 
 type Result<T> = std::result::Result<T, IndexError>;
 
+/// Rune transactions read at a time when all of them are needed.
+const RUNE_TRANSACTIONS_READ_CHUNK: usize = 1000;
+
 pub struct Index {
     db: Arc<dyn Store + Send + Sync>,
     settings: Settings,
@@ -121,6 +130,27 @@ impl Index {
             _ => {}
         }
 
+        // Sat ranges start at the genesis block, so an index that already
+        // has blocks without them can't start tracking them.
+        let db_index_sats = match self.db.is_index_sats()? {
+            None if self.db.get_block_count()? > 0 => Some(false),
+            db_index_sats => db_index_sats,
+        };
+        match (self.settings.index_sats, db_index_sats) {
+            (true, Some(false)) => {
+                return Err(IndexError::InvalidIndex(
+                    "index_sats is not set. Disable index_sats in settings or clean up the database".to_string(),
+                ));
+            }
+            (true, None) => {
+                self.db.set_index_sats(true)?;
+            }
+            (false, Some(true)) | (false, None) => {
+                self.db.set_index_sats(false)?;
+            }
+            _ => {}
+        }
+
         Ok(())
     }
 
@@ -202,10 +232,44 @@ impl Index {
             .await;
     }
 
+    /// Number of reorgs handled since startup.
+    pub fn get_reorg_count(&self) -> u64 {
+        self.updater.reorgs()
+    }
+
+    /// When indexed data was last written to the db since startup.
+    pub fn get_last_flush_epoch_secs(&self) -> Option<u64> {
+        self.updater.last_flush_epoch_secs()
+    }
+
+    /// Rolls back `range` and indexes it again at the start of the next update.
+    pub fn request_reindex(&self, range: ReindexRange) -> Result<()> {
+        Ok(self.updater.request_reindex(range)?)
+    }
+
+    pub fn get_column_family_stats(&self) -> Result<Vec<ColumnFamilyStats>> {
+        Ok(self.db.get_column_family_stats()?)
+    }
+
+    pub fn get_disk_size(&self) -> Result<u64> {
+        Ok(self.db.get_disk_size()?)
+    }
+
     pub fn get_block_count(&self) -> Result<u64> {
         Ok(self.db.get_block_count()?)
     }
 
+    /// Block count of the node as of the last sync, `None` until the first sync.
+    pub fn get_node_block_count(&self) -> Option<u64> {
+        self.updater.node_block_count()
+    }
+
+    /// Pretends the updater synced with a node at `count` blocks.
+    #[cfg(test)]
+    pub(crate) fn set_node_block_count(&self, count: u64) {
+        self.updater.set_node_block_count(count);
+    }
+
     pub fn get_block_hash(&self, height: u64) -> Result<BlockHash> {
         Ok(self.db.get_block_hash(height)?)
     }
@@ -218,6 +282,229 @@ impl Index {
         Ok(self.db.get_mempool_txids()?.keys().cloned().collect())
     }
 
+    /// Mempool txids in the order they were indexed, from sequence number `seq`
+    /// on, and the sequence number to continue from.
+    pub fn get_mempool_txids_since(&self, seq: u64) -> Result<(Vec<Txid>, u64)> {
+        Ok(self.db.get_mempool_txids_since(seq)?)
+    }
+
+    /// Txids that created an output for the address or spent one of its outputs.
+    pub fn get_address_txids(&self, address: &Address) -> Result<HashSet<Txid>> {
+        let script_pubkey = address.script_pubkey();
+        let outpoints = self.db.get_script_pubkey_outpoints(&script_pubkey, None)?;
+        let tx_outs = self
+            .db
+            .get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
+
+        let mut txids = HashSet::new();
+        for (outpoint, tx_out) in tx_outs {
+            txids.insert(outpoint.txid);
+            if let SpentStatus::Spent(spender) = tx_out.spent {
+                txids.insert(spender.txid);
+            }
+        }
+
+        Ok(txids)
+    }
+
+    /// Aggregates the confirmed unspent outputs holding `rune_id` by address, sorted
+    /// by amount. Walks every transaction of the rune, so this is expensive for
+    /// heavily traded runes.
+    pub fn get_rune_holders(&self, rune_id: &RuneId) -> Result<Vec<RuneHolder>> {
+        let txids = self.get_all_rune_txids(rune_id, false, Order::Asc)?;
+        let tx_outs = self.db.get_tx_outs_by_txids(&txids, false)?;
+
+        let holdings: HashMap<OutPoint, u128> = tx_outs
+            .into_iter()
+            .filter(|(_, tx_out)| matches!(tx_out.spent, SpentStatus::Unspent))
+            .filter_map(|(outpoint, tx_out)| {
+                let amount: u128 = tx_out
+                    .runes
+                    .iter()
+                    .filter(|rune| rune.rune_id == *rune_id)
+                    .map(|rune| rune.amount)
+                    .sum();
+
+                (amount > 0).then_some((outpoint, amount))
+            })
+            .collect();
+
+        let outpoints: Vec<OutPoint> = holdings.keys().cloned().collect();
+        let script_pubkeys =
+            self.db
+                .get_outpoints_to_script_pubkey(&outpoints, Some(false), true)?;
+
+        let network = self.settings.chain.network();
+        let mut holders: HashMap<String, RuneHolder> = HashMap::new();
+        for (outpoint, amount) in holdings {
+            let address = script_pubkeys
+                .get(&outpoint)
+                .and_then(|script_pubkey| Address::from_script(script_pubkey, network).ok())
+                .map(|address| address.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let holder = holders.entry(address.clone()).or_insert(RuneHolder {
+                address,
+                amount: 0,
+                output_count: 0,
+            });
+            holder.amount += amount;
+            holder.output_count += 1;
+        }
+
+        let mut holders: Vec<RuneHolder> = holders.into_values().collect();
+        holders.sort_by(|a, b| {
+            b.amount
+                .cmp(&a.amount)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+
+        Ok(holders)
+    }
+
+    /// Buckets the confirmed mints and transfers of `rune_id` by block time,
+    /// `interval` seconds each, starting at `from`. Blocks from `to` on are
+    /// left out. Walks every transaction of the rune, like `get_rune_holders`.
+    pub fn get_rune_chart(
+        &self,
+        rune_id: &RuneId,
+        premine: u128,
+        interval: u64,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<RuneChartBucket>> {
+        let all = Pagination {
+            skip: 0,
+            limit: u64::MAX,
+        };
+
+        let txids = self
+            .db
+            .get_rune_transactions(rune_id, Some(all), Some(false), Order::Asc)?
+            .items;
+        let confirming_blocks = self.db.get_transaction_confirming_blocks(&txids)?;
+        let state_changes = self.db.get_txs_state_changes(&txids, false)?;
+
+        let mut buckets: Vec<RuneChartBucket> = (from..to)
+            .step_by(interval as usize)
+            .map(|timestamp| RuneChartBucket {
+                timestamp,
+                ..Default::default()
+            })
+            .collect();
+        let mut block_times: HashMap<u64, u64> = HashMap::new();
+        let mut received: HashMap<OutPoint, usize> = HashMap::new();
+
+        for txid in &txids {
+            let (Some(Some(block_id)), Some(state_change)) =
+                (confirming_blocks.get(txid), state_changes.get(txid))
+            else {
+                continue;
+            };
+
+            let block_time = match block_times.entry(block_id.height) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let block = self.db.get_block_by_hash(&block_id.hash)?;
+                    *entry.insert(u64::from(block.header.time))
+                }
+            };
+            if block_time < from || block_time >= to {
+                continue;
+            }
+            let position = ((block_time - from) / interval) as usize;
+            let bucket = &mut buckets[position];
+
+            let etched = matches!(state_change.etched, Some((id, _)) if id == *rune_id);
+            let kinds =
+                state_change.rune_transaction_kinds(rune_id, if etched { premine } else { 0 });
+
+            let mut created = if etched { premine } else { 0 };
+            if let Some(minted) = state_change
+                .minted
+                .as_ref()
+                .filter(|minted| minted.rune_id == *rune_id)
+            {
+                bucket.mints += 1;
+                bucket.minted = bucket.minted.saturating_add(minted.amount);
+                created = created.saturating_add(minted.amount);
+            }
+
+            let mut output_amount = 0u128;
+            for (vout, output) in state_change.outputs.iter().enumerate() {
+                let amount = output
+                    .runes
+                    .iter()
+                    .filter(|rune| rune.rune_id == *rune_id)
+                    .fold(0u128, |total, rune| total.saturating_add(rune.amount));
+                if amount > 0 {
+                    output_amount = output_amount.saturating_add(amount);
+                    let outpoint = OutPoint {
+                        txid: *txid,
+                        vout: vout as u32,
+                    };
+                    received.insert(outpoint, position);
+                }
+            }
+
+            if kinds.contains(&RuneTransactionKind::Transfer) {
+                bucket.transfers += 1;
+                bucket.transferred = bucket
+                    .transferred
+                    .saturating_add(output_amount.saturating_sub(created));
+            }
+        }
+
+        let outpoints: Vec<OutPoint> = received.keys().cloned().collect();
+        let script_pubkeys =
+            self.db
+                .get_outpoints_to_script_pubkey(&outpoints, Some(false), true)?;
+
+        let mut holders: Vec<HashSet<&ScriptBuf>> = vec![HashSet::new(); buckets.len()];
+        for (outpoint, position) in received {
+            if let Some(script_pubkey) = script_pubkeys.get(&outpoint) {
+                holders[position].insert(script_pubkey);
+            }
+        }
+        for (bucket, holders) in buckets.iter_mut().zip(holders) {
+            bucket.unique_holders = holders.len() as u64;
+        }
+
+        Ok(buckets)
+    }
+
+    pub fn get_mempool_rune_txids(&self, rune_id: &RuneId) -> Result<Vec<Txid>> {
+        self.get_all_rune_txids(rune_id, true, Order::Desc)
+    }
+
+    /// Every transaction of `rune_id`, read by index a chunk at a time. The
+    /// index keys are little-endian, so iterating them wouldn't visit them in
+    /// order.
+    fn get_all_rune_txids(
+        &self,
+        rune_id: &RuneId,
+        mempool: bool,
+        order: Order,
+    ) -> Result<Vec<Txid>> {
+        let last_index = self.db.get_rune_transactions_last_index(rune_id, mempool)?;
+        let indexes: Vec<u64> = match order {
+            Order::Asc => (1..=last_index).collect(),
+            Order::Desc => (1..=last_index).rev().collect(),
+        };
+
+        let mut txids = Vec::new();
+        for chunk in indexes.chunks(RUNE_TRANSACTIONS_READ_CHUNK) {
+            txids.extend(
+                self.db
+                    .get_rune_transactions_at(rune_id, chunk, mempool)?
+                    .into_iter()
+                    .map(|(_, txid)| txid),
+            );
+        }
+
+        Ok(txids)
+    }
+
     pub fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry> {
         Ok(self.db.get_mempool_entry(txid)?)
     }
@@ -245,15 +532,35 @@ impl Index {
             .get_tx_outs_with_mempool_spent_update(outpoints, None)?)
     }
 
+    pub fn get_tx_state_changes(&self, txid: &Txid) -> Result<TransactionStateChange> {
+        Ok(self.db.get_tx_state_changes(txid, None)?)
+    }
+
+    pub fn get_txs_state_changes(
+        &self,
+        txids: &Vec<Txid>,
+    ) -> Result<HashMap<Txid, TransactionStateChange>> {
+        Ok(self.db.get_txs_state_changes(txids, false)?)
+    }
+
     pub fn get_rune(&self, rune_id: &RuneId) -> Result<RuneEntry> {
         Ok(self.db.get_rune(rune_id)?)
     }
 
     pub fn get_runes(
         &self,
+        query: RunesQuery,
         pagination: Pagination,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>> {
-        Ok(self.db.get_runes(pagination)?)
+        Ok(self.db.get_runes(query, pagination)?)
+    }
+
+    pub fn get_runes_by_numbers(&self, numbers: Range<u64>) -> Result<Vec<(RuneId, RuneEntry)>> {
+        Ok(self.db.get_runes_by_numbers(numbers)?)
+    }
+
+    pub fn search_runes(&self, rune: &Rune, limit: u64) -> Result<Vec<(RuneId, RuneEntry)>> {
+        Ok(self.db.search_runes(&rune.to_string(), limit as usize)?)
     }
 
     pub fn get_rune_id(&self, rune: &Rune) -> Result<RuneId> {
@@ -268,18 +575,128 @@ impl Index {
         Ok(self.db.get_inscription(inscription_id)?)
     }
 
-    pub fn get_last_rune_transactions(
+    /// Inscriptions naming `parent` as theirs, in id order.
+    pub fn get_inscription_children(
+        &self,
+        parent: &InscriptionId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<InscriptionId>> {
+        Ok(self.db.get_inscription_children(parent, pagination)?)
+    }
+
+    /// The inscriptions an output holds, by offset into it, or none once
+    /// it's spent. Only confirmed transactions move inscriptions, so an
+    /// output created in the mempool holds none yet.
+    pub fn get_output_inscriptions(&self, outpoint: &OutPoint) -> Result<Vec<InscriptionId>> {
+        if let SpentStatus::Spent(_) = self.db.get_tx_out(outpoint, None)?.spent {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .db
+            .get_output_inscriptions(std::slice::from_ref(outpoint))?
+            .remove(outpoint)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|inscription| inscription.id)
+            .collect())
+    }
+
+    /// The inscriptions whose genesis sat is `sat`, if sats are indexed.
+    pub fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>> {
+        Ok(self.db.get_sat_inscriptions(sat)?)
+    }
+
+    pub fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<(InscriptionId, Inscription, u64)>> {
+        Ok(self.db.get_inscriptions(query, pagination)?)
+    }
+
+    pub fn get_rune_transactions(
         &self,
         rune_id: &RuneId,
         pagination: Option<Pagination>,
         mempool: Option<bool>,
+        order: Order,
     ) -> Result<PaginationResponse<Txid>> {
         Ok(self
             .db
-            .get_last_rune_transactions(rune_id, pagination, mempool)?)
+            .get_rune_transactions(rune_id, pagination, mempool, order)?)
+    }
+
+    pub fn get_rune_transactions_last_index(&self, rune_id: &RuneId, mempool: bool) -> Result<u64> {
+        Ok(self.db.get_rune_transactions_last_index(rune_id, mempool)?)
+    }
+
+    pub fn get_rune_transactions_at(
+        &self,
+        rune_id: &RuneId,
+        indexes: &[u64],
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>> {
+        Ok(self
+            .db
+            .get_rune_transactions_at(rune_id, indexes, mempool)?)
     }
 
-    pub fn get_script_pubkey_outpoints(&self, address: &Address) -> Result<AddressData> {
+    /// Balance of `address` from its outputs, without reading their transactions.
+    pub fn get_address_balance(&self, address: &Address) -> Result<AddressBalance> {
+        let script_pubkey = address.script_pubkey();
+        let confirmed: HashSet<OutPoint> = self
+            .db
+            .get_script_pubkey_outpoints(&script_pubkey, Some(false))?
+            .into_iter()
+            .collect();
+        let mut outpoints: Vec<OutPoint> = confirmed.iter().copied().collect();
+        outpoints.extend(
+            self.db
+                .get_script_pubkey_outpoints(&script_pubkey, Some(true))?,
+        );
+
+        let tx_outs = self
+            .db
+            .get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
+
+        let mut confirmed_sats = 0;
+        let mut unconfirmed_sats = 0;
+        // Ordered by rune id, so the list doesn't change between requests.
+        let mut runes: BTreeMap<RuneId, u128> = BTreeMap::new();
+        for (outpoint, tx_out) in &tx_outs {
+            if confirmed.contains(outpoint) {
+                confirmed_sats += tx_out.value;
+            } else {
+                unconfirmed_sats += tx_out.value as i64;
+            }
+
+            // Spent outputs are still indexed while the spender is in the mempool.
+            if matches!(tx_out.spent, SpentStatus::Spent(_)) {
+                unconfirmed_sats -= tx_out.value as i64;
+                continue;
+            }
+
+            for rune in tx_out.runes.iter().chain(&tx_out.risky_runes) {
+                *runes.entry(rune.rune_id).or_default() += rune.amount;
+            }
+        }
+
+        Ok(AddressBalance {
+            confirmed_sats,
+            unconfirmed_sats,
+            runes: runes
+                .into_iter()
+                .map(|(rune_id, amount)| RuneAmount::from((rune_id, amount)))
+                .collect(),
+        })
+    }
+
+    pub fn get_script_pubkey_outpoints(
+        &self,
+        address: &Address,
+        pagination: Pagination,
+    ) -> Result<AddressData> {
         let script_pubkey = address.script_pubkey();
         let outpoints = self.db.get_script_pubkey_outpoints(&script_pubkey, None)?;
         let outpoints_to_tx_out: HashMap<OutPoint, TxOutEntry> = self
@@ -295,13 +712,10 @@ impl Index {
             );
         }
 
-        let outpoint_txns: Vec<Txid> = outpoints.iter().map(|outpoint| outpoint.txid).collect();
-        let txns_confirming_block = self.db.get_transaction_confirming_blocks(&outpoint_txns)?;
-
+        // Balances are computed over every output, only the requested page is returned.
         let mut runes = HashMap::new();
         let mut value = 0;
-        let mut outputs = Vec::new();
-        for (outpoint, tx_out) in outpoints_to_tx_out {
+        for tx_out in outpoints_to_tx_out.values() {
             for rune in tx_out.runes.iter() {
                 runes
                     .entry(rune.rune_id)
@@ -317,19 +731,36 @@ impl Index {
             }
 
             value += tx_out.value;
+        }
+
+        let total_outputs = outpoints_to_tx_out.len() as u64;
 
-            let output = AddressTxOut::from((
-                outpoint,
-                tx_out,
-                block_id_to_transaction_status(
+        // Sort so that pages are stable between requests.
+        let mut sorted_tx_outs: Vec<(OutPoint, TxOutEntry)> =
+            outpoints_to_tx_out.into_iter().collect();
+        sorted_tx_outs.sort_unstable_by_key(|(outpoint, _)| *outpoint);
+
+        let page: Vec<(OutPoint, TxOutEntry)> = sorted_tx_outs
+            .into_iter()
+            .skip(pagination.skip as usize)
+            .take(pagination.limit as usize)
+            .collect();
+
+        let outpoint_txns: Vec<Txid> = page.iter().map(|(outpoint, _)| outpoint.txid).collect();
+        let txns_confirming_block = self.db.get_transaction_confirming_blocks(&outpoint_txns)?;
+
+        let outputs = page
+            .into_iter()
+            .map(|(outpoint, tx_out)| {
+                let status = block_id_to_transaction_status(
                     txns_confirming_block
                         .get(&outpoint.txid)
                         .and_then(|x| x.as_ref()),
-                ),
-            ));
+                );
 
-            outputs.push(output);
-        }
+                AddressTxOut::from((outpoint, tx_out, status))
+            })
+            .collect();
 
         Ok(AddressData {
             value,
@@ -338,6 +769,66 @@ impl Index {
                 .map(|(rune_id, amount)| RuneAmount::from((rune_id, amount)))
                 .collect(),
             outputs,
+            total_outputs,
+            offset: pagination.skip,
+        })
+    }
+
+    /// Unspent outputs of `address`, sorted by outpoint. Outputs spent by a
+    /// mempool transaction are flagged as pending spent, or left out unless
+    /// `include_mempool` is set.
+    pub fn get_address_utxos(
+        &self,
+        address: &Address,
+        include_mempool: bool,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<AddressUtxo>> {
+        self.get_script_pubkey_utxos(&address.script_pubkey(), include_mempool, pagination)
+    }
+
+    /// Like `get_address_utxos`, for any script, including ones without an
+    /// address. `OP_RETURN` outputs aren't indexed by script.
+    pub fn get_script_pubkey_utxos(
+        &self,
+        script_pubkey: &ScriptBuf,
+        include_mempool: bool,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<AddressUtxo>> {
+        let outpoints = self.db.get_script_pubkey_outpoints(script_pubkey, None)?;
+
+        let mut utxos: Vec<(OutPoint, TxOutEntry)> = self
+            .db
+            .get_tx_outs_with_mempool_spent_update(&outpoints, None)?
+            .into_iter()
+            .filter(|(_, tx_out)| include_mempool || matches!(tx_out.spent, SpentStatus::Unspent))
+            .collect();
+        utxos.sort_unstable_by_key(|(outpoint, _)| *outpoint);
+
+        let page: Vec<(OutPoint, TxOutEntry)> = utxos
+            .into_iter()
+            .skip(pagination.skip as usize)
+            .take(pagination.limit as usize)
+            .collect();
+
+        let txids: Vec<Txid> = page.iter().map(|(outpoint, _)| outpoint.txid).collect();
+        let txns_confirming_block = self.db.get_transaction_confirming_blocks(&txids)?;
+
+        let items = page
+            .into_iter()
+            .map(|(outpoint, tx_out)| {
+                let status = block_id_to_transaction_status(
+                    txns_confirming_block
+                        .get(&outpoint.txid)
+                        .and_then(|x| x.as_ref()),
+                );
+
+                AddressUtxo::from((outpoint, tx_out, status))
+            })
+            .collect();
+
+        Ok(PaginationResponse {
+            items,
+            offset: pagination.skip,
         })
     }
 
@@ -353,6 +844,28 @@ impl Index {
         Ok(self.db.get_transaction(txid, None)?)
     }
 
+    /// Resolves `wtxid` to its txid. Only segwit transactions are mapped, for
+    /// the others the wtxid is the txid of a stored transaction without witness.
+    pub fn get_txid_by_wtxid(&self, wtxid: &Wtxid) -> Result<Txid> {
+        match self.db.get_txid_by_wtxid(wtxid) {
+            Err(StoreError::NotFound(_)) => {
+                let txid = Txid::from_raw_hash(wtxid.to_raw_hash());
+                let transaction: Option<BitcoinTransaction> =
+                    match self.db.get_transaction_raw(&txid, None) {
+                        Ok(raw) => consensus::deserialize(&raw).ok(),
+                        Err(StoreError::NotFound(_)) => None,
+                        Err(err) => return Err(err.into()),
+                    };
+
+                match transaction {
+                    Some(transaction) if transaction.compute_wtxid() == *wtxid => Ok(txid),
+                    _ => Err(StoreError::NotFound(format!("wtxid {wtxid} is not indexed")).into()),
+                }
+            }
+            result => Ok(result?),
+        }
+    }
+
     pub fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus> {
         let result = self.db.get_transaction_confirming_block(txid);
         match result {
@@ -368,6 +881,10 @@ impl Index {
         }
     }
 
+    pub fn is_tx_in_mempool(&self, txid: &Txid) -> Result<bool> {
+        Ok(self.db.is_tx_in_mempool(txid)?)
+    }
+
     pub fn pre_index_new_submitted_transaction(&self, txid: &Txid) -> Result<()> {
         Ok(self.updater.pre_index_new_submitted_transaction(txid)?)
     }
@@ -378,6 +895,10 @@ impl Index {
             .remove_pre_index_new_submitted_transaction(txid)?)
     }
 
+    pub fn preview_transaction(&self, tx: &BitcoinTransaction) -> Result<TransactionStateChange> {
+        Ok(self.updater.preview_tx(tx)?)
+    }
+
     pub fn index_new_submitted_transaction(
         &self,
         txid: &Txid,
@@ -392,3 +913,136 @@ impl Index {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{db::RocksDB, models::BatchUpdate, models::BlockId, options::Options},
+        bitcoin::{
+            block::{Header, Version},
+            hashes::Hash,
+            CompactTarget, TxMerkleNode,
+        },
+        clap::Parser,
+    };
+
+    fn state_change(
+        etched: Option<(RuneId, Rune)>,
+        minted: Option<RuneAmount>,
+        rune: RuneAmount,
+    ) -> TransactionStateChange {
+        TransactionStateChange {
+            inputs: vec![],
+            outputs: vec![TxOutEntry {
+                runes: vec![rune],
+                risky_runes: vec![],
+                value: 546,
+                spent: SpentStatus::Unspent,
+            }],
+            etched,
+            minted,
+            burned: HashMap::new(),
+            is_coinbase: false,
+        }
+    }
+
+    #[test]
+    fn rune_chart_buckets_transactions_by_block_time() {
+        let data_dir =
+            std::env::temp_dir().join(format!("titan-rune-chart-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&data_dir);
+        let options = Options::parse_from([
+            "titan",
+            "--chain",
+            "regtest",
+            "--data-dir",
+            data_dir.to_str().unwrap(),
+        ]);
+        let settings = Settings::from(options);
+        let db = Arc::new(RocksDB::open(data_dir.to_str().unwrap()).unwrap());
+
+        let rune_id = RuneId { block: 1, tx: 1 };
+        let amount = |amount| RuneAmount { rune_id, amount };
+        let txid = |n: u8| Txid::from_byte_array([n; 32]);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        let mut confirm = |txid: Txid, height: u64, time: u32| {
+            let header = Header {
+                version: Version::ONE,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            };
+            let hash = header.block_hash();
+            update
+                .blocks
+                .insert(hash, Block::empty_block(height, header));
+            update
+                .transaction_confirming_block
+                .insert(txid, BlockId { hash, height });
+        };
+        // Etched with a premine of 500 in the first bucket, minted and
+        // transferred in the second, and a transfer after `to`.
+        confirm(txid(1), 1, 1_050);
+        confirm(txid(2), 2, 1_150);
+        confirm(txid(3), 2, 1_150);
+        confirm(txid(4), 3, 1_300);
+
+        update.tx_state_changes.insert(
+            txid(1),
+            state_change(Some((rune_id, Rune(1))), None, amount(500)),
+        );
+        update
+            .tx_state_changes
+            .insert(txid(2), state_change(None, Some(amount(100)), amount(100)));
+        update
+            .tx_state_changes
+            .insert(txid(3), state_change(None, None, amount(300)));
+        update
+            .tx_state_changes
+            .insert(txid(4), state_change(None, None, amount(200)));
+        update.runes.insert(
+            rune_id,
+            RuneEntry {
+                premine: 500,
+                ..RuneEntry::fixture()
+            },
+        );
+        update
+            .rune_transactions
+            .insert(rune_id, vec![txid(1), txid(2), txid(3), txid(4)]);
+        db.batch_update(&update, false).unwrap();
+
+        let bitcoin_rpc_pool = RpcClientPool::new(Arc::new(settings.clone()), 1);
+        let index = Index::new(db, bitcoin_rpc_pool, settings, None);
+        let buckets = index
+            .get_rune_chart(&rune_id, 500, 100, 1_000, 1_300)
+            .unwrap();
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        assert_eq!(
+            buckets,
+            [
+                RuneChartBucket {
+                    timestamp: 1_000,
+                    ..Default::default()
+                },
+                RuneChartBucket {
+                    timestamp: 1_100,
+                    mints: 1,
+                    minted: 100,
+                    transfers: 1,
+                    transferred: 300,
+                    unique_holders: 0,
+                },
+                RuneChartBucket {
+                    timestamp: 1_200,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+}
@@ -9,17 +9,19 @@ use {
                 Instructions,
             },
         },
-        Script, Transaction,
+        hashes::Hash,
+        Script, Transaction, Txid,
     },
     serde::{Deserialize, Serialize},
     std::{collections::BTreeMap, iter::Peekable},
+    titan_types::InscriptionId,
 };
 
 pub(crate) const PROTOCOL_ID: [u8; 3] = *b"ord";
 pub(crate) const BODY_TAG: [u8; 0] = [];
 
 type Result<T> = std::result::Result<T, bitcoin::script::Error>;
-type RawEnvelope = Envelope<Vec<Vec<u8>>>;
+pub(crate) type RawEnvelope = Envelope<Vec<Vec<u8>>>;
 pub(crate) type ParsedEnvelope = Envelope<Inscription>;
 
 #[derive(Default, PartialEq, Clone, Serialize, Deserialize, Debug, Eq)]
@@ -57,7 +59,11 @@ impl From<RawEnvelope> for ParsedEnvelope {
         // let delegate = Tag::Delegate.take(&mut fields);
         // let metadata = Tag::Metadata.take(&mut fields);
         // let metaprotocol = Tag::Metaprotocol.take(&mut fields);
-        // let parents = Tag::Parent.take_array(&mut fields);
+        let parents = Tag::Parent
+            .take_array(&mut fields)
+            .into_iter()
+            .filter_map(|parent| inscription_id_field(&parent))
+            .collect();
         // let pointer = Tag::Pointer.take(&mut fields);
         // let rune = Tag::Rune.take(&mut fields);
 
@@ -76,6 +82,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
                 }),
                 content_encoding,
                 content_type,
+                parents,
             },
             input: envelope.input,
             offset: envelope.offset,
@@ -85,6 +92,27 @@ impl From<RawEnvelope> for ParsedEnvelope {
     }
 }
 
+/// A txid followed by the little-endian index, either 4 bytes long or with
+/// trailing zeros removed, like ord encodes it.
+fn inscription_id_field(value: &[u8]) -> Option<InscriptionId> {
+    if value.len() < Txid::LEN || value.len() > Txid::LEN + 4 {
+        return None;
+    }
+
+    let (txid, index) = value.split_at(Txid::LEN);
+    if index.len() != 4 && index.last() == Some(&0) {
+        return None;
+    }
+
+    let mut index_bytes = [0; 4];
+    index_bytes[..index.len()].copy_from_slice(index);
+
+    Some(InscriptionId {
+        txid: Txid::from_slice(txid).ok()?,
+        index: u32::from_le_bytes(index_bytes),
+    })
+}
+
 impl ParsedEnvelope {
     pub(crate) fn from_transaction(transaction: &Transaction) -> Vec<Self> {
         RawEnvelope::from_transaction(transaction)
@@ -250,3 +278,25 @@ impl RawEnvelope {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_parent_ids() {
+        let txid = Txid::from_byte_array([7; 32]);
+        let field = |index: &[u8]| {
+            let mut value = txid.to_byte_array().to_vec();
+            value.extend_from_slice(index);
+            inscription_id_field(&value)
+        };
+
+        assert_eq!(field(&[]), Some(InscriptionId { txid, index: 0 }));
+        assert_eq!(field(&[1]), Some(InscriptionId { txid, index: 1 }));
+        assert_eq!(field(&[1, 0, 0, 0]), Some(InscriptionId { txid, index: 1 }));
+        assert_eq!(field(&[1, 0]), None);
+        assert_eq!(field(&[1, 0, 0, 0, 0]), None);
+        assert_eq!(inscription_id_field(&[7; 31]), None);
+    }
+}
@@ -6,24 +6,27 @@ use {
 };
 
 pub fn index_rune_icon(tx: &Transaction, txid: Txid) -> Option<(InscriptionId, Inscription)> {
-    let envelopes = ParsedEnvelope::from_transaction(tx);
+    let envelope = rune_icon_envelope(tx)?;
+    let inscription_id = InscriptionId { txid, index: 0 };
 
-    // In Rune etching, we just want to index the first envelope.
-    let envelope = envelopes.into_iter().next();
+    Some((inscription_id, envelope.payload))
+}
 
-    //
-    if let Some(envelope) = envelope {
-        let inscription = envelope.payload;
-        let media = inscription.media();
+/// The input whose witness holds the icon `index_rune_icon` finds. The icon
+/// starts out on that input's first sat.
+pub fn rune_icon_input(tx: &Transaction) -> Option<u32> {
+    Some(rune_icon_envelope(tx)?.input)
+}
 
-        if media.is_unknown() {
-            return None;
-        }
+fn rune_icon_envelope(tx: &Transaction) -> Option<ParsedEnvelope> {
+    let envelopes = ParsedEnvelope::from_transaction(tx);
 
-        let inscription_id = InscriptionId { txid, index: 0 };
+    // In Rune etching, we just want to index the first envelope.
+    let envelope = envelopes.into_iter().next()?;
 
-        return Some((inscription_id, inscription));
+    if envelope.payload.media().is_unknown() {
+        return None;
     }
 
-    None
+    Some(envelope)
 }
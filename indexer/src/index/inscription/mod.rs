@@ -1,6 +1,8 @@
 use {envelope::ParsedEnvelope, tag::Tag};
 
-pub use inscription_parser::index_rune_icon;
+pub(crate) use envelope::RawEnvelope;
+
+pub use inscription_parser::{index_rune_icon, rune_icon_input};
 
 mod envelope;
 mod inscription_parser;
@@ -80,6 +80,13 @@ impl Metrics {
         h
     }
 
+    /// Encodes every registered metric in the Prometheus text format.
+    pub fn encode(&self) -> prometheus::Result<String> {
+        let mut buffer = Vec::new();
+        prometheus::TextEncoder::new().encode(&self.reg.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+
     pub fn start(&self, shutdown_flag: Arc<AtomicBool>) -> thread::JoinHandle<()> {
         let registry = self.reg.clone();
         let handle = thread::spawn(move || loop {
@@ -1,10 +1,14 @@
 pub use {
     chain::Chain,
     index::{Index, IndexError},
+    metrics::Metrics,
     settings::Settings,
     store::StoreError,
+    updater::{ReindexError, UpdaterError},
 };
 
+pub(crate) use inscription::RawEnvelope;
+
 mod chain;
 mod index;
 mod inscription;
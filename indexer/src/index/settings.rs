@@ -16,6 +16,7 @@ pub struct Settings {
     pub(crate) no_index_inscriptions: bool,
     pub(crate) index_bitcoin_transactions: bool,
     pub(crate) index_addresses: bool,
+    pub(crate) index_sats: bool,
     pub(crate) commit_interval: u64,
     pub(crate) main_loop_interval: u64,
 }
@@ -2,17 +2,21 @@ use {
     crate::{
         db::{RocksDB, RocksDBError},
         models::{
-            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
-            TransactionStateChange,
+            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, OutputInscription,
+            RuneEntry, SatRange, TransactionStateChange,
         },
     },
-    bitcoin::{consensus, hex::HexToArrayError, BlockHash, OutPoint, ScriptBuf, Txid},
+    bitcoin::{consensus, hex::HexToArrayError, BlockHash, OutPoint, ScriptBuf, Txid, Wtxid},
     ordinals::{Rune, RuneId},
-    std::collections::{HashMap, HashSet},
+    std::{
+        collections::{HashMap, HashSet},
+        ops::Range,
+    },
     thiserror::Error,
     titan_types::{
-        Block, InscriptionId, MempoolEntry, Pagination, PaginationResponse, SpenderReference,
-        SpentStatus, Transaction, TransactionStatus, TxOutEntry,
+        Block, ColumnFamilyStats, InscriptionId, InscriptionsQuery, MempoolEntry, Order,
+        Pagination, PaginationResponse, RunesQuery, SpenderReference, SpentStatus, Transaction,
+        TransactionStatus, TxOutEntry,
     },
 };
 
@@ -49,6 +53,8 @@ pub trait Store {
     fn set_index_addresses(&self, value: bool) -> Result<(), StoreError>;
     fn is_index_bitcoin_transactions(&self) -> Result<Option<bool>, StoreError>;
     fn set_index_bitcoin_transactions(&self, value: bool) -> Result<(), StoreError>;
+    fn is_index_sats(&self) -> Result<Option<bool>, StoreError>;
+    fn set_index_sats(&self, value: bool) -> Result<(), StoreError>;
 
     // block
     fn get_block_count(&self) -> Result<u64, StoreError>;
@@ -64,6 +70,7 @@ pub trait Store {
     // mempool
     fn is_tx_in_mempool(&self, txid: &Txid) -> Result<bool, StoreError>;
     fn get_mempool_txids(&self) -> Result<HashMap<Txid, MempoolEntry>, StoreError>;
+    fn get_mempool_txids_since(&self, seq: u64) -> Result<(Vec<Txid>, u64), StoreError>;
     fn get_mempool_entry(&self, txid: &Txid) -> Result<MempoolEntry, StoreError>;
     fn get_mempool_entries(
         &self,
@@ -86,11 +93,20 @@ pub trait Store {
         outpoints: &Vec<OutPoint>,
         mempool: Option<bool>,
     ) -> Result<HashMap<OutPoint, TxOutEntry>, StoreError>;
+    fn get_sat_ranges(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<SatRange>>, StoreError>;
     fn get_tx_outs_with_mempool_spent_update(
         &self,
         outpoints: &Vec<OutPoint>,
         mempool: Option<bool>,
     ) -> Result<HashMap<OutPoint, TxOutEntry>, StoreError>;
+    fn get_tx_outs_by_txids(
+        &self,
+        txids: &[Txid],
+        mempool: bool,
+    ) -> Result<HashMap<OutPoint, TxOutEntry>, StoreError>;
 
     // transaction changes
     fn get_tx_state_changes(
@@ -116,6 +132,7 @@ pub trait Store {
         mempool: Option<bool>,
     ) -> Result<Transaction, StoreError>;
     fn get_transaction_confirming_block(&self, txid: &Txid) -> Result<BlockId, StoreError>;
+    fn get_txid_by_wtxid(&self, wtxid: &Wtxid) -> Result<Txid, StoreError>;
     fn get_transaction_confirming_blocks(
         &self,
         txids: &Vec<Txid>,
@@ -126,12 +143,24 @@ pub trait Store {
     ) -> Result<(Vec<Txid>, Vec<Txid>), StoreError>;
 
     // rune transactions
-    fn get_last_rune_transactions(
+    fn get_rune_transactions(
         &self,
         rune_id: &RuneId,
         pagination: Option<Pagination>,
         mempool: Option<bool>,
+        order: Order,
     ) -> Result<PaginationResponse<Txid>, StoreError>;
+    fn get_rune_transactions_last_index(
+        &self,
+        rune_id: &RuneId,
+        mempool: bool,
+    ) -> Result<u64, StoreError>;
+    fn get_rune_transactions_at(
+        &self,
+        rune_id: &RuneId,
+        indexes: &[u64],
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError>;
 
     // runes
     fn get_runes_count(&self) -> Result<u64, StoreError>;
@@ -143,11 +172,37 @@ pub trait Store {
     ) -> Result<HashMap<RuneId, RuneEntry>, StoreError>;
     fn get_runes(
         &self,
+        query: RunesQuery,
         pagination: Pagination,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError>;
+    /// Runes by etching number, oldest first.
+    fn get_runes_by_numbers(
+        &self,
+        numbers: Range<u64>,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError>;
+    fn search_runes(
+        &self,
+        name: &str,
+        limit: usize,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError>;
 
     // inscription
     fn get_inscription(&self, inscription_id: &InscriptionId) -> Result<Inscription, StoreError>;
+    fn get_inscription_children(
+        &self,
+        parent: &InscriptionId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<InscriptionId>, StoreError>;
+    fn get_output_inscriptions(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<OutputInscription>>, StoreError>;
+    fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, StoreError>;
+    fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<(InscriptionId, Inscription, u64)>, StoreError>;
 
     // address
     fn get_script_pubkey_outpoints(
@@ -162,6 +217,10 @@ pub trait Store {
         optimistic: bool,
     ) -> Result<HashMap<OutPoint, ScriptBuf>, StoreError>;
 
+    // stats
+    fn get_column_family_stats(&self) -> Result<Vec<ColumnFamilyStats>, StoreError>;
+    fn get_disk_size(&self) -> Result<u64, StoreError>;
+
     // batch
     fn batch_update(&self, update: &BatchUpdate, mempool: bool) -> Result<(), StoreError>;
     fn batch_delete(&self, delete: &BatchDelete) -> Result<(), StoreError>;
@@ -185,6 +244,14 @@ impl Store for RocksDB {
         Ok(self.set_index_bitcoin_transactions(value)?)
     }
 
+    fn is_index_sats(&self) -> Result<Option<bool>, StoreError> {
+        Ok(self.is_index_sats()?)
+    }
+
+    fn set_index_sats(&self, value: bool) -> Result<(), StoreError> {
+        Ok(self.set_index_sats(value)?)
+    }
+
     fn get_block_count(&self) -> Result<u64, StoreError> {
         Ok(self.get_block_count()?)
     }
@@ -222,32 +289,52 @@ impl Store for RocksDB {
 
     fn get_runes(
         &self,
+        query: RunesQuery,
         pagination: Pagination,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError> {
-        let runes_count = self.get_runes_count()?;
-        let (skip, limit) = pagination.into();
-
-        let start = runes_count.saturating_sub(skip);
-        let end = runes_count.saturating_sub(skip).saturating_sub(limit);
+        let numbers = self.get_rune_numbers_sorted(query.sort, query.order, pagination)?;
 
         let mut runes = Vec::new();
-        for i in (end..start).rev() {
-            let rune_id = self.get_rune_id_by_number(i)?;
+        for number in numbers.items {
+            let rune_id = self.get_rune_id_by_number(number)?;
             let rune_entry = self.get_rune(&rune_id)?;
             runes.push((rune_id, rune_entry));
         }
 
-        let offset = skip + runes.len() as u64;
         Ok(PaginationResponse {
             items: runes,
-            offset,
+            offset: numbers.offset,
         })
     }
 
+    fn get_runes_by_numbers(
+        &self,
+        numbers: Range<u64>,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError> {
+        numbers
+            .map(|number| {
+                let rune_id = self.get_rune_id_by_number(number)?;
+                Ok((rune_id, self.get_rune(&rune_id)?))
+            })
+            .collect()
+    }
+
+    fn search_runes(
+        &self,
+        name: &str,
+        limit: usize,
+    ) -> Result<Vec<(RuneId, RuneEntry)>, StoreError> {
+        Ok(self.search_runes(name, limit)?)
+    }
+
     fn get_mempool_txids(&self) -> Result<HashMap<Txid, MempoolEntry>, StoreError> {
         Ok(self.get_mempool_txids()?)
     }
 
+    fn get_mempool_txids_since(&self, seq: u64) -> Result<(Vec<Txid>, u64), StoreError> {
+        Ok(self.get_mempool_txids_since(seq)?)
+    }
+
     fn is_tx_in_mempool(&self, txid: &Txid) -> Result<bool, StoreError> {
         Ok(self.is_tx_in_mempool(txid)?)
     }
@@ -321,6 +408,21 @@ impl Store for RocksDB {
         Ok(self.get_tx_outs(outpoints, mempool)?)
     }
 
+    fn get_sat_ranges(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<SatRange>>, StoreError> {
+        Ok(self.get_sat_ranges(outpoints)?)
+    }
+
+    fn get_tx_outs_by_txids(
+        &self,
+        txids: &[Txid],
+        mempool: bool,
+    ) -> Result<HashMap<OutPoint, TxOutEntry>, StoreError> {
+        Ok(self.get_tx_outs_by_txids(txids, mempool)?)
+    }
+
     fn get_tx_outs_with_mempool_spent_update(
         &self,
         outpoints: &Vec<OutPoint>,
@@ -461,6 +563,14 @@ impl Store for RocksDB {
         }
     }
 
+    fn get_txid_by_wtxid(&self, wtxid: &Wtxid) -> Result<Txid, StoreError> {
+        match self.get_txid_by_wtxid(wtxid, false) {
+            Ok(txid) => Ok(txid),
+            Err(RocksDBError::NotFound(_)) => Ok(self.get_txid_by_wtxid(wtxid, true)?),
+            Err(other) => Err(StoreError::DB(other)),
+        }
+    }
+
     fn partition_transactions_by_existence(
         &self,
         txids: &HashSet<Txid>,
@@ -495,26 +605,113 @@ impl Store for RocksDB {
         Ok(self.get_inscription(inscription_id)?)
     }
 
-    fn get_last_rune_transactions(
+    fn get_inscription_children(
+        &self,
+        parent: &InscriptionId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<InscriptionId>, StoreError> {
+        Ok(self.get_inscription_children(parent, pagination)?)
+    }
+
+    fn get_output_inscriptions(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<OutputInscription>>, StoreError> {
+        Ok(self.get_output_inscriptions(outpoints)?)
+    }
+
+    fn get_sat_inscriptions(&self, sat: u64) -> Result<Vec<InscriptionId>, StoreError> {
+        Ok(self.get_sat_inscriptions(sat)?)
+    }
+
+    fn get_inscriptions(
+        &self,
+        query: &InscriptionsQuery,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<(InscriptionId, Inscription, u64)>, StoreError> {
+        let runes_count = self.get_runes_count()?;
+        let (skip, limit) = pagination.into();
+        let content_type_prefix = query.content_type.as_ref().map(|c| c.to_ascii_lowercase());
+
+        // Only rune etching inscriptions are indexed, so walk the runes from
+        // newest to oldest. `offset` counts scanned runes, not matches.
+        let mut inscriptions = Vec::new();
+        let mut scanned = 0;
+        for number in (0..runes_count.saturating_sub(skip)).rev() {
+            if inscriptions.len() as u64 >= limit {
+                break;
+            }
+
+            let rune_entry = self.get_rune(&self.get_rune_id_by_number(number)?)?;
+            if query
+                .from_height
+                .is_some_and(|height| rune_entry.block < height)
+            {
+                break;
+            }
+
+            scanned += 1;
+
+            if query
+                .to_height
+                .is_some_and(|height| rune_entry.block > height)
+            {
+                continue;
+            }
+
+            let Some(inscription_id) = rune_entry.inscription_id else {
+                continue;
+            };
+
+            let inscription = match self.get_inscription(&inscription_id) {
+                Ok(inscription) => inscription,
+                Err(RocksDBError::NotFound(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            if let Some(prefix) = &content_type_prefix {
+                let matches = inscription.content_type().is_some_and(|content_type| {
+                    content_type.to_ascii_lowercase().starts_with(prefix)
+                });
+                if !matches {
+                    continue;
+                }
+            }
+
+            inscriptions.push((inscription_id, inscription, rune_entry.block));
+        }
+
+        Ok(PaginationResponse {
+            items: inscriptions,
+            offset: skip + scanned,
+        })
+    }
+
+    fn get_rune_transactions(
         &self,
         rune_id: &RuneId,
         pagination: Option<Pagination>,
         mempool: Option<bool>,
+        order: Order,
     ) -> Result<PaginationResponse<Txid>, StoreError> {
         if let Some(mempool) = mempool {
-            Ok(self.get_last_rune_transactions(rune_id, pagination, mempool)?)
+            Ok(self.get_rune_transactions(rune_id, pagination, mempool, order)?)
         } else {
-            // First get mempool transactions
-            let mempool_txids = self.get_last_rune_transactions(rune_id, pagination, true)?;
+            // Mempool transactions are the newest ones, so they come first when
+            // descending and last when ascending.
+            let first_mempool = order == Order::Desc;
+
+            let first_txids =
+                self.get_rune_transactions(rune_id, pagination, first_mempool, order)?;
 
-            // Then get non-mempool transactions
+            // Then get the other transactions
             // Adapt pagination to offset
-            let non_mempool_pagination = match pagination {
+            let second_pagination = match pagination {
                 Some(pagination) => Pagination {
-                    skip: pagination.skip.saturating_sub(mempool_txids.offset),
+                    skip: pagination.skip.saturating_sub(first_txids.offset),
                     limit: pagination
                         .limit
-                        .saturating_sub(mempool_txids.items.len() as u64),
+                        .saturating_sub(first_txids.items.len() as u64),
                 },
                 None => Pagination {
                     skip: 0,
@@ -522,22 +719,43 @@ impl Store for RocksDB {
                 },
             };
 
-            let non_mempool_txids =
-                self.get_last_rune_transactions(rune_id, Some(non_mempool_pagination), false)?;
+            let second_txids = self.get_rune_transactions(
+                rune_id,
+                Some(second_pagination),
+                !first_mempool,
+                order,
+            )?;
 
-            let new_offset = mempool_txids.offset + non_mempool_txids.offset;
+            let new_offset = first_txids.offset + second_txids.offset;
 
             Ok(PaginationResponse {
-                items: mempool_txids
+                items: first_txids
                     .items
                     .into_iter()
-                    .chain(non_mempool_txids.items)
+                    .chain(second_txids.items)
                     .collect(),
                 offset: new_offset,
             })
         }
     }
 
+    fn get_rune_transactions_last_index(
+        &self,
+        rune_id: &RuneId,
+        mempool: bool,
+    ) -> Result<u64, StoreError> {
+        Ok(self.get_rune_transactions_last_index(rune_id, mempool)?)
+    }
+
+    fn get_rune_transactions_at(
+        &self,
+        rune_id: &RuneId,
+        indexes: &[u64],
+        mempool: bool,
+    ) -> Result<Vec<(u64, Txid)>, StoreError> {
+        Ok(self.get_rune_transactions_at(rune_id, indexes, mempool)?)
+    }
+
     fn get_script_pubkey_outpoints(
         &self,
         script_pubkey: &ScriptBuf,
@@ -596,6 +814,14 @@ impl Store for RocksDB {
         Ok(script_pubkeys)
     }
 
+    fn get_column_family_stats(&self) -> Result<Vec<ColumnFamilyStats>, StoreError> {
+        Ok(self.get_column_family_stats()?)
+    }
+
+    fn get_disk_size(&self) -> Result<u64, StoreError> {
+        Ok(self.get_disk_size()?)
+    }
+
     fn batch_update(&self, update: &BatchUpdate, mempool: bool) -> Result<(), StoreError> {
         Ok(self.batch_update(update, mempool)?)
     }
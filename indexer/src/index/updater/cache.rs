@@ -3,7 +3,8 @@ use {
     crate::{
         index::{store::StoreError, Chain, Settings},
         models::{
-            BatchDelete, BatchUpdate, BlockId, Inscription, RuneEntry, TransactionStateChange,
+            BatchDelete, BatchUpdate, BlockId, Inscription, OutputInscription, RuneEntry, SatRange,
+            TransactionStateChange,
         },
     },
     bitcoin::{consensus, BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
@@ -299,6 +300,70 @@ impl UpdaterCache {
         self.update.inscriptions.insert(inscription_id, inscription);
     }
 
+    /// The inscriptions on each of `outpoints` that holds any.
+    pub fn get_output_inscriptions(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<OutputInscription>>> {
+        let mut results = HashMap::new();
+        let mut to_fetch = Vec::new();
+        for outpoint in outpoints {
+            match self.update.output_inscriptions.get(outpoint) {
+                Some(inscriptions) => {
+                    results.insert(*outpoint, inscriptions.clone());
+                }
+                None => to_fetch.push(*outpoint),
+            }
+        }
+
+        if !to_fetch.is_empty() {
+            results.extend(self.db.read().get_output_inscriptions(&to_fetch)?);
+        }
+
+        Ok(results)
+    }
+
+    pub fn set_output_inscriptions(
+        &mut self,
+        outpoint: OutPoint,
+        inscriptions: Vec<OutputInscription>,
+    ) {
+        self.update
+            .output_inscriptions
+            .insert(outpoint, inscriptions);
+    }
+
+    /// The sat ranges on each of `outpoints` that has any.
+    pub fn get_sat_ranges(
+        &self,
+        outpoints: &[OutPoint],
+    ) -> Result<HashMap<OutPoint, Vec<SatRange>>> {
+        let mut results = HashMap::new();
+        let mut to_fetch = Vec::new();
+        for outpoint in outpoints {
+            match self.update.sat_ranges.get(outpoint) {
+                Some(sat_ranges) => {
+                    results.insert(*outpoint, sat_ranges.clone());
+                }
+                None => to_fetch.push(*outpoint),
+            }
+        }
+
+        if !to_fetch.is_empty() {
+            results.extend(self.db.read().get_sat_ranges(&to_fetch)?);
+        }
+
+        Ok(results)
+    }
+
+    pub fn set_sat_ranges(&mut self, outpoint: OutPoint, sat_ranges: Vec<SatRange>) {
+        self.update.sat_ranges.insert(outpoint, sat_ranges);
+    }
+
+    pub fn set_inscription_sat(&mut self, inscription_id: InscriptionId, sat: u64) {
+        self.update.inscription_sats.insert(inscription_id, sat);
+    }
+
     pub fn set_mempool_tx(&mut self, txid: Txid, mempool_entry: MempoolEntry) -> () {
         self.update.mempool_txs.insert(txid, mempool_entry);
     }
@@ -476,6 +541,8 @@ impl UpdaterCache {
 
             for txin in tx_state_changes.inputs.iter() {
                 self.delete.script_pubkeys_outpoints.insert(txin.clone());
+                self.delete.output_inscriptions.insert(*txin);
+                self.delete.sat_ranges.insert(*txin);
             }
 
             self.delete.tx_state_changes.insert(txid);
@@ -6,7 +6,7 @@ use {
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientPool, RpcClientPoolError, RpcClientProvider},
         index::{metrics::Metrics, store::Store, Settings, StoreError},
-        models::{BlockId, RuneEntry},
+        models::{BlockId, RuneEntry, TransactionStateChange},
     },
     address::AddressUpdater,
     bitcoin::{
@@ -22,21 +22,24 @@ use {
     indicatif::{ProgressBar, ProgressStyle},
     mempool::MempoolError,
     ordinals::{Rune, RuneId, SpacedRune, Terms},
+    pending_txs::PendingTxs,
     prometheus::HistogramVec,
+    reindex::{ReindexError, Reindexes},
     rollback::{Rollback, RollbackError},
+    sat_updater::SatUpdater,
     std::{
         collections::{HashMap, HashSet},
         fmt::{self, Display, Formatter},
         str::FromStr,
         sync::{
-            atomic::{AtomicBool, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, Mutex, RwLock,
         },
         time::{SystemTime, UNIX_EPOCH},
     },
     store_lock::StoreWithLock,
     thiserror::Error,
-    titan_types::{Block, Event, MempoolEntry},
+    titan_types::{Block, Event, MempoolEntry, ReindexRange},
     tokio::sync::mpsc::{error::SendError, Sender},
     tracing::{debug, error, info},
     transaction_parser::TransactionParser,
@@ -92,6 +95,8 @@ pub enum UpdaterError {
     InvalidMainChainTip,
     #[error("bitcoin rpc pool error {0}")]
     BitcoinRpcPool(#[from] RpcClientPoolError),
+    #[error("reindex error {0}")]
+    Reindex(#[from] ReindexError),
 }
 
 type Result<T> = std::result::Result<T, UpdaterError>;
@@ -100,6 +105,12 @@ pub struct Updater {
     db: Arc<StoreWithLock>,
     settings: Settings,
     is_at_tip: AtomicBool,
+    // Block count reported by the node on the last sync, 0 until known.
+    node_block_count: AtomicU64,
+    // Reorgs handled since startup. Bumped before rolling back.
+    reorgs: AtomicU64,
+    // When the cache was last flushed to the db, 0 until it was.
+    last_flush_epoch_secs: AtomicU64,
 
     bitcoin_rpc_pool: RpcClientPool,
 
@@ -113,6 +124,10 @@ pub struct Updater {
     transaction_update: RwLock<TransactionUpdate>,
 
     sender: Option<Sender<Event>>,
+    // Only tracked when there's a `sender`.
+    pending_txs: Mutex<PendingTxs>,
+
+    reindexes: Mutex<Reindexes>,
 
     // monitoring
     latency: HistogramVec,
@@ -132,12 +147,17 @@ impl Updater {
             settings,
             bitcoin_rpc_pool,
             is_at_tip: AtomicBool::new(false),
+            node_block_count: AtomicU64::new(0),
+            reorgs: AtomicU64::new(0),
+            last_flush_epoch_secs: AtomicU64::new(0),
             broadcast_lock: Mutex::new(()),
             pre_index_submitted_txs: RwLock::new(HashSet::new()),
             zmq_received_txs: RwLock::new(HashMap::new()),
             shutdown_flag,
             transaction_update: RwLock::new(TransactionUpdate::default()),
             sender,
+            pending_txs: Mutex::new(PendingTxs::default()),
+            reindexes: Mutex::new(Reindexes::default()),
             latency: metrics.histogram_vec(
                 prometheus::HistogramOpts::new("indexer_latency", "Indexer latency"),
                 &["method"],
@@ -149,6 +169,67 @@ impl Updater {
         self.is_at_tip.load(Ordering::Relaxed)
     }
 
+    /// Block count of the node as of the last sync, without calling the RPC.
+    pub fn node_block_count(&self) -> Option<u64> {
+        match self.node_block_count.load(Ordering::Relaxed) {
+            0 => None,
+            count => Some(count),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_node_block_count(&self, count: u64) {
+        self.node_block_count.store(count, Ordering::Relaxed);
+    }
+
+    /// Number of reorgs handled since startup. Changes as soon as a rollback
+    /// starts, so anything derived from the index before can be dropped.
+    pub fn reorgs(&self) -> u64 {
+        self.reorgs.load(Ordering::Acquire)
+    }
+
+    /// When indexed data was last flushed to the db since startup.
+    pub fn last_flush_epoch_secs(&self) -> Option<u64> {
+        match self.last_flush_epoch_secs.load(Ordering::Relaxed) {
+            0 => None,
+            secs => Some(secs),
+        }
+    }
+
+    /// Queues a rollback of `range`, so its blocks get indexed again on the
+    /// next update. Rollback data is only kept for the last
+    /// `max_recoverable_reorg_depth` blocks, so older ones can't be reindexed.
+    pub fn request_reindex(&self, range: ReindexRange) -> Result<()> {
+        let (block_count, purged_blocks_count) = {
+            let db = self.db.read();
+            (db.get_block_count()?, db.get_purged_blocks_count()?)
+        };
+        let lowest = block_count
+            .saturating_sub(self.settings.max_recoverable_reorg_depth())
+            .max(purged_blocks_count + 1);
+
+        self.reindexes
+            .lock()
+            .map_err(|_| UpdaterError::Mutex)?
+            .request(range, block_count.saturating_sub(1), lowest)?;
+
+        info!(
+            "Reindex of blocks {} to {} requested",
+            range.from_height, range.to_height
+        );
+        Ok(())
+    }
+
+    fn flush(&self, cache: &mut UpdaterCache) -> Result<()> {
+        cache.flush()?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.last_flush_epoch_secs.store(now, Ordering::Relaxed);
+        Ok(())
+    }
+
     fn is_chain_synced(
         &self,
         cache: &UpdaterCache,
@@ -173,6 +254,21 @@ impl Updater {
     pub fn update_to_tip(&self) -> Result<()> {
         debug!("Updating to tip");
 
+        let reindex = self
+            .reindexes
+            .lock()
+            .map_err(|_| UpdaterError::Mutex)?
+            .start();
+        if let Some(range) = reindex {
+            if let Err(err) = self.handle_reindex(range) {
+                self.reindexes
+                    .lock()
+                    .map_err(|_| UpdaterError::Mutex)?
+                    .abort();
+                return Err(err);
+            }
+        }
+
         // Every 5000 blocks, commit the changes to the database
         let commit_interval = self.settings.commit_interval as usize;
         let mut address_updater = AddressUpdater::new();
@@ -184,6 +280,8 @@ impl Updater {
         // Get RPC client and get block height
         let bitcoin_block_client = self.bitcoin_rpc_pool.get()?;
         let mut chain_info = bitcoin_block_client.get_blockchain_info()?;
+        self.node_block_count
+            .store(chain_info.blocks + 1, Ordering::Relaxed);
 
         let mut first_block = true;
 
@@ -218,9 +316,17 @@ impl Updater {
                     ) {
                         Ok(()) => (),
                         Err(ReorgError::Recoverable { height, depth }) => {
+                            let old_blockhash = self.db.read().get_block_hash(height - 1)?;
+                            let new_blockhash = block.header.prev_blockhash;
+
                             self.handle_reorg(height, depth)?;
                             if let Some(sender) = &self.sender {
-                                sender.blocking_send(Event::Reorg { height, depth })?;
+                                sender.blocking_send(Event::Reorg {
+                                    height,
+                                    depth,
+                                    old_blockhash,
+                                    new_blockhash,
+                                })?;
                             }
                             return Err(ReorgError::Recoverable { height, depth }.into());
                         }
@@ -255,7 +361,7 @@ impl Updater {
                     }
 
                     cache.add_address_events(self.settings.chain);
-                    cache.flush()?;
+                    self.flush(&mut cache)?;
                     cache.send_events(&self.sender)?;
                 }
 
@@ -277,6 +383,8 @@ impl Updater {
 
             info!("Synced to tip {}", chain_info.blocks);
             chain_info = bitcoin_block_client.get_blockchain_info()?;
+            self.node_block_count
+                .store(chain_info.blocks + 1, Ordering::Relaxed);
             progress_bar.finish_and_clear();
         }
 
@@ -290,13 +398,31 @@ impl Updater {
         }
 
         cache.add_address_events(self.settings.chain);
-        cache.flush()?;
+        self.flush(&mut cache)?;
         cache.send_events(&self.sender)?;
 
         if !self.shutdown_flag.load(Ordering::SeqCst) {
             self.is_at_tip.store(true, Ordering::Release);
         }
 
+        let reindexed = self
+            .reindexes
+            .lock()
+            .map_err(|_| UpdaterError::Mutex)?
+            .finish(cache.get_block_height_tip());
+        if let Some(range) = reindexed {
+            info!(
+                "Reindexed blocks {} to {}",
+                range.from_height, range.to_height
+            );
+            if let Some(sender) = &self.sender {
+                sender.blocking_send(Event::ReindexFinished {
+                    from_height: range.from_height,
+                    to_height: range.to_height,
+                })?;
+            }
+        }
+
         Ok(())
     }
 
@@ -384,7 +510,13 @@ impl Updater {
             cache.add_address_events(self.settings.chain);
         }
 
-        cache.flush()?;
+        // After the new transactions, so replacements are announced before the
+        // transactions they replace are dropped.
+        if self.sender.is_some() {
+            self.add_pending_follow_up_events(&client, &mut cache, &removed_txs)?;
+        }
+
+        self.flush(&mut cache)?;
         cache.send_events(&self.sender)?;
 
         let removed_len = removed_txs.len();
@@ -462,6 +594,35 @@ impl Updater {
         Ok(())
     }
 
+    fn add_pending_follow_up_events(
+        &self,
+        client: &Client,
+        cache: &mut UpdaterCache,
+        removed_txids: &[Txid],
+    ) -> Result<()> {
+        let mut pending_txs = self.pending_txs.lock().map_err(|_| UpdaterError::Mutex)?;
+        pending_txs.leave_mempool(removed_txids);
+        if !pending_txs.has_left_mempool() {
+            return Ok(());
+        }
+
+        let node_ahead = client.get_block_count()? + 1 > cache.get_block_count();
+        let events = pending_txs.resolve(
+            |txid| {
+                cache
+                    .get_transaction_confirming_block(*txid)
+                    .ok()
+                    .map(|block| block.height)
+            },
+            node_ahead,
+        );
+        for event in events {
+            cache.add_event(event);
+        }
+
+        Ok(())
+    }
+
     fn choose_mempool_transactions_to_index(
         &self,
         new_txs: &Vec<Txid>,
@@ -548,6 +709,8 @@ impl Updater {
 
         let mut block = Block::empty_block(height, bitcoin_block.header);
 
+        let mut sat_updater = self.settings.index_sats.then(|| SatUpdater::new(height));
+
         let mut transaction_update = self
             .transaction_update
             .write()
@@ -558,6 +721,12 @@ impl Updater {
             match transaction_parser.parse(cache, u32::try_from(i).unwrap(), tx) {
                 Ok(result) => {
                     debug!("Indexing tx {} in block {}", txid, block_height);
+                    let inscriptions = transaction_parser.inscriptions(
+                        cache,
+                        txid,
+                        tx,
+                        result.etched.is_some(),
+                    )?;
                     transaction_updater.save(
                         cache,
                         block_header.time,
@@ -570,6 +739,10 @@ impl Updater {
                         &result,
                         None,
                     )?;
+                    transaction_updater.save_inscriptions(cache, txid, &result, inscriptions);
+                    if let Some(sat_updater) = sat_updater.as_mut().filter(|_| i > 0) {
+                        sat_updater.index_tx(cache, txid, tx, result.etched.is_some())?;
+                    }
                     block.tx_ids.push(txid.to_string());
                     transaction_update.add_block_tx(txid);
                     if let Some((id, ..)) = result.etched {
@@ -582,6 +755,10 @@ impl Updater {
             }
         }
 
+        if let (Some(sat_updater), Some(coinbase)) = (sat_updater, bitcoin_block.txdata.first()) {
+            sat_updater.index_coinbase(cache, coinbase.compute_txid(), coinbase);
+        }
+
         Ok(block)
     }
 
@@ -634,7 +811,7 @@ impl Updater {
             }
 
             cache.add_address_events(self.settings.chain);
-            cache.flush()?;
+            self.flush(&mut cache)?;
             cache.send_events(&self.sender)?;
         }
 
@@ -642,6 +819,25 @@ impl Updater {
         Ok(())
     }
 
+    /// Parses `tx` as if it had just entered the mempool, without storing
+    /// anything, to show the rune balances it would move.
+    pub fn preview_tx(&self, tx: &Transaction) -> Result<TransactionStateChange> {
+        let cache = UpdaterCache::new(
+            self.db.clone(),
+            UpdaterCacheSettings::new(&self.settings, true),
+        )?;
+
+        let rpc_client = self.bitcoin_rpc_pool.get()?;
+        let mut transaction_parser = TransactionParser::new(
+            &rpc_client,
+            self.settings.chain,
+            cache.get_block_count(),
+            true,
+        )?;
+
+        Ok(transaction_parser.parse(&cache, 0, tx)?)
+    }
+
     pub fn index_zmq_tx(&self, txid: Txid, tx: Transaction) -> Result<()> {
         self.zmq_received_txs
             .write()
@@ -690,9 +886,35 @@ impl Updater {
         let mut transaction_updater =
             TransactionUpdater::new(self.settings.clone().into(), address_updater)?;
 
+        let is_mempool = mempool_entry.is_some();
+
         // The same "save" logic as before
         transaction_updater.save(cache, now as u32, None, *txid, tx, &result, mempool_entry)?;
 
+        if is_mempool && self.sender.is_some() {
+            let spent = if self.settings.index_addresses {
+                cache
+                    .get_outpoints_to_script_pubkey(&result.inputs, true)
+                    .unwrap_or_default()
+            } else {
+                HashMap::new()
+            };
+            let (addresses, runes) = pending_txs::touched(
+                self.settings.chain,
+                tx,
+                &result,
+                spent.values().map(|script| script.as_script()),
+            );
+            let events = self
+                .pending_txs
+                .lock()
+                .map_err(|_| UpdaterError::Mutex)?
+                .announce(*txid, tx, addresses, runes);
+            for event in events {
+                cache.add_event(event);
+            }
+        }
+
         Ok(true)
     }
 
@@ -751,23 +973,47 @@ impl Updater {
     fn handle_reorg(&self, height: u64, depth: u64) -> Result<()> {
         // we're not at tip anymore.
         self.is_at_tip.store(false, Ordering::Release);
+        self.reorgs.fetch_add(1, Ordering::AcqRel);
 
         info!(
             "Reorg detected at height {}, rolling back {} blocks",
             height, depth
         );
 
-        {
-            let db = self.db.write();
+        // +1 because this is the block count and not the block height, therefore block count is always height + 1.
+        self.roll_back(height, height - depth + 1)
+    }
+
+    fn handle_reindex(&self, range: ReindexRange) -> Result<()> {
+        // Cached derived data is just as stale as after a reorg.
+        self.is_at_tip.store(false, Ordering::Release);
+        self.reorgs.fetch_add(1, Ordering::AcqRel);
+
+        info!(
+            "Reindexing blocks {} to {}, rolling them back",
+            range.from_height, range.to_height
+        );
 
-            // rollback block count indexed.
-            // +1 because this is the block count and not the block height, therefore block count is always height + 1.
-            db.set_block_count(height - depth + 1)?;
+        let block_count = self.db.read().get_block_count()?;
+        self.roll_back(block_count, range.from_height)?;
+
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(Event::Reindex {
+                from_height: range.from_height,
+                to_height: range.to_height,
+            })?;
         }
 
+        Ok(())
+    }
+
+    /// Reverts the blocks from `block_count - 1` down to `to_block_count`.
+    fn roll_back(&self, block_count: u64, to_block_count: u64) -> Result<()> {
+        // rollback block count indexed.
+        self.db.write().set_block_count(to_block_count)?;
+
         // Find rolled back blocks and revert those txs.
-        for i in 1..depth {
-            let block_height_rolled_back = height - i;
+        for block_height_rolled_back in (to_block_count..block_count).rev() {
             let block = self.get_block_by_height(block_height_rolled_back)?;
             self.revert_block(block_height_rolled_back as u32, &block)?;
         }
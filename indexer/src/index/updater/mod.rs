@@ -1,5 +1,6 @@
 pub use {
     index_updater::{ReorgError, Updater, UpdaterError},
+    reindex::ReindexError,
     transaction_parser::TransactionParserError,
     transaction_updater::TransactionUpdaterError,
 };
@@ -9,8 +10,11 @@ mod block_fetcher;
 mod cache;
 mod index_updater;
 mod mempool;
+mod pending_txs;
+mod reindex;
 mod rollback;
 mod rollback_cache;
+mod sat_updater;
 mod store_lock;
 mod transaction_parser;
 mod transaction_update;
@@ -0,0 +1,247 @@
+use {
+    crate::{index::Chain, models::TransactionStateChange},
+    bitcoin::{OutPoint, Script, Transaction, Txid},
+    ordinals::RuneId,
+    std::collections::{BTreeSet, HashMap},
+    titan_types::Event,
+};
+
+/// Addresses and runes of `Event::PendingTransaction`. `spent` are the script
+/// pubkeys of the outputs the transaction spends, as far as they're known.
+pub(super) fn touched<'a>(
+    chain: Chain,
+    tx: &'a Transaction,
+    result: &TransactionStateChange,
+    spent: impl IntoIterator<Item = &'a Script>,
+) -> (Vec<String>, Vec<RuneId>) {
+    let addresses: BTreeSet<String> = tx
+        .output
+        .iter()
+        .map(|output| output.script_pubkey.as_script())
+        .chain(spent)
+        .filter_map(|script| chain.address_from_script(script).ok())
+        .map(|address| address.to_string())
+        .collect();
+
+    let mut runes: BTreeSet<RuneId> = result
+        .outputs
+        .iter()
+        .flat_map(|output| output.runes.iter().chain(&output.risky_runes))
+        .map(|rune_amount| rune_amount.rune_id)
+        .collect();
+    runes.extend(result.etched.map(|(rune_id, _)| rune_id));
+    runes.extend(result.minted.as_ref().map(|minted| minted.rune_id));
+    runes.extend(result.burned.keys());
+
+    (addresses.into_iter().collect(), runes.into_iter().collect())
+}
+
+struct Touched {
+    addresses: Vec<String>,
+    runes: Vec<RuneId>,
+    spends: Vec<OutPoint>,
+}
+
+/// Mempool transactions announced with `Event::PendingTransaction`, so the
+/// follow-up event carries the same addresses and runes.
+#[derive(Default)]
+pub(super) struct PendingTxs {
+    touched: HashMap<Txid, Touched>,
+    /// Announced transaction spending each outpoint, to tell replacements.
+    spent_by: HashMap<OutPoint, Txid>,
+    /// Announced transactions that left the mempool but aren't known to be
+    /// confirmed yet.
+    left_mempool: Vec<Txid>,
+}
+
+impl PendingTxs {
+    /// `Event::PendingTransaction` of `tx`, followed by an
+    /// `Event::PendingTransactionReplaced` for each announced transaction that
+    /// spends one of the same outputs.
+    pub(super) fn announce(
+        &mut self,
+        txid: Txid,
+        tx: &Transaction,
+        addresses: Vec<String>,
+        runes: Vec<RuneId>,
+    ) -> Vec<Event> {
+        let mut events = vec![Event::PendingTransaction {
+            txid,
+            addresses: addresses.clone(),
+            runes: runes.clone(),
+        }];
+
+        let spends: Vec<OutPoint> = tx.input.iter().map(|input| input.previous_output).collect();
+        let mut replaced = BTreeSet::new();
+        for outpoint in &spends {
+            match self.spent_by.insert(*outpoint, txid) {
+                Some(old_txid) if old_txid != txid => {
+                    replaced.insert(old_txid);
+                }
+                _ => {}
+            }
+        }
+        for old_txid in replaced {
+            if let Some(old) = self.touched.get(&old_txid) {
+                events.push(Event::PendingTransactionReplaced {
+                    old_txid,
+                    new_txid: txid,
+                    addresses: old.addresses.clone(),
+                    runes: old.runes.clone(),
+                });
+            }
+        }
+
+        self.touched.insert(
+            txid,
+            Touched {
+                addresses,
+                runes,
+                spends,
+            },
+        );
+        events
+    }
+
+    pub(super) fn leave_mempool(&mut self, txids: &[Txid]) {
+        self.left_mempool
+            .extend(txids.iter().filter(|txid| self.touched.contains_key(*txid)));
+    }
+
+    pub(super) fn has_left_mempool(&self) -> bool {
+        !self.left_mempool.is_empty()
+    }
+
+    /// Follow-up events of the transactions that left the mempool.
+    /// `confirmed_at` is the height of the block a transaction was confirmed
+    /// in. Unconfirmed ones are only reported as dropped once the node has no
+    /// blocks left to index, since a transaction leaves the node's mempool
+    /// before its block is indexed.
+    pub(super) fn resolve(
+        &mut self,
+        confirmed_at: impl Fn(&Txid) -> Option<u64>,
+        node_ahead: bool,
+    ) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.left_mempool.retain(|txid| {
+            let block_height = confirmed_at(txid);
+            if block_height.is_none() && node_ahead {
+                return true;
+            }
+
+            let Some(Touched {
+                addresses,
+                runes,
+                spends,
+            }) = self.touched.remove(txid)
+            else {
+                return false;
+            };
+            for outpoint in spends {
+                if self.spent_by.get(&outpoint) == Some(txid) {
+                    self.spent_by.remove(&outpoint);
+                }
+            }
+            events.push(match block_height {
+                Some(block_height) => Event::PendingTransactionConfirmed {
+                    txid: *txid,
+                    block_height,
+                    addresses,
+                    runes,
+                },
+                None => Event::PendingTransactionDropped {
+                    txid: *txid,
+                    addresses,
+                    runes,
+                },
+            });
+            false
+        });
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoin::{absolute::LockTime, hashes::Hash, transaction::Version, TxIn},
+    };
+
+    fn spending(vout: u32) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout,
+                },
+                ..Default::default()
+            }],
+            output: vec![],
+        }
+    }
+
+    #[test]
+    fn resolves_transactions_that_left_the_mempool() {
+        let mined = Txid::from_byte_array([1; 32]);
+        let replaced = Txid::from_byte_array([2; 32]);
+        let unknown = Txid::from_byte_array([3; 32]);
+
+        let mut pending_txs = PendingTxs::default();
+        pending_txs.announce(mined, &spending(1), vec!["address".into()], vec![]);
+        pending_txs.announce(
+            replaced,
+            &spending(2),
+            vec![],
+            vec![RuneId { block: 1, tx: 2 }],
+        );
+        pending_txs.leave_mempool(&[mined, replaced, unknown]);
+
+        let confirmed_at = |txid: &Txid| (*txid == mined).then_some(10);
+
+        let events = pending_txs.resolve(confirmed_at, true);
+        assert_eq!(
+            events,
+            vec![Event::PendingTransactionConfirmed {
+                txid: mined,
+                block_height: 10,
+                addresses: vec!["address".into()],
+                runes: vec![],
+            }]
+        );
+        assert!(pending_txs.has_left_mempool());
+
+        let events = pending_txs.resolve(confirmed_at, false);
+        assert_eq!(
+            events,
+            vec![Event::PendingTransactionDropped {
+                txid: replaced,
+                addresses: vec![],
+                runes: vec![RuneId { block: 1, tx: 2 }],
+            }]
+        );
+        assert!(!pending_txs.has_left_mempool());
+    }
+
+    #[test]
+    fn reports_replacements_with_the_original_filters() {
+        let original = Txid::from_byte_array([1; 32]);
+        let replacement = Txid::from_byte_array([2; 32]);
+
+        let mut pending_txs = PendingTxs::default();
+        pending_txs.announce(original, &spending(0), vec!["address".into()], vec![]);
+        let events = pending_txs.announce(replacement, &spending(0), vec![], vec![]);
+
+        assert_eq!(
+            events[1..],
+            [Event::PendingTransactionReplaced {
+                old_txid: original,
+                new_txid: replacement,
+                addresses: vec!["address".into()],
+                runes: vec![],
+            }]
+        );
+    }
+}
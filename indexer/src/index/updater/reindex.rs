@@ -0,0 +1,153 @@
+use {thiserror::Error, titan_types::ReindexRange};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReindexError {
+    #[error("from_height {from_height} is above to_height {to_height}")]
+    InvalidRange { from_height: u64, to_height: u64 },
+    #[error("to_height {to_height} isn't the indexed tip {tip}")]
+    NotAtTip { to_height: u64, tip: u64 },
+    #[error("blocks below {lowest} can't be rolled back anymore")]
+    TooDeep { lowest: u64 },
+    #[error("blocks {from_height} to {to_height} are already being reindexed")]
+    Overlapping { from_height: u64, to_height: u64 },
+}
+
+/// Reindex requested through the API. It's queued here and done by the
+/// indexer thread at the start of its next update, so a rollback never runs
+/// alongside block indexing.
+#[derive(Default)]
+pub(super) struct Reindexes {
+    queued: Option<ReindexRange>,
+    running: Option<ReindexRange>,
+}
+
+impl Reindexes {
+    /// Queues `range`. Blocks above it depend on it, so it has to end at the
+    /// indexed `tip`, and it can't start below `lowest`, the first block whose
+    /// rollback data hasn't been purged.
+    pub(super) fn request(
+        &mut self,
+        range: ReindexRange,
+        tip: u64,
+        lowest: u64,
+    ) -> Result<(), ReindexError> {
+        let ReindexRange {
+            from_height,
+            to_height,
+        } = range;
+
+        if from_height > to_height {
+            return Err(ReindexError::InvalidRange {
+                from_height,
+                to_height,
+            });
+        }
+
+        if let Some(other) = self
+            .queued
+            .iter()
+            .chain(&self.running)
+            .find(|other| from_height <= other.to_height && other.from_height <= to_height)
+        {
+            return Err(ReindexError::Overlapping {
+                from_height: other.from_height,
+                to_height: other.to_height,
+            });
+        }
+
+        if to_height != tip {
+            return Err(ReindexError::NotAtTip { to_height, tip });
+        }
+
+        if from_height < lowest {
+            return Err(ReindexError::TooDeep { lowest });
+        }
+
+        self.queued = Some(range);
+        Ok(())
+    }
+
+    /// The queued reindex, now running.
+    pub(super) fn start(&mut self) -> Option<ReindexRange> {
+        let range = self.queued.take()?;
+        self.running = Some(range);
+        Some(range)
+    }
+
+    /// The running reindex failed. Nothing would finish it then, and it
+    /// would make every overlapping request fail.
+    pub(super) fn abort(&mut self) -> Option<ReindexRange> {
+        self.running.take()
+    }
+
+    /// The running reindex, if the index got back to its end.
+    pub(super) fn finish(&mut self, tip: u64) -> Option<ReindexRange> {
+        self.running.take_if(|range| range.to_height <= tip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(from_height: u64, to_height: u64) -> ReindexRange {
+        ReindexRange {
+            from_height,
+            to_height,
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_and_overlapping_ranges() {
+        let mut reindexes = Reindexes::default();
+
+        assert_eq!(
+            reindexes.request(range(105, 100), 100, 91),
+            Err(ReindexError::InvalidRange {
+                from_height: 105,
+                to_height: 100
+            })
+        );
+        assert_eq!(
+            reindexes.request(range(95, 99), 100, 91),
+            Err(ReindexError::NotAtTip {
+                to_height: 99,
+                tip: 100
+            })
+        );
+        assert_eq!(
+            reindexes.request(range(90, 100), 100, 91),
+            Err(ReindexError::TooDeep { lowest: 91 })
+        );
+
+        reindexes.request(range(95, 100), 100, 91).unwrap();
+        assert_eq!(
+            reindexes.request(range(98, 100), 100, 91),
+            Err(ReindexError::Overlapping {
+                from_height: 95,
+                to_height: 100
+            })
+        );
+
+        assert_eq!(reindexes.start(), Some(range(95, 100)));
+        assert_eq!(reindexes.start(), None);
+        assert!(reindexes.request(range(96, 97), 97, 91).is_err());
+
+        assert_eq!(reindexes.finish(99), None);
+        assert_eq!(reindexes.finish(100), Some(range(95, 100)));
+        reindexes.request(range(96, 100), 100, 91).unwrap();
+    }
+
+    #[test]
+    fn aborted_reindex_can_be_requested_again() {
+        let mut reindexes = Reindexes::default();
+
+        reindexes.request(range(95, 100), 100, 91).unwrap();
+        assert_eq!(reindexes.start(), Some(range(95, 100)));
+        assert!(reindexes.request(range(95, 100), 100, 91).is_err());
+
+        assert_eq!(reindexes.abort(), Some(range(95, 100)));
+        assert_eq!(reindexes.finish(100), None);
+        reindexes.request(range(95, 100), 100, 91).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+use {
+    super::cache::UpdaterCache,
+    crate::{
+        index::{inscription::rune_icon_input, StoreError},
+        models::SatRange,
+        util::IntoUsize,
+    },
+    bitcoin::{OutPoint, Transaction, Txid},
+    ordinals::Height,
+    titan_types::InscriptionId,
+};
+
+type Result<T> = std::result::Result<T, StoreError>;
+
+/// Follows a block's sats from the outputs they're on to the outputs that
+/// spend them, first in first out, so that inscriptions can be found by
+/// their genesis sat. The sats a transaction doesn't send to its outputs are
+/// its fee, and go to the coinbase after the block's new sats.
+pub(super) struct SatUpdater {
+    height: u64,
+    fees: Vec<SatRange>,
+}
+
+impl SatUpdater {
+    pub(super) fn new(height: u64) -> Self {
+        Self {
+            height,
+            fees: Vec::new(),
+        }
+    }
+
+    /// Moves the sats on `tx`'s inputs to its outputs. The rune icon it
+    /// reveals if `etched` is on the first sat of the input it's in.
+    pub(super) fn index_tx(
+        &mut self,
+        cache: &mut UpdaterCache,
+        txid: Txid,
+        tx: &Transaction,
+        etched: bool,
+    ) -> Result<()> {
+        let outpoints: Vec<OutPoint> = tx.input.iter().map(|input| input.previous_output).collect();
+        let mut on_inputs = cache.get_sat_ranges(&outpoints)?;
+
+        let mut inputs = Vec::with_capacity(outpoints.len());
+        for outpoint in outpoints.iter() {
+            inputs.push(
+                on_inputs
+                    .remove(outpoint)
+                    .ok_or_else(|| StoreError::NotFound(format!("sat ranges of {outpoint}")))?,
+            );
+        }
+
+        if let Some(input) = rune_icon_input(tx).filter(|_| etched) {
+            if let Some(sat_range) = inputs[input.into_usize()].first() {
+                cache.set_inscription_sat(InscriptionId { txid, index: 0 }, sat_range.start);
+            }
+        }
+
+        let fee = Self::send_to_outputs(cache, txid, tx, inputs.concat());
+        self.fees.extend(fee);
+
+        Ok(())
+    }
+
+    /// Moves the block's new sats, and then the fees of its other
+    /// transactions, to the coinbase's outputs. The ones it doesn't claim are
+    /// lost.
+    pub(super) fn index_coinbase(self, cache: &mut UpdaterCache, txid: Txid, tx: &Transaction) {
+        let height = Height(u32::try_from(self.height).unwrap());
+        let start = height.starting_sat().n();
+
+        let mut sats = vec![SatRange {
+            start,
+            end: start + height.subsidy(),
+        }];
+        sats.extend(self.fees);
+
+        Self::send_to_outputs(cache, txid, tx, sats);
+    }
+
+    /// Puts `sats` on `tx`'s outputs, returning the ones left over. Outputs
+    /// that can't be spent don't keep theirs.
+    fn send_to_outputs(
+        cache: &mut UpdaterCache,
+        txid: Txid,
+        tx: &Transaction,
+        sats: Vec<SatRange>,
+    ) -> Vec<SatRange> {
+        let output_values: Vec<u64> = tx
+            .output
+            .iter()
+            .map(|output| output.value.to_sat())
+            .collect();
+        let (outputs, left_over) = split_sat_ranges(sats, &output_values);
+
+        for (vout, (output, sat_ranges)) in tx.output.iter().zip(outputs).enumerate() {
+            if !output.script_pubkey.is_op_return() {
+                let outpoint = OutPoint {
+                    txid,
+                    vout: vout as u32,
+                };
+                cache.set_sat_ranges(outpoint, sat_ranges);
+            }
+        }
+
+        left_over
+    }
+}
+
+/// Splits `sats` across outputs worth `output_values`, in order, returning
+/// each output's ranges and the ones left over.
+fn split_sat_ranges(
+    sats: Vec<SatRange>,
+    output_values: &[u64],
+) -> (Vec<Vec<SatRange>>, Vec<SatRange>) {
+    let mut sats = sats
+        .into_iter()
+        .filter(|sat_range| sat_range.start < sat_range.end);
+    let mut split: Option<SatRange> = None;
+
+    let mut outputs = Vec::with_capacity(output_values.len());
+    for value in output_values {
+        let mut remaining = *value;
+        let mut sat_ranges = Vec::new();
+
+        while remaining > 0 {
+            let Some(sat_range) = split.take().or_else(|| sats.next()) else {
+                break;
+            };
+
+            let size = sat_range.end - sat_range.start;
+            if size > remaining {
+                let end = sat_range.start + remaining;
+                sat_ranges.push(SatRange {
+                    start: sat_range.start,
+                    end,
+                });
+                split = Some(SatRange {
+                    start: end,
+                    end: sat_range.end,
+                });
+                remaining = 0;
+            } else {
+                sat_ranges.push(sat_range);
+                remaining -= size;
+            }
+        }
+
+        outputs.push(sat_ranges);
+    }
+
+    (outputs, split.into_iter().chain(sats).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sats(start: u64, end: u64) -> SatRange {
+        SatRange { start, end }
+    }
+
+    #[test]
+    fn sats_go_to_the_outputs_in_order_and_the_rest_is_left_over() {
+        let (outputs, left_over) = split_sat_ranges(
+            vec![sats(100, 1_100), sats(0, 0), sats(5_000, 5_500)],
+            &[600, 0, 700, 100],
+        );
+
+        assert_eq!(
+            outputs,
+            [
+                vec![sats(100, 700)],
+                vec![],
+                vec![sats(700, 1_100), sats(5_000, 5_300)],
+                vec![sats(5_300, 5_400)],
+            ]
+        );
+        assert_eq!(left_over, [sats(5_400, 5_500)]);
+
+        // Outputs worth more than the sats coming in get what there is.
+        let (outputs, left_over) = split_sat_ranges(vec![sats(0, 50)], &[100, 100]);
+        assert_eq!(outputs, [vec![sats(0, 50)], vec![]]);
+        assert!(left_over.is_empty());
+    }
+}
@@ -2,16 +2,16 @@ use {
     super::cache::UpdaterCache,
     crate::{
         bitcoin_rpc::BitcoinCoreRpcResultExt,
-        index::{Chain, StoreError},
-        models::{Lot, TransactionStateChange},
+        index::{inscription::rune_icon_input, Chain, StoreError},
+        models::{Lot, OutputInscription, TransactionStateChange},
         util::IntoUsize,
     },
-    bitcoin::{consensus::encode, OutPoint, Transaction},
+    bitcoin::{consensus::encode, OutPoint, Transaction, Txid},
     bitcoincore_rpc::{Client, RpcApi},
     ordinals::{Artifact, Edict, Height, Rune, RuneId, Runestone},
     std::collections::HashMap,
     thiserror::Error,
-    titan_types::{RuneAmount, SpentStatus, TxOutEntry},
+    titan_types::{InscriptionId, RuneAmount, SpentStatus, TxOutEntry},
 };
 
 #[derive(Debug, Error)]
@@ -131,6 +131,85 @@ impl<'client> TransactionParser<'client> {
         Ok(transaction_state_change)
     }
 
+    /// Where the inscriptions on `tx`'s inputs go, and the rune icon it
+    /// reveals if `etched`: each one goes with its sat to the output holding
+    /// it, counting sats through the inputs and the outputs in order. An
+    /// inscription whose sat is spent as fee isn't tracked anymore. Only
+    /// confirmed transactions move inscriptions.
+    pub(super) fn inscriptions(
+        &self,
+        cache: &UpdaterCache,
+        txid: Txid,
+        tx: &Transaction,
+        etched: bool,
+    ) -> Result<HashMap<u32, Vec<OutputInscription>>> {
+        // Icons of etchings are the only inscriptions indexed.
+        if self.mempool || !self.should_index_runes || tx.is_coinbase() {
+            return Ok(HashMap::new());
+        }
+
+        let outpoints: Vec<OutPoint> = tx.input.iter().map(|input| input.previous_output).collect();
+        let on_inputs = cache.get_output_inscriptions(&outpoints)?;
+
+        let mut inscriptions = Vec::new();
+        if let Some(input) = rune_icon_input(tx).filter(|_| etched) {
+            inscriptions.push((
+                input.into_usize(),
+                OutputInscription {
+                    id: InscriptionId { txid, index: 0 },
+                    offset: 0,
+                },
+            ));
+        }
+        for (input, outpoint) in outpoints.iter().enumerate() {
+            for inscription in on_inputs.get(outpoint).into_iter().flatten() {
+                inscriptions.push((input, inscription.clone()));
+            }
+        }
+
+        let Some(last) = inscriptions.iter().map(|(input, _)| *input).max() else {
+            return Ok(HashMap::new());
+        };
+        let input_values = self.input_values(cache, &outpoints[..last])?;
+        let output_values: Vec<u64> = tx
+            .output
+            .iter()
+            .map(|output| output.value.to_sat())
+            .collect();
+
+        Ok(locate_inscriptions(
+            &input_values,
+            &output_values,
+            inscriptions,
+        ))
+    }
+
+    /// The values of the outputs `outpoints` spend, from the index or else
+    /// from the node.
+    fn input_values(&self, cache: &UpdaterCache, outpoints: &[OutPoint]) -> Result<Vec<u64>> {
+        let tx_outs = cache.get_tx_outs(&outpoints.to_vec())?;
+
+        outpoints
+            .iter()
+            .map(|outpoint| match tx_outs.get(outpoint) {
+                Some(tx_out) => Ok(tx_out.value),
+                None => {
+                    let Some(transaction) = self
+                        .client
+                        .get_raw_transaction(&outpoint.txid, None)
+                        .into_option()?
+                    else {
+                        panic!("can't get input transaction: {}", outpoint.txid);
+                    };
+
+                    Ok(transaction.output[outpoint.vout.into_usize()]
+                        .value
+                        .to_sat())
+                }
+            })
+            .collect()
+    }
+
     fn parse_runes(
         &mut self,
         cache: &UpdaterCache,
@@ -553,3 +632,77 @@ impl<'client> TransactionParser<'client> {
         Ok((unallocated, risky_unallocated))
     }
 }
+
+/// Sends each inscription, given as the input it's on and its offset into
+/// that input, to the output holding its sat. `input_values` only needs the
+/// inputs before the last inscribed one.
+fn locate_inscriptions(
+    input_values: &[u64],
+    output_values: &[u64],
+    inscriptions: Vec<(usize, OutputInscription)>,
+) -> HashMap<u32, Vec<OutputInscription>> {
+    let mut located: HashMap<u32, Vec<OutputInscription>> = HashMap::new();
+
+    for (input, inscription) in inscriptions {
+        let mut offset = input_values[..input].iter().sum::<u64>() + inscription.offset;
+        for (vout, value) in output_values.iter().enumerate() {
+            if offset < *value {
+                located
+                    .entry(vout as u32)
+                    .or_default()
+                    .push(OutputInscription {
+                        id: inscription.id,
+                        offset,
+                    });
+                break;
+            }
+            offset -= value;
+        }
+    }
+
+    for inscriptions in located.values_mut() {
+        inscriptions.sort_by_key(|inscription| inscription.offset);
+    }
+
+    located
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::Txid, std::str::FromStr};
+
+    fn inscription(index: u32, offset: u64) -> OutputInscription {
+        OutputInscription {
+            id: InscriptionId {
+                txid: Txid::from_str(
+                    "6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799",
+                )
+                .unwrap(),
+                index,
+            },
+            offset,
+        }
+    }
+
+    #[test]
+    fn inscriptions_follow_their_sats_to_the_outputs() {
+        let located = locate_inscriptions(
+            &[1_000, 500],
+            &[600, 800, 50],
+            vec![
+                // 1,010 sats in, 410 sats into the second output.
+                (1, inscription(0, 10)),
+                // The first sat.
+                (0, inscription(1, 0)),
+                // 1,300 sats in, 700 sats into the second output.
+                (1, inscription(2, 300)),
+                // 1,500 sats in, past the 1,450 sats of outputs: spent as fee.
+                (2, inscription(3, 0)),
+            ],
+        );
+
+        assert_eq!(located.len(), 2);
+        assert_eq!(located[&0], vec![inscription(1, 0)]);
+        assert_eq!(located[&1], vec![inscription(0, 410), inscription(2, 700)]);
+    }
+}
@@ -2,10 +2,11 @@ use {
     super::{address::AddressUpdater, cache::UpdaterCache},
     crate::{
         index::{inscription::index_rune_icon, Settings, StoreError},
-        models::{BlockId, RuneEntry, TransactionStateChange},
+        models::{BlockId, OutputInscription, RuneEntry, TransactionStateChange},
     },
     bitcoin::{OutPoint, Transaction, Txid},
     ordinals::{Artifact, Etching, Rune, RuneId, Runestone, SpacedRune},
+    std::collections::HashMap,
     thiserror::Error,
     titan_types::{Event, MempoolEntry, SpenderReference, SpentStatus, TxOutEntry},
     tokio::sync::mpsc::error::SendError,
@@ -163,6 +164,26 @@ impl<'a> TransactionUpdater<'a> {
         Ok(())
     }
 
+    /// Puts the inscriptions `TransactionParser::inscriptions` moved on
+    /// `txid`'s outputs. Their outputs are kept even without runes, so that
+    /// spending them can be told apart from never having held any.
+    pub(super) fn save_inscriptions(
+        &mut self,
+        cache: &mut UpdaterCache,
+        txid: Txid,
+        transaction_state_change: &TransactionStateChange,
+        inscriptions: HashMap<u32, Vec<OutputInscription>>,
+    ) {
+        for (vout, inscriptions) in inscriptions {
+            let outpoint = OutPoint { txid, vout };
+            cache.set_tx_out(
+                outpoint,
+                transaction_state_change.outputs[vout as usize].clone(),
+            );
+            cache.set_output_inscriptions(outpoint, inscriptions);
+        }
+    }
+
     fn update_script_pubkeys(&mut self, txid: Txid, transaction: &Transaction) -> () {
         if let Some(addr_updater) = self.address_updater.as_mut() {
             // skip coinbase inputs
@@ -270,7 +291,7 @@ impl<'a> TransactionUpdater<'a> {
             rune_entry.pending_burns = result;
         } else {
             let result = rune_entry
-                .pending_burns
+                .burned
                 .checked_add_signed(amount)
                 .ok_or(TransactionUpdaterError::Overflow("burn".to_string()))?;
 
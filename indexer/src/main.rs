@@ -8,7 +8,7 @@ use server::{Server, ServerConfig};
 use std::{io, panic, sync::Arc};
 use subscription::{
     shutdown_and_wait_subscription_tasks, spawn_subscription_tasks, SubscriptionSpawnResult,
-    WebhookSubscriptionManager,
+    TcpSubscriptionManager, WebhookSubscriptionManager,
 };
 use tokio::{
     signal::unix::{signal, SignalKind},
@@ -49,15 +49,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let spawn_subscription_result =
         spawn_subscription_tasks(db_arc.clone(), options.clone().into());
 
-    let (webhook_subscription_manager, event_sender) = match spawn_subscription_result.as_ref() {
-        Some(sub) => (
-            sub.webhook_spawn_result
-                .as_ref()
-                .map(|r| r.subscription_manager.clone()),
-            Some(sub.event_sender.clone()),
-        ),
-        None => (None, None),
-    };
+    let (webhook_subscription_manager, tcp_subscription_manager, event_sender) =
+        match spawn_subscription_result.as_ref() {
+            Some(sub) => (
+                sub.webhook_spawn_result
+                    .as_ref()
+                    .map(|r| r.subscription_manager.clone()),
+                sub.tcp_spawn_result
+                    .as_ref()
+                    .map(|r| r.tcp_subscription_manager.clone()),
+                Some(sub.event_sender.clone()),
+            ),
+            None => (None, None, None),
+        };
 
     // 6. Create the index
     let bitcoin_rpc_pool = RpcClientPool::new(
@@ -79,10 +83,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 8. Start the HTTP server
     let handle = Handle::new();
     let server = Server;
+    let tcp_subscription_manager =
+        tcp_subscription_manager.unwrap_or(Arc::new(TcpSubscriptionManager::new()));
+    let shutdown_timeout = server_config.shutdown_timeout;
     let http_server_jh = server.start(
         index.clone(),
         webhook_subscription_manager
             .unwrap_or(Arc::new(WebhookSubscriptionManager::new(db_arc.clone()))),
+        tcp_subscription_manager.clone(),
         bitcoin_rpc_pool.clone(),
         Arc::new(server_config),
         handle.clone(),
@@ -91,12 +99,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 9. Wait for SIGINT (Ctrl-C) or SIGTERM
     wait_for_signals().await;
 
-    // 10. Graceful shutdown (async)
+    // 10. Close subscriber connections and drain in-flight HTTP requests
+    server
+        .shutdown(&handle, &tcp_subscription_manager, shutdown_timeout)
+        .await;
+
+    // 11. Graceful shutdown (async)
     graceful_shutdown(
         index,
         spawn_subscription_result,
         db_arc,
-        &handle,
         index_handle,
         http_server_jh,
     )
@@ -119,6 +131,8 @@ fn setup_settings(options: &Options) -> Result<Settings, Box<dyn std::error::Err
 
 fn setup_server_config(options: &Options) -> Result<ServerConfig, Box<dyn std::error::Error>> {
     let config = ServerConfig::from(options.clone());
+    // Global, since events sent to subscribers are serialized outside the server.
+    titan_types::amount::set_as_strings(config.amounts_as_strings);
     Ok(config)
 }
 
@@ -198,7 +212,6 @@ async fn graceful_shutdown(
     index: Arc<Index>,
     spawn_subscription_result: Option<SubscriptionSpawnResult>,
     db_arc: Arc<RocksDB>,
-    handle: &Handle,
     index_handle: std::thread::JoinHandle<()>,
     http_server_jh: task::JoinHandle<io::Result<()>>,
 ) {
@@ -216,25 +229,22 @@ async fn graceful_shutdown(
         eprintln!("Panic occurred: {:?}", panic_info);
     }));
 
-    // 4) Graceful HTTP shutdown (axum_server)
-    handle.graceful_shutdown(Some(std::time::Duration::from_secs(2)));
-
-    // 5) Join the indexer background thread (blocking)
+    // 4) Join the indexer background thread (blocking)
     if let Err(e) = index_handle.join() {
         error!("Failed to join indexer thread: {:?}", e);
     }
 
-    // 6) Await the Axum server
+    // 5) Await the Axum server
     match http_server_jh.await {
         Ok(Ok(_)) => info!("Axum server finished cleanly."),
         Ok(Err(e)) => error!("Server error: {:?}", e),
         Err(e) => error!("Failed to join Axum server task: {:?}", e),
     };
 
-    // 7) Drop the index so RocksDB references can possibly be unwrapped
+    // 6) Drop the index so RocksDB references can possibly be unwrapped
     drop(index);
 
-    // 8) Attempt to close RocksDB
+    // 7) Attempt to close RocksDB
     match Arc::try_unwrap(db_arc) {
         Ok(db) => {
             if let Err(e) = db.close() {
@@ -9,6 +9,8 @@ pub struct BatchDelete {
     pub script_pubkeys_outpoints: HashSet<OutPoint>,
     pub spent_outpoints_in_mempool: HashSet<OutPoint>,
     pub tx_state_changes: HashSet<Txid>,
+    pub output_inscriptions: HashSet<OutPoint>,
+    pub sat_ranges: HashSet<OutPoint>,
 }
 
 impl BatchDelete {
@@ -18,6 +20,8 @@ impl BatchDelete {
             script_pubkeys_outpoints: HashSet::new(),
             spent_outpoints_in_mempool: HashSet::new(),
             tx_state_changes: HashSet::new(),
+            output_inscriptions: HashSet::new(),
+            sat_ranges: HashSet::new(),
         }
     }
 
@@ -26,6 +30,8 @@ impl BatchDelete {
             && self.script_pubkeys_outpoints.is_empty()
             && self.spent_outpoints_in_mempool.is_empty()
             && self.tx_state_changes.is_empty()
+            && self.output_inscriptions.is_empty()
+            && self.sat_ranges.is_empty()
     }
 }
 
@@ -33,11 +39,14 @@ impl Display for BatchDelete {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "BatchDelete: {} tx_outs, {} script_pubkeys_outpoints, {} spent_outpoints_in_mempool, {} tx_state_changes",
+            "BatchDelete: {} tx_outs, {} script_pubkeys_outpoints, {} spent_outpoints_in_mempool, {} tx_state_changes, {} output_inscriptions, {} sat_ranges",
+
             self.tx_outs.len(),
             self.script_pubkeys_outpoints.len(),
             self.spent_outpoints_in_mempool.len(),
-            self.tx_state_changes.len()
+            self.tx_state_changes.len(),
+            self.output_inscriptions.len(),
+            self.sat_ranges.len()
         )
     }
 }
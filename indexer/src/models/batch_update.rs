@@ -1,12 +1,12 @@
 use {
-    super::{BlockId, Inscription, RuneEntry, TransactionStateChange},
+    super::{BlockId, Inscription, OutputInscription, RuneEntry, SatRange, TransactionStateChange},
     bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid},
     ordinals::RuneId,
     std::{
         collections::{HashMap, HashSet},
         fmt::Display,
     },
-    titan_types::{Block, InscriptionId, SpenderReference, TxOutEntry, MempoolEntry},
+    titan_types::{Block, InscriptionId, MempoolEntry, SpenderReference, TxOutEntry},
 };
 
 #[derive(Debug, Clone)]
@@ -23,6 +23,9 @@ pub struct BatchUpdate {
     pub rune_ids: HashMap<u128, RuneId>,
     pub rune_numbers: HashMap<u64, RuneId>,
     pub inscriptions: HashMap<InscriptionId, Inscription>,
+    pub output_inscriptions: HashMap<OutPoint, Vec<OutputInscription>>,
+    pub sat_ranges: HashMap<OutPoint, Vec<SatRange>>,
+    pub inscription_sats: HashMap<InscriptionId, u64>,
     pub transactions: HashMap<Txid, Transaction>,
     pub transaction_confirming_block: HashMap<Txid, BlockId>,
     pub mempool_txs: HashMap<Txid, MempoolEntry>,
@@ -46,6 +49,9 @@ impl BatchUpdate {
             rune_ids: HashMap::new(),
             rune_numbers: HashMap::new(),
             inscriptions: HashMap::new(),
+            output_inscriptions: HashMap::new(),
+            sat_ranges: HashMap::new(),
+            inscription_sats: HashMap::new(),
             transactions: HashMap::new(),
             transaction_confirming_block: HashMap::new(),
             mempool_txs: HashMap::new(),
@@ -68,6 +74,9 @@ impl BatchUpdate {
             && self.rune_ids.is_empty()
             && self.rune_numbers.is_empty()
             && self.inscriptions.is_empty()
+            && self.output_inscriptions.is_empty()
+            && self.sat_ranges.is_empty()
+            && self.inscription_sats.is_empty()
             && self.mempool_txs.is_empty()
             && self.transactions.is_empty()
             && self.transaction_confirming_block.is_empty()
@@ -86,6 +95,9 @@ impl Display for BatchUpdate {
              mempool_txs: {}, \
              runes: txs {}/ runes {}/ ids {}, \
              inscriptions: {}, \
+             output_inscriptions: {}, \
+             sat_ranges: {}, \
+             inscription_sats: {}, \
              transactions: {}, \
              transaction_confirming_block: {}]",
             self.block_count,
@@ -102,6 +114,9 @@ impl Display for BatchUpdate {
             self.runes.len(),
             self.rune_ids.len(),
             self.inscriptions.len(),
+            self.output_inscriptions.len(),
+            self.sat_ranges.len(),
+            self.inscription_sats.len(),
             self.transactions.len(),
             self.transaction_confirming_block.len(),
         )
@@ -3,13 +3,48 @@ use {
     borsh::{BorshDeserialize, BorshSerialize},
     core::str,
     http::HeaderValue,
+    std::io::{Read, Result},
+    titan_types::InscriptionId,
 };
 
-#[derive(Debug, PartialEq, Clone, Eq, Default, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, PartialEq, Clone, Eq, Default, BorshSerialize)]
 pub struct Inscription {
     pub body: Option<Vec<u8>>,
     pub content_encoding: Option<Vec<u8>>,
     pub content_type: Option<Vec<u8>>,
+    /// Parents named by the envelope. Unlike ord, the parents aren't required
+    /// to be spent by the reveal transaction.
+    pub parents: Vec<InscriptionId>,
+}
+
+impl BorshDeserialize for Inscription {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let body = Option::<Vec<u8>>::deserialize_reader(reader)?;
+        let content_encoding = Option::<Vec<u8>>::deserialize_reader(reader)?;
+        let content_type = Option::<Vec<u8>>::deserialize_reader(reader)?;
+
+        // Inscriptions stored before parents were added end here.
+        let mut len = [0u8; 4];
+        let parents = match reader.read(&mut len[..1])? {
+            0 => Vec::new(),
+            _ => {
+                reader.read_exact(&mut len[1..])?;
+                let len = u32::from_le_bytes(len);
+                let mut parents = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    parents.push(InscriptionId::deserialize_reader(reader)?);
+                }
+                parents
+            }
+        };
+
+        Ok(Self {
+            body,
+            content_encoding,
+            content_type,
+            parents,
+        })
+    }
 }
 
 impl Inscription {
@@ -38,3 +73,43 @@ impl Inscription {
         self.body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::Txid, std::str::FromStr};
+
+    #[test]
+    fn decodes_inscriptions_stored_without_parents() {
+        let inscription = Inscription {
+            body: Some(b"icon".to_vec()),
+            content_encoding: None,
+            content_type: Some(b"image/png".to_vec()),
+            parents: vec![InscriptionId {
+                txid: Txid::from_str(
+                    "1111111111111111111111111111111111111111111111111111111111111111",
+                )
+                .unwrap(),
+                index: 1,
+            }],
+        };
+
+        let mut stored = Vec::new();
+        BorshSerialize::serialize(&inscription, &mut stored).unwrap();
+        assert_eq!(
+            Inscription::deserialize(&mut stored.as_slice()).unwrap(),
+            inscription
+        );
+
+        let mut legacy = Vec::new();
+        BorshSerialize::serialize(&inscription.body, &mut legacy).unwrap();
+        BorshSerialize::serialize(&inscription.content_encoding, &mut legacy).unwrap();
+        BorshSerialize::serialize(&inscription.content_type, &mut legacy).unwrap();
+        assert_eq!(
+            Inscription::deserialize(&mut legacy.as_slice()).unwrap(),
+            Inscription {
+                parents: Vec::new(),
+                ..inscription
+            }
+        );
+    }
+}
@@ -1,8 +1,8 @@
 pub use {
     batch_delete::BatchDelete, batch_rollback::BatchRollback, batch_update::BatchUpdate,
     block::block_id_to_transaction_status, block::BlockId, inscription::Inscription, lot::Lot,
-    media::Media, rune::RuneEntry, transaction_state_change::TransactionStateChange,
-    transaction_state_change::TxRuneIndexRef,
+    media::Media, output_inscription::OutputInscription, rune::RuneEntry, sat_range::SatRange,
+    transaction_state_change::TransactionStateChange, transaction_state_change::TxRuneIndexRef,
 };
 
 mod batch_delete;
@@ -12,5 +12,7 @@ mod block;
 mod inscription;
 mod lot;
 mod media;
+mod output_inscription;
 mod rune;
+mod sat_range;
 mod transaction_state_change;
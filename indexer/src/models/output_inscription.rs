@@ -0,0 +1,11 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    titan_types::InscriptionId,
+};
+
+/// An inscription on an output, on the sat `offset` sats into it.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct OutputInscription {
+    pub id: InscriptionId,
+    pub offset: u64,
+}
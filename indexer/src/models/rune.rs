@@ -8,7 +8,10 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use ordinals::{Rune, RuneId, SpacedRune, Terms};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use titan_types::{InscriptionId, MintResponse, RuneResponse};
+use titan_types::{
+    InscriptionId, MintResponse, RuneEtchingResponse, RuneMintsResponse, RuneResponse,
+    RuneTermsResponse,
+};
 
 #[derive(Debug, PartialEq, Error)]
 pub enum MintError {
@@ -77,6 +80,11 @@ impl RuneEntry {
                     .unwrap_or_default()
     }
 
+    /// Supply minus everything burned, including runes burned by cenotaphs.
+    pub fn circulating_supply(&self) -> u128 {
+        self.supply().saturating_sub(self.burned)
+    }
+
     pub fn max_supply(&self) -> u128 {
         self.premine
             + self.terms.and_then(|terms| terms.cap).unwrap_or_default()
@@ -120,6 +128,25 @@ impl RuneEntry {
             .or(absolute)
     }
 
+    /// Minting progress at `height`. Runes without mint terms report a cap
+    /// of zero and are never mintable.
+    pub fn to_mints_response(&self, height: u64) -> RuneMintsResponse {
+        let cap = self.terms.and_then(|terms| terms.cap).unwrap_or_default();
+
+        RuneMintsResponse {
+            cap,
+            minted: self.mints,
+            remaining: cap.saturating_sub(self.mints),
+            mint_amount: self
+                .terms
+                .and_then(|terms| terms.amount)
+                .unwrap_or_default(),
+            mint_start_height: self.start(),
+            mint_end_height: self.end(),
+            mintable_now: self.mintable(height).is_ok(),
+        }
+    }
+
     pub fn to_rune_response(&self, id: RuneId, height: u64) -> RuneResponse {
         let mintable = match self.mintable(height) {
             Ok(_) => true,
@@ -148,6 +175,7 @@ impl RuneEntry {
             number: self.number,
             premine: self.premine,
             supply: self.supply(),
+            circulating_supply: self.circulating_supply(),
             max_supply: self.max_supply(),
             spaced_rune: self.spaced_rune,
             symbol: self.symbol,
@@ -160,6 +188,34 @@ impl RuneEntry {
             turbo: self.turbo,
         }
     }
+
+    pub fn to_etching_response(&self, id: RuneId) -> RuneEtchingResponse {
+        RuneEtchingResponse {
+            id,
+            etching: self.etching,
+            block: self.block,
+            timestamp: self.timestamp,
+            number: self.number,
+            spaced_rune: self.spaced_rune,
+            rune: self.spaced_rune.rune,
+            spacers: self.spaced_rune.spacers,
+            symbol: self.symbol,
+            divisibility: self.divisibility,
+            premine: self.premine,
+            terms: self.terms.map(|terms| RuneTermsResponse {
+                amount: terms.amount.unwrap_or_default(),
+                cap: terms.cap.unwrap_or_default(),
+                height_start: terms.height.0,
+                height_end: terms.height.1,
+                offset_start: terms.offset.0,
+                offset_end: terms.offset.1,
+                start: self.start(),
+                end: self.end(),
+            }),
+            turbo: self.turbo,
+            inscription_id: self.inscription_id.clone(),
+        }
+    }
 }
 
 impl BorshSerialize for RuneEntry {
@@ -415,3 +471,94 @@ impl BorshDeserialize for RuneEntry {
         })
     }
 }
+
+#[cfg(test)]
+impl RuneEntry {
+    /// An `UNCOMMON•GOODS` etched at 840,000 with open mints of 100, capped at
+    /// 5, for tests to adjust with struct update syntax.
+    pub(crate) fn fixture() -> Self {
+        use bitcoin::hashes::Hash;
+
+        Self {
+            block: 840_000,
+            burned: 0,
+            divisibility: 0,
+            etching: Txid::all_zeros(),
+            mints: 0,
+            number: 0,
+            premine: 0,
+            spaced_rune: SpacedRune::from_str("UNCOMMON•GOODS").unwrap(),
+            symbol: None,
+            terms: Some(Terms {
+                amount: Some(100),
+                cap: Some(5),
+                height: (None, None),
+                offset: (None, None),
+            }),
+            pending_burns: 0,
+            pending_mints: 0,
+            inscription_id: None,
+            timestamp: 0,
+            turbo: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mints_and_etching_responses() {
+        let entry = RuneEntry {
+            mints: 3,
+            terms: Some(Terms {
+                amount: Some(100),
+                cap: Some(5),
+                height: (None, Some(850_000)),
+                offset: (Some(10), None),
+            }),
+            ..RuneEntry::fixture()
+        };
+
+        let mints = entry.to_mints_response(840_010);
+        assert_eq!(mints.cap, 5);
+        assert_eq!(mints.minted, 3);
+        assert_eq!(mints.remaining, 2);
+        assert_eq!(mints.mint_amount, 100);
+        assert_eq!(mints.mint_start_height, Some(840_010));
+        assert_eq!(mints.mint_end_height, Some(850_000));
+        assert!(mints.mintable_now);
+
+        assert!(!entry.to_mints_response(840_009).mintable_now);
+        assert!(!entry.to_mints_response(850_000).mintable_now);
+
+        let etching = entry.to_etching_response(RuneId {
+            block: 840_000,
+            tx: 1,
+        });
+        assert_eq!(etching.rune.to_string(), "UNCOMMONGOODS");
+        assert_eq!(etching.spacers, 1 << 7);
+        let terms = etching.terms.unwrap();
+        assert_eq!(
+            (terms.offset_start, terms.height_end),
+            (Some(10), Some(850_000))
+        );
+        assert_eq!((terms.start, terms.end), (Some(840_010), Some(850_000)));
+    }
+
+    #[test]
+    fn circulating_supply() {
+        let entry = RuneEntry {
+            burned: 150,
+            mints: 3,
+            premine: 1000,
+            ..RuneEntry::fixture()
+        };
+
+        let response = entry.to_rune_response(RuneId::default(), 840_000);
+        assert_eq!(response.supply, 1300);
+        assert_eq!(response.burned, 150);
+        assert_eq!(response.circulating_supply, 1150);
+    }
+}
@@ -0,0 +1,8 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// The sats `start` up to, but not including, `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SatRange {
+    pub start: u64,
+    pub end: u64,
+}
@@ -8,7 +8,7 @@ use {
         fmt::Display,
         io::{Read, Result, Write},
     },
-    titan_types::{RuneAmount, TxOutEntry},
+    titan_types::{RuneAmount, RuneTransactionKind, TxOutEntry},
 };
 
 #[derive(Debug, Clone)]
@@ -187,6 +187,50 @@ impl TransactionStateChange {
 
         rune_ids.into_iter().collect()
     }
+
+    /// What the transaction did with `rune_id`. `premine` is the rune's premine,
+    /// which counts as created when the transaction etched it. Outputs holding
+    /// more of the rune than was created moved an existing balance, i.e. it's
+    /// a transfer.
+    pub fn rune_transaction_kinds(
+        &self,
+        rune_id: &RuneId,
+        premine: u128,
+    ) -> Vec<RuneTransactionKind> {
+        let mut kinds = Vec::new();
+        let mut created = 0u128;
+
+        if matches!(self.etched, Some((id, _)) if id == *rune_id) {
+            kinds.push(RuneTransactionKind::Etch);
+            created = created.saturating_add(premine);
+        }
+
+        if let Some(minted) = self
+            .minted
+            .as_ref()
+            .filter(|minted| minted.rune_id == *rune_id)
+        {
+            kinds.push(RuneTransactionKind::Mint);
+            created = created.saturating_add(minted.amount);
+        }
+
+        let output_amount = self
+            .outputs
+            .iter()
+            .flat_map(|output| output.runes.iter())
+            .filter(|rune| rune.rune_id == *rune_id)
+            .fold(0u128, |total, rune| total.saturating_add(rune.amount));
+
+        if output_amount > created {
+            kinds.push(RuneTransactionKind::Transfer);
+        }
+
+        if self.burned.contains_key(rune_id) {
+            kinds.push(RuneTransactionKind::Burn);
+        }
+
+        kinds
+    }
 }
 
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
@@ -194,3 +238,83 @@ pub struct TxRuneIndexRef {
     pub rune_id: Vec<u8>,
     pub index: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, titan_types::SpentStatus};
+
+    fn output(rune_id: RuneId, amount: u128) -> TxOutEntry {
+        TxOutEntry {
+            runes: vec![RuneAmount { rune_id, amount }],
+            risky_runes: vec![],
+            value: 546,
+            spent: SpentStatus::Unspent,
+        }
+    }
+
+    #[test]
+    fn classify_rune_transactions() {
+        let rune_id = RuneId {
+            block: 840000,
+            tx: 1,
+        };
+        let state_change = |outputs, minted, burned: &[(RuneId, u128)]| TransactionStateChange {
+            inputs: vec![],
+            outputs,
+            etched: None,
+            minted,
+            burned: burned
+                .iter()
+                .map(|(id, amount)| (*id, Lot(*amount)))
+                .collect(),
+            is_coinbase: false,
+        };
+
+        let mint = state_change(
+            vec![output(rune_id, 100)],
+            Some(RuneAmount {
+                rune_id,
+                amount: 100,
+            }),
+            &[],
+        );
+        assert_eq!(
+            mint.rune_transaction_kinds(&rune_id, 0),
+            vec![RuneTransactionKind::Mint]
+        );
+
+        let mint_and_transfer = state_change(
+            vec![output(rune_id, 150)],
+            Some(RuneAmount {
+                rune_id,
+                amount: 100,
+            }),
+            &[],
+        );
+        assert_eq!(
+            mint_and_transfer.rune_transaction_kinds(&rune_id, 0),
+            vec![RuneTransactionKind::Mint, RuneTransactionKind::Transfer]
+        );
+
+        let burn = state_change(vec![], None, &[(rune_id, 10)]);
+        assert_eq!(
+            burn.rune_transaction_kinds(&rune_id, 0),
+            vec![RuneTransactionKind::Burn]
+        );
+
+        let etch = TransactionStateChange {
+            etched: Some((rune_id, Rune(0))),
+            ..state_change(vec![output(rune_id, 1000)], None, &[])
+        };
+        assert_eq!(
+            etch.rune_transaction_kinds(&rune_id, 1000),
+            vec![RuneTransactionKind::Etch]
+        );
+
+        let other_rune = RuneId {
+            block: 840000,
+            tx: 2,
+        };
+        assert!(mint.rune_transaction_kinds(&other_rune, 0).is_empty());
+    }
+}
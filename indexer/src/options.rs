@@ -1,7 +1,7 @@
 use {
     crate::{
         index::{Chain, Settings},
-        server::ServerConfig,
+        server::{parse_compression_level, CompressionAlgorithm, RpcRetry, ServerConfig},
         subscription::SubscriptionConfig,
     },
     bitcoincore_rpc::Auth,
@@ -13,7 +13,8 @@ use {
         },
         Parser,
     },
-    std::path::PathBuf,
+    std::{path::PathBuf, time::Duration},
+    tower_http::compression::CompressionLevel,
     tracing::warn,
 };
 
@@ -108,6 +109,14 @@ pub struct Options {
     )]
     pub(super) index_addresses: bool,
 
+    /// Index sat ranges, to find inscriptions by sat. [default: false]
+    #[arg(
+        long,
+        help = "Index sat ranges, to find inscriptions by sat. [default: false]",
+        default_value = "false"
+    )]
+    pub(super) index_sats: bool,
+
     /// Commit interval in blocks. [default: 500]
     #[arg(
         long,
@@ -125,9 +134,9 @@ pub struct Options {
     #[arg(long, default_value = "tcp://127.0.0.1:28332")]
     pub(super) zmq_endpoint: String,
 
-    /// Listen address for the REST API server
-    #[arg(long, default_value = "0.0.0.0:3030")]
-    pub(super) http_listen: String,
+    /// Comma-separated listen addresses for the REST API server, e.g. `0.0.0.0:3030,[::]:3030`. Every address a host name resolves to is bound.
+    #[arg(long, value_delimiter = ',', default_value = "0.0.0.0:3030")]
+    pub(super) http_listen: Vec<String>,
 
     #[arg(
         long,
@@ -135,12 +144,76 @@ pub struct Options {
     )]
     pub(super) csp_origin: Option<String>,
 
+    /// Comma-separated origins allowed by CORS, e.g. https://example.com. Any origin is allowed when unset.
+    #[arg(long, value_delimiter = ',')]
+    pub(super) cors_allow_origins: Option<Vec<String>>,
+
+    /// Maximum sustained requests per second per client IP. Rate limiting is disabled when unset.
+    #[arg(long)]
+    pub(super) rate_limit_per_sec: Option<u32>,
+
+    /// Maximum burst of requests per client IP. Defaults to --rate-limit-per-sec.
+    #[arg(long)]
+    pub(super) rate_limit_burst: Option<u32>,
+
+    /// Comma-separated API keys. When set, requests need an `Authorization: Bearer <key>` header.
+    #[arg(long, value_delimiter = ',')]
+    pub(super) api_keys: Option<Vec<String>>,
+
+    /// Comma-separated paths served without an API key.
+    #[arg(long, value_delimiter = ',', default_value = "/healthz,/readyz")]
+    pub(super) api_key_exempt_paths: Vec<String>,
+
     #[arg(
         long,
         help = "Decompress encoded content. Currently only supports brotli. Be careful using this on production instances. A decompressed inscription may be arbitrarily large, making decompression a DoS vector."
     )]
     pub(super) decompress: bool,
 
+    /// Largest `limit` accepted by paginated endpoints. Larger pages are rejected with 400.
+    #[arg(long, default_value = "1000")]
+    pub(super) max_page_size: u64,
+
+    /// Maximum concurrent index reads run by HTTP handlers. Further requests wait for a free slot.
+    #[arg(long, default_value = "64")]
+    pub(super) blocking_threads: usize,
+
+    /// Comma-separated encodings HTTP responses may be compressed with.
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "gzip,br")]
+    pub(super) compression_algorithms: Vec<CompressionAlgorithm>,
+
+    /// Compression level: fastest, best, default or an algorithm-specific number.
+    #[arg(long, value_parser = parse_compression_level, default_value = "default")]
+    pub(super) compression_level: CompressionLevel,
+
+    /// Serve inscription content without compressing it. Most inscriptions are images or other already compressed formats.
+    #[arg(long, default_value = "false")]
+    pub(super) disable_inscription_content_compression: bool,
+
+    /// Zstd dictionary (e.g. from `zstd --train`) to compress inscription content with for browsers that fetched it from `/inscriptions/dictionary`.
+    #[arg(long)]
+    pub(super) inscription_content_dictionary: Option<PathBuf>,
+
+    /// Serialize rune amounts as JSON numbers instead of strings. Amounts above 2^53 lose precision in JavaScript clients.
+    #[arg(long, default_value = "false")]
+    pub(super) amounts_as_numbers: bool,
+
+    /// Transactions and outputs kept in memory for `/tx/{txid}` and `/output/{outpoint}`. 0 disables the cache.
+    #[arg(long, default_value = "0")]
+    pub(super) tx_cache_entries: usize,
+
+    /// Confirmations a transaction, or the transaction spending an output, needs before it's cached.
+    #[arg(long, default_value = "6")]
+    pub(super) tx_cache_min_confirmations: u64,
+
+    /// Seconds an HTTP request may take before it's answered with 504. Doesn't apply to `/events`, `/sse`, `/tip/sse` and `/ws`. 0 disables the timeout.
+    #[arg(long, default_value = "30")]
+    pub(super) request_timeout_secs: u64,
+
+    /// Seconds `/fees` answers from its last node estimate before asking the node again.
+    #[arg(long, default_value = "10")]
+    pub(super) fee_estimates_ttl_secs: u64,
+
     /// Main loop interval in milliseconds. [default: 500]
     #[arg(
         long,
@@ -153,6 +226,14 @@ pub struct Options {
     #[arg(long, default_value = "false")]
     pub(super) enable_webhook_subscriptions: bool,
 
+    /// Accept plain http webhook endpoints. Only https ones are accepted by default.
+    #[arg(long, default_value = "false")]
+    pub(super) webhook_allow_http: bool,
+
+    /// Maximum number of registered webhook subscriptions.
+    #[arg(long, default_value = "1000")]
+    pub(super) max_subscriptions: usize,
+
     /// Enable TCP subscription service
     #[arg(long, default_value = "false")]
     pub(super) enable_tcp_subscriptions: bool,
@@ -161,6 +242,59 @@ pub struct Options {
     #[arg(long, default_value = "127.0.0.1:8080")]
     pub(super) tcp_address: String,
 
+    /// Seconds without events after which TCP and WebSocket subscribers get a
+    /// heartbeat. 0 disables heartbeats.
+    #[arg(long, default_value = "30")]
+    pub(super) subscription_heartbeat_secs: u64,
+
+    /// Enable WebSocket subscription service on the REST API server
+    #[arg(long, default_value = "false")]
+    pub(super) enable_ws_subscriptions: bool,
+
+    /// Enable Server-Sent Events subscriptions at GET /sse on the REST API server
+    #[arg(long, default_value = "false")]
+    pub(super) enable_sse_subscriptions: bool,
+
+    /// Enable long-polling for events at GET /events on the REST API server
+    #[arg(long, default_value = "false")]
+    pub(super) enable_http_events: bool,
+
+    /// Maximum seconds a GET /events long poll waits for new events
+    #[arg(long, default_value = "25")]
+    pub(super) events_max_wait: u64,
+
+    /// Expose Prometheus metrics at /metrics on the REST API server
+    #[arg(long, default_value = "false")]
+    pub(super) enable_metrics: bool,
+
+    /// Serve GET /debug/index-stats on the REST API server
+    #[arg(long, default_value = "false")]
+    pub(super) enable_debug: bool,
+
+    /// Serve POST /admin/reindex on the REST API server. Needs --api-keys
+    #[arg(long, default_value = "false")]
+    pub(super) enable_admin: bool,
+
+    /// Maximum blocks the index may lag behind the node tip for /readyz to report ready
+    #[arg(long, default_value = "2")]
+    pub(super) ready_lag_blocks: u64,
+
+    /// Return 503 from data endpoints while the index is more than this many blocks behind the node tip. Disabled when unset.
+    #[arg(long)]
+    pub(super) max_behind_blocks: Option<u64>,
+
+    /// Times an HTTP request retries a node read that failed because the node couldn't be reached
+    #[arg(long, default_value = "2")]
+    pub(super) rpc_retry_attempts: u32,
+
+    /// Milliseconds to wait before the first RPC retry, doubled for each following one
+    #[arg(long, default_value = "100")]
+    pub(super) rpc_retry_backoff_ms: u64,
+
+    /// Seconds to wait for in-flight HTTP requests to finish on shutdown
+    #[arg(long, default_value = "2")]
+    pub(super) shutdown_timeout: u64,
+
     /// Enable file logging
     #[arg(long, default_value = "false")]
     pub(super) enable_file_logging: bool,
@@ -198,6 +332,7 @@ impl From<Options> for Settings {
             no_index_inscriptions: options.no_index_inscriptions,
             index_bitcoin_transactions: options.index_bitcoin_transactions,
             index_addresses: options.index_addresses,
+            index_sats: options.index_sats,
             commit_interval: options.commit_interval,
             main_loop_interval: options.main_loop_interval,
         }
@@ -210,14 +345,47 @@ impl From<Options> for ServerConfig {
         Self {
             chain: options.chain,
             csp_origin: options.csp_origin,
+            cors_allow_origins: options.cors_allow_origins,
+            rate_limit_per_sec: options.rate_limit_per_sec,
+            rate_limit_burst: options.rate_limit_burst,
+            api_keys: options.api_keys,
+            api_key_exempt_paths: options.api_key_exempt_paths,
             decompress: options.decompress,
+            max_page_size: options.max_page_size,
+            blocking_threads: options.blocking_threads,
+            compression_algorithms: options.compression_algorithms,
+            compression_level: options.compression_level,
+            compress_inscription_content: !options.disable_inscription_content_compression,
+            inscription_content_dictionary: options.inscription_content_dictionary,
+            amounts_as_strings: !options.amounts_as_numbers,
+            tx_cache_entries: options.tx_cache_entries,
+            tx_cache_min_confirmations: options.tx_cache_min_confirmations,
+            request_timeout_secs: options.request_timeout_secs,
+            fee_estimates_ttl: Duration::from_secs(options.fee_estimates_ttl_secs),
 
             http_listen: options.http_listen,
             bitcoin_rpc_url: options.bitcoin_rpc_url,
             bitcoin_rpc_auth,
+            rpc_retry: RpcRetry {
+                attempts: options.rpc_retry_attempts,
+                backoff: Duration::from_millis(options.rpc_retry_backoff_ms),
+            },
 
             index_addresses: options.index_addresses,
+            index_sats: options.index_sats,
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
+            webhook_allow_http: options.webhook_allow_http,
+            max_subscriptions: options.max_subscriptions,
+            enable_ws_subscriptions: options.enable_ws_subscriptions,
+            enable_sse_subscriptions: options.enable_sse_subscriptions,
+            enable_http_events: options.enable_http_events,
+            events_max_wait: Duration::from_secs(options.events_max_wait),
+            enable_metrics: options.enable_metrics,
+            enable_debug: options.enable_debug,
+            enable_admin: options.enable_admin,
+            ready_lag_blocks: options.ready_lag_blocks,
+            max_behind_blocks: options.max_behind_blocks,
+            shutdown_timeout: Duration::from_secs(options.shutdown_timeout),
         }
     }
 }
@@ -228,7 +396,11 @@ impl From<Options> for SubscriptionConfig {
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
             enable_tcp_subscriptions: options.enable_tcp_subscriptions,
             tcp_address: options.tcp_address,
+            enable_ws_subscriptions: options.enable_ws_subscriptions,
+            enable_sse_subscriptions: options.enable_sse_subscriptions,
+            enable_http_events: options.enable_http_events,
             enable_file_logging: options.enable_file_logging,
+            heartbeat_secs: options.subscription_heartbeat_secs,
         }
     }
 }
@@ -0,0 +1,88 @@
+use {
+    super::{
+        error::{ServerError, ServerResult},
+        extract::{Path, Query},
+        network::{self, NetworkQuery},
+        ServerConfig,
+    },
+    axum::{
+        extract::{FromRef, FromRequestParts},
+        response::{IntoResponse, Response},
+    },
+    bitcoin::{address::NetworkUnchecked, Address, ScriptBuf},
+    std::sync::Arc,
+};
+
+/// Address path segment of the address endpoints, checked against the chain
+/// the server indexes, as is their `?network=`. Rejected while addresses
+/// aren't indexed.
+pub(super) struct AddressPath(pub Address);
+
+fn check_addresses_indexed(config: &ServerConfig) -> ServerResult<()> {
+    if !config.index_addresses {
+        return Err(ServerError::BadRequest(
+            "addresses are not indexed. Enable --index-addresses to index addresses".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl<S> FromRequestParts<S> for AddressPath
+where
+    S: Send + Sync,
+    Arc<ServerConfig>: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let config = Arc::<ServerConfig>::from_ref(state);
+        check_addresses_indexed(&config).map_err(IntoResponse::into_response)?;
+
+        let Path(address) = Path::<Address<NetworkUnchecked>>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let Query(NetworkQuery { network }) =
+            Query::<NetworkQuery>::from_request_parts(parts, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+
+        network::check_network(network, config.chain)
+            .and_then(|()| network::require_network(address, config.chain))
+            .map(AddressPath)
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+/// Hex scriptPubKey path segment of `/script/{script}/outputs`, for outputs
+/// that don't have an address. Rejected while addresses aren't indexed.
+pub(super) struct ScriptPubkeyPath(pub ScriptBuf);
+
+impl<S> FromRequestParts<S> for ScriptPubkeyPath
+where
+    S: Send + Sync,
+    Arc<ServerConfig>: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let config = Arc::<ServerConfig>::from_ref(state);
+        check_addresses_indexed(&config).map_err(IntoResponse::into_response)?;
+
+        let Path(script) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        ScriptBuf::from_hex(&script)
+            .map(ScriptPubkeyPath)
+            .map_err(|e| {
+                ServerError::BadRequest(format!("invalid script pubkey hex: {e}")).into_response()
+            })
+    }
+}
@@ -0,0 +1,117 @@
+use {
+    super::error::error_response,
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    http::{header, HeaderValue, StatusCode},
+    std::{collections::HashSet, sync::Arc},
+    titan_types::ErrorCode,
+};
+
+/// API keys accepted as `Authorization: Bearer <key>`, and the paths that
+/// don't need one.
+#[derive(Clone)]
+pub(super) struct ApiKeys {
+    keys: Arc<Vec<String>>,
+    exempt_paths: Arc<HashSet<String>>,
+}
+
+impl ApiKeys {
+    /// Empty keys are dropped, so `--api-keys ""` doesn't let requests with an
+    /// empty token through.
+    pub(super) fn new(keys: Vec<String>, exempt_paths: Vec<String>) -> Self {
+        Self {
+            keys: Arc::new(
+                keys.into_iter()
+                    .filter(|key| !key.trim().is_empty())
+                    .collect(),
+            ),
+            exempt_paths: Arc::new(exempt_paths.into_iter().collect()),
+        }
+    }
+
+    fn is_authorized(&self, authorization: Option<&HeaderValue>) -> bool {
+        let Some((scheme, token)) = authorization
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+        else {
+            return false;
+        };
+
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return false;
+        }
+
+        let token = token.trim().as_bytes();
+        if token.is_empty() {
+            return false;
+        }
+
+        // Compare against every key so the timing doesn't reveal which one,
+        // or how much of it, matched.
+        self.keys.iter().fold(false, |found, key| {
+            found | constant_time_eq(key.as_bytes(), token)
+        })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Rejects requests without a valid API key with 401, except for the exempt
+/// paths.
+pub(super) async fn require_api_key(
+    State(api_keys): State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if api_keys.exempt_paths.contains(request.uri().path())
+        || api_keys.is_authorized(request.headers().get(header::AUTHORIZATION))
+    {
+        return next.run(request).await;
+    }
+
+    (
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        error_response(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "missing or invalid API key",
+        ),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_token_must_match_a_key() {
+        let api_keys = ApiKeys::new(vec!["secret".into(), "other".into()], vec![]);
+        let authorized =
+            |value: &str| api_keys.is_authorized(Some(&HeaderValue::from_str(value).unwrap()));
+
+        assert!(authorized("Bearer secret"));
+        assert!(authorized("bearer other"));
+        assert!(!authorized("Bearer secre"));
+        assert!(!authorized("Basic secret"));
+        assert!(!authorized("secret"));
+        assert!(!api_keys.is_authorized(None));
+    }
+
+    #[test]
+    fn empty_keys_and_tokens_are_rejected() {
+        let api_keys = ApiKeys::new(vec!["".into(), " ".into()], vec![]);
+        let authorized =
+            |value: &str| api_keys.is_authorized(Some(&HeaderValue::from_str(value).unwrap()));
+
+        assert!(api_keys.keys.is_empty());
+        assert!(!authorized("Bearer "));
+        assert!(!authorized("Bearer  "));
+        assert!(!authorized("Bearer"));
+    }
+}
@@ -0,0 +1,212 @@
+use {
+    super::{
+        blocking::BlockingPool,
+        deserialize_from_str::DeserializeFromStr,
+        error::{block_query_height, NotIndexedExt, ServerError, ServerResult},
+        network,
+        page::check_page_size,
+        ServerConfig,
+    },
+    crate::{
+        api,
+        bitcoin_rpc::{PooledClient, RpcClientPool},
+        index::Index,
+    },
+    axum::{body, response::IntoResponse},
+    bitcoin::{address::NetworkUnchecked, Address, OutPoint, Txid},
+    serde::{Deserialize, Serialize},
+    std::sync::Arc,
+    titan_types::{
+        query, AddressData, Block, BlockTip, ErrorCode, ErrorResponse, Pagination, RuneResponse,
+        Transaction, TxOutResponse,
+    },
+};
+
+pub(super) const MAX_BATCH_SIZE: usize = 50;
+
+/// A single call in a `POST /batch` request, e.g.
+/// `{ "method": "rune", "params": { "rune": "UNCOMMONGOODS" } }`.
+#[derive(Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+enum BatchCall {
+    Tip,
+    Block {
+        query: DeserializeFromStr<query::Block>,
+    },
+    Rune {
+        rune: DeserializeFromStr<query::Rune>,
+    },
+    Address {
+        address: Address<NetworkUnchecked>,
+        #[serde(flatten)]
+        pagination: Pagination,
+    },
+    Output {
+        outpoint: OutPoint,
+    },
+    Transaction {
+        txid: Txid,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(super) enum BatchValue {
+    Tip(BlockTip),
+    Block(Block),
+    Rune(Box<RuneResponse>),
+    Address(AddressData),
+    Output(TxOutResponse),
+    Transaction(Transaction),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(super) enum BatchResult {
+    Success {
+        result: BatchValue,
+    },
+    Error {
+        code: u16,
+        error_code: ErrorCode,
+        error: String,
+    },
+}
+
+/// Runs every call in order. A failing call doesn't affect the others. The
+/// RPC client is only taken from the pool once, and only if a call needs it.
+pub(super) async fn run(
+    index: Arc<Index>,
+    config: Arc<ServerConfig>,
+    bitcoin_rpc_pool: RpcClientPool,
+    blocking: &BlockingPool,
+    calls: Vec<serde_json::Value>,
+) -> ServerResult<Vec<BatchResult>> {
+    let results = blocking
+        .run(move || {
+            let mut client = None;
+            calls
+                .into_iter()
+                .map(|call| execute(&index, &config, &bitcoin_rpc_pool, &mut client, call))
+                .collect::<Vec<_>>()
+        })
+        .await?;
+
+    let mut batch_results = Vec::with_capacity(results.len());
+    for result in results {
+        batch_results.push(match result {
+            Ok(result) => BatchResult::Success { result },
+            Err(err) => {
+                let (code, error_code, error) = rendered_error(err).await;
+                BatchResult::Error {
+                    code,
+                    error_code,
+                    error,
+                }
+            }
+        });
+    }
+
+    Ok(batch_results)
+}
+
+/// Status, code and message of `err` exactly as a single-call endpoint would
+/// render it.
+pub(super) async fn rendered_error(err: ServerError) -> (u16, ErrorCode, String) {
+    let response = err.into_response();
+    let code = response.status().as_u16();
+    let ErrorResponse {
+        code: error_code,
+        error,
+        ..
+    } = body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_else(|| ErrorResponse::new(ErrorCode::InternalError, String::new()));
+    (code, error_code, error)
+}
+
+fn execute(
+    index: &Arc<Index>,
+    config: &ServerConfig,
+    bitcoin_rpc_pool: &RpcClientPool,
+    client: &mut Option<PooledClient>,
+    call: serde_json::Value,
+) -> ServerResult<BatchValue> {
+    let call: BatchCall =
+        serde_json::from_value(call).map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+    Ok(match call {
+        BatchCall::Tip => BatchValue::Tip(api::tip(index.clone())?),
+        BatchCall::Block {
+            query: DeserializeFromStr(query),
+        } => BatchValue::Block(
+            api::block(index.clone(), &query)
+                .or_not_indexed_yet(index, block_query_height(&query))?,
+        ),
+        BatchCall::Rune {
+            rune: DeserializeFromStr(rune),
+        } => BatchValue::Rune(Box::new(api::rune(index.clone(), &rune)?)),
+        BatchCall::Address {
+            address,
+            pagination,
+        } => {
+            if !config.index_addresses {
+                return Err(ServerError::BadRequest(
+                    "addresses are not indexed. Enable --index-addresses to index addresses"
+                        .to_string(),
+                ));
+            }
+
+            let address = network::require_network(address, config.chain)?;
+
+            let pagination = check_page_size(pagination, config.max_page_size)?;
+
+            BatchValue::Address(api::address(index.clone(), &address, pagination)?)
+        }
+        BatchCall::Output { outpoint } => BatchValue::Output(
+            api::output(index.clone(), &outpoint).or_not_indexed_yet(index, None)?,
+        ),
+        BatchCall::Transaction { txid } => {
+            let client = match client {
+                Some(client) => client,
+                None => client.insert(bitcoin_rpc_pool.get()?),
+            };
+
+            BatchValue::Transaction(
+                api::transaction_with_client(index, client, &txid)
+                    .or_not_indexed_yet(index, None)?,
+            )
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_batch_call() {
+        assert!(matches!(
+            serde_json::from_str::<BatchCall>(r#"{"method":"tip"}"#).unwrap(),
+            BatchCall::Tip
+        ));
+        assert!(matches!(
+            serde_json::from_str::<BatchCall>(r#"{"method":"block","params":{"query":"840000"}}"#)
+                .unwrap(),
+            BatchCall::Block { .. }
+        ));
+        assert!(matches!(
+            serde_json::from_str::<BatchCall>(
+                r#"{"method":"address","params":{"address":"bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq","limit":10}}"#
+            )
+            .unwrap(),
+            BatchCall::Address {
+                pagination: Pagination { skip: 0, limit: 10 },
+                ..
+            }
+        ));
+        assert!(serde_json::from_str::<BatchCall>(r#"{"method":"unknown"}"#).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    super::error::ServerResult,
+    std::sync::Arc,
+    tokio::{sync::Semaphore, task},
+    tracing::Span,
+};
+
+/// Runs synchronous index work on tokio's blocking threads, at most
+/// `--blocking-threads` jobs at a time, so slow queries queue up instead of
+/// tying up the threads that serve connections.
+#[derive(Clone)]
+pub(super) struct BlockingPool {
+    permits: Arc<Semaphore>,
+}
+
+impl BlockingPool {
+    pub(super) fn new(threads: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(threads.max(1))),
+        }
+    }
+
+    pub(super) async fn run<F, T>(&self, f: F) -> ServerResult<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("blocking pool semaphore is never closed");
+
+        // The permit moves into the task so it's only released once the work
+        // is done, even if the request is dropped in the meantime. The span
+        // keeps the request's id on what the work logs.
+        let span = Span::current();
+        Ok(task::spawn_blocking(move || {
+            let _permit = permit;
+            let _span = span.enter();
+            f()
+        })
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::Duration,
+        },
+    };
+
+    #[tokio::test]
+    async fn limits_concurrent_jobs() {
+        let pool = BlockingPool::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let jobs = (0..8).map(|_| {
+            let (pool, running, max_running) = (pool.clone(), running.clone(), max_running.clone());
+            tokio::spawn(async move {
+                pool.run(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await
+                .unwrap();
+            })
+        });
+
+        for job in jobs.collect::<Vec<_>>() {
+            job.await.unwrap();
+        }
+
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+}
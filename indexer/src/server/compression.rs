@@ -0,0 +1,248 @@
+use {
+    super::{content_dictionary::ContentDictionary, ServerConfig},
+    crate::api::content::AcceptEncoding,
+    axum::body::{Body, Bytes, HttpBody},
+    brotli::enc::BrotliEncoderParams,
+    clap::ValueEnum,
+    flate2::{write::GzEncoder, Compression},
+    http::{header, HeaderMap, HeaderValue},
+    std::io::{self, Write},
+    tower_http::compression::{
+        predicate::{And, DefaultPredicate, Predicate},
+        CompressionLayer, CompressionLevel,
+    },
+};
+
+/// Content encoding the server may compress responses with.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Br,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    fn content_encoding(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            Self::Gzip => "gzip",
+            Self::Br => "br",
+            Self::Zstd => "zstd",
+        })
+    }
+
+    fn compress(self, level: CompressionLevel, body: &[u8]) -> io::Result<Vec<u8>> {
+        // Levels are mapped like the compression layer does.
+        match self {
+            Self::Gzip => {
+                let level = match level {
+                    CompressionLevel::Fastest => Compression::fast(),
+                    CompressionLevel::Best => Compression::best(),
+                    CompressionLevel::Precise(level) => Compression::new(level.clamp(0, 9) as u32),
+                    _ => Compression::default(),
+                };
+                let mut encoder = GzEncoder::new(Vec::new(), level);
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            Self::Br => {
+                let params = BrotliEncoderParams {
+                    quality: match level {
+                        CompressionLevel::Fastest => 0,
+                        CompressionLevel::Best => 11,
+                        CompressionLevel::Precise(level) => level.clamp(0, 11),
+                        _ => 4,
+                    },
+                    ..Default::default()
+                };
+                let mut compressed = Vec::new();
+                brotli::BrotliCompress(&mut &body[..], &mut compressed, &params)?;
+                Ok(compressed)
+            }
+            Self::Zstd => {
+                let level = zstd_level(level);
+                let mut encoder = zstd::Encoder::new(Vec::new(), level)?;
+                limit_zstd_window(&mut encoder, level)?;
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+pub(super) fn zstd_level(level: CompressionLevel) -> i32 {
+    let (fastest, best) = zstd::compression_level_range().into_inner();
+    match level {
+        CompressionLevel::Fastest => fastest,
+        CompressionLevel::Best => best,
+        CompressionLevel::Precise(level) => level.clamp(fastest, best),
+        _ => zstd::DEFAULT_COMPRESSION_LEVEL,
+    }
+}
+
+/// Browsers only support windows up to 8 MB, which levels from 17 exceed.
+pub(super) fn limit_zstd_window<W: Write>(
+    encoder: &mut zstd::Encoder<'_, W>,
+    level: i32,
+) -> io::Result<()> {
+    if level >= 17 {
+        encoder.window_log(23)?;
+    }
+    Ok(())
+}
+
+/// Compresses `body` up front instead of leaving it to the compression layer,
+/// which streams without a `Content-Length`. Only compresses what the layer
+/// would have, with the dictionary if the client has it and accepts `dcz`, or
+/// else with the first of br, zstd and gzip that's enabled and accepted.
+/// Returns the body unchanged otherwise.
+pub(super) fn precompress(
+    config: &ServerConfig,
+    accept_encoding: &AcceptEncoding,
+    dictionary: Option<&ContentDictionary>,
+    available_dictionary: Option<&HeaderValue>,
+    headers: &mut HeaderMap,
+    body: Vec<u8>,
+) -> io::Result<Vec<u8>> {
+    if headers.contains_key(header::CONTENT_ENCODING) {
+        return Ok(body);
+    }
+
+    let body = Bytes::from(body);
+    let should_compress = {
+        let mut response = http::Response::new(Body::from(body.clone()));
+        *response.headers_mut() = headers.clone();
+        DefaultPredicate::new().should_compress(&response)
+    };
+    if !should_compress {
+        return Ok(body.into());
+    }
+
+    headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+    if let Some(dictionary) = dictionary {
+        headers.insert(
+            header::VARY,
+            HeaderValue::from_static("accept-encoding, available-dictionary"),
+        );
+
+        if accept_encoding.is_acceptable(&HeaderValue::from_static("dcz"))
+            && available_dictionary.is_some_and(|available| dictionary.is_available(available))
+        {
+            let compressed = dictionary.compress(&body)?;
+            headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("dcz"));
+            headers.insert(header::CONTENT_LENGTH, compressed.len().into());
+            return Ok(compressed);
+        }
+    }
+
+    let Some(algorithm) = [
+        CompressionAlgorithm::Br,
+        CompressionAlgorithm::Zstd,
+        CompressionAlgorithm::Gzip,
+    ]
+    .into_iter()
+    .find(|algorithm| {
+        config.compression_algorithms.contains(algorithm)
+            && accept_encoding.is_acceptable(&algorithm.content_encoding())
+    }) else {
+        return Ok(body.into());
+    };
+
+    let compressed = algorithm.compress(config.compression_level, &body)?;
+    headers.insert(header::CONTENT_ENCODING, algorithm.content_encoding());
+    headers.insert(header::CONTENT_LENGTH, compressed.len().into());
+
+    Ok(compressed)
+}
+
+/// Parses `--compression-level`: `fastest`, `best`, `default` or an
+/// algorithm-specific number, which is clamped to the algorithm's maximum.
+pub(crate) fn parse_compression_level(s: &str) -> Result<CompressionLevel, String> {
+    match s {
+        "fastest" => Ok(CompressionLevel::Fastest),
+        "best" => Ok(CompressionLevel::Best),
+        "default" => Ok(CompressionLevel::Default),
+        _ => s.parse().map(CompressionLevel::Precise).map_err(|_| {
+            format!("invalid compression level `{s}`, expected fastest, best, default or a number")
+        }),
+    }
+}
+
+/// Response extension for bodies that must be sent as is, e.g. inscription
+/// content that is often compressed already.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct SkipCompression;
+
+#[derive(Copy, Clone, Debug)]
+pub(super) struct NotSkipped;
+
+impl Predicate for NotSkipped {
+    fn should_compress<B>(&self, response: &http::Response<B>) -> bool
+    where
+        B: HttpBody,
+    {
+        response.extensions().get::<SkipCompression>().is_none()
+    }
+}
+
+pub(super) fn compression_layer(
+    config: &ServerConfig,
+) -> CompressionLayer<And<DefaultPredicate, NotSkipped>> {
+    let enabled = |algorithm| config.compression_algorithms.contains(&algorithm);
+
+    CompressionLayer::new()
+        .gzip(enabled(CompressionAlgorithm::Gzip))
+        .br(enabled(CompressionAlgorithm::Br))
+        .zstd(enabled(CompressionAlgorithm::Zstd))
+        .quality(config.compression_level)
+        .compress_when(DefaultPredicate::new().and(NotSkipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_level() {
+        assert_eq!(
+            parse_compression_level("fastest").unwrap(),
+            CompressionLevel::Fastest
+        );
+        assert_eq!(
+            parse_compression_level("6").unwrap(),
+            CompressionLevel::Precise(6)
+        );
+        assert!(parse_compression_level("fast").is_err());
+    }
+
+    #[test]
+    fn compress_round_trip() {
+        use std::io::Read;
+
+        let body = "inscription content ".repeat(100).into_bytes();
+
+        let gzip = CompressionAlgorithm::Gzip
+            .compress(CompressionLevel::Default, &body)
+            .unwrap();
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+
+        let br = CompressionAlgorithm::Br
+            .compress(CompressionLevel::Best, &body)
+            .unwrap();
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(br.as_slice(), 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+
+        let zstd = CompressionAlgorithm::Zstd
+            .compress(CompressionLevel::Precise(19), &body)
+            .unwrap();
+        assert_eq!(zstd::decode_all(zstd.as_slice()).unwrap(), body);
+        assert!(zstd.len() < body.len());
+    }
+}
@@ -0,0 +1,171 @@
+use {
+    super::compression::{limit_zstd_window, zstd_level},
+    axum::body::Bytes,
+    base64::{prelude::BASE64_STANDARD, Engine},
+    bitcoin::hashes::{sha256, Hash},
+    http::HeaderValue,
+    std::{
+        fs,
+        io::{self, Write},
+        path::Path,
+    },
+    tower_http::compression::CompressionLevel,
+    zstd::dict::EncoderDictionary,
+};
+
+/// Start of a `dcz` body, before the dictionary's SHA-256.
+const DCZ_MAGIC: [u8; 8] = [0x5e, 0x2a, 0x4d, 0x18, 0x20, 0x00, 0x00, 0x00];
+
+/// Zstd dictionary for inscription content, shared with browsers through
+/// Compression Dictionary Transport (RFC 9842). Many inscriptions start with
+/// the same HTML or SVG boilerplate, which then doesn't have to be sent again.
+/// A body compressed with the dictionary can only be decoded by a client that
+/// has it, so it's only sent as `dcz` to requests whose `Available-Dictionary`
+/// names this one.
+///
+/// To use it, train a dictionary on typical inscriptions, e.g. with
+/// `zstd --train samples/* -o inscriptions.dict`, and start the server with
+/// `--inscription-content-dictionary inscriptions.dict`. A browser that
+/// fetches `/inscriptions/dictionary` keeps it for `/inscription/*` because
+/// of its `Use-As-Dictionary` header. Its later requests there send
+/// `Available-Dictionary: :<base64 SHA-256>:` and `Accept-Encoding: dcz`, and
+/// get `Content-Encoding: dcz` bodies back. Other clients keep getting br,
+/// zstd or gzip.
+///
+/// With a dictionary of common SVG, HTML and BRC-20 boilerplate, recursive
+/// SVGs and HTML pages of 200 to 400 bytes compress to 48-64% of plain zstd's
+/// size, 40-byte header included. JSON of about 60 bytes, such as BRC-20
+/// operations, comes out a few bytes larger than with plain zstd, since the
+/// header costs more than the dictionary saves. The samples are in the tests
+/// below.
+pub(super) struct ContentDictionary {
+    bytes: Bytes,
+    hash: [u8; 32],
+    prepared: EncoderDictionary<'static>,
+    level: i32,
+}
+
+impl ContentDictionary {
+    pub(super) fn load(path: &Path, level: CompressionLevel) -> io::Result<Self> {
+        Ok(Self::new(fs::read(path)?, level))
+    }
+
+    fn new(bytes: Vec<u8>, level: CompressionLevel) -> Self {
+        let level = zstd_level(level);
+        Self {
+            hash: sha256::Hash::hash(&bytes).to_byte_array(),
+            prepared: EncoderDictionary::copy(&bytes, level),
+            bytes: Bytes::from(bytes),
+            level,
+        }
+    }
+
+    /// Shared with every response that serves the dictionary.
+    pub(super) fn bytes(&self) -> Bytes {
+        self.bytes.clone()
+    }
+
+    /// The dictionary's hash as an RFC 8941 byte sequence, the form
+    /// `Available-Dictionary` takes.
+    pub(super) fn available_dictionary(&self) -> String {
+        format!(":{}:", BASE64_STANDARD.encode(self.hash))
+    }
+
+    pub(super) fn is_available(&self, available_dictionary: &HeaderValue) -> bool {
+        available_dictionary
+            .to_str()
+            .is_ok_and(|value| value.trim() == self.available_dictionary())
+    }
+
+    /// `body` as a `dcz` response body: the magic number, the dictionary's
+    /// hash and a zstd frame compressed with the dictionary.
+    pub(super) fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+        let mut compressed = Vec::with_capacity(DCZ_MAGIC.len() + self.hash.len() + body.len());
+        compressed.extend_from_slice(&DCZ_MAGIC);
+        compressed.extend_from_slice(&self.hash);
+
+        let mut encoder = zstd::Encoder::with_prepared_dictionary(compressed, &self.prepared)?;
+        limit_zstd_window(&mut encoder, self.level)?;
+        encoder.write_all(body)?;
+        encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG_HEADER: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 1000 1000" preserveAspectRatio="xMidYMid meet"><style>image{image-rendering:pixelated}</style>"#;
+
+    const HTML_HEADER: &str = r#"<!DOCTYPE html><html lang="en"><head><meta charset="utf-8"><meta name="viewport" content="width=device-width,initial-scale=1"><style>html,body{margin:0;padding:0;width:100%;height:100%;overflow:hidden}canvas,img{width:100%;height:100%;object-fit:contain;image-rendering:pixelated}</style></head><body>"#;
+
+    const BRC20_MINT: &str = r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#;
+
+    /// Typical small inscriptions: recursive SVGs and HTML pages and BRC-20
+    /// operations.
+    fn samples() -> Vec<Vec<u8>> {
+        vec![
+            format!(r#"{SVG_HEADER}<image href="/content/abci0"/></svg>"#),
+            format!(
+                r#"{SVG_HEADER}<image href="/content/6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0"/><image href="/content/0301e0480b374b32851a9462db29dc19fe830a7f7d7a88b81612b9d42099c0aei0"/></svg>"#
+            ),
+            format!(
+                r#"{HTML_HEADER}<script src="/content/2dbdf9ebbec6be793fd16ae9b797c7cf968ab2427166aaf390b90b71778266abi0"></script></body></html>"#
+            ),
+            format!(
+                r#"{HTML_HEADER}<img src="/content/9a4f3a4e8b7d3a2f4b1c8e0d9f6a7b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9ai0"></body></html>"#
+            ),
+            BRC20_MINT.to_string(),
+            r#"{"p":"brc-20","op":"transfer","tick":"sats","amt":"250000000"}"#.to_string(),
+        ]
+        .into_iter()
+        .map(String::into_bytes)
+        .collect()
+    }
+
+    #[test]
+    fn compresses_sample_inscriptions_better_than_plain_zstd() {
+        let dictionary = ContentDictionary::new(
+            format!("{BRC20_MINT}{HTML_HEADER}{SVG_HEADER}").into(),
+            CompressionLevel::Default,
+        );
+
+        let (mut plain, mut dcz) = (0, 0);
+        for body in samples() {
+            let compressed = dictionary.compress(&body).unwrap();
+            plain += zstd::encode_all(body.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL)
+                .unwrap()
+                .len();
+            dcz += compressed.len();
+        }
+
+        // 692 bytes against 1190, `dcz` headers included. The BRC-20
+        // operations alone come out 7 bytes larger, the header costing more
+        // than the dictionary saves.
+        assert!(dcz * 10 < plain * 6, "{dcz} bytes against {plain}");
+    }
+
+    #[test]
+    fn compresses_small_inscriptions_better_than_plain_zstd() {
+        let dictionary = ContentDictionary::new(SVG_HEADER.into(), CompressionLevel::Default);
+        let body = format!(r#"{SVG_HEADER}<image href="/content/abci0"/></svg>"#).into_bytes();
+
+        let dcz = dictionary.compress(&body).unwrap();
+        assert_eq!(dcz[..8], DCZ_MAGIC);
+        assert_eq!(dcz[8..40], dictionary.hash);
+
+        let mut decoder =
+            zstd::stream::Decoder::with_dictionary(&dcz[40..], &dictionary.bytes()).unwrap();
+        let mut decompressed = Vec::new();
+        io::copy(&mut decoder, &mut decompressed).unwrap();
+        assert_eq!(decompressed, body);
+
+        let plain = zstd::encode_all(body.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+        // 49 bytes against 174, before the 40 bytes of `dcz` header.
+        assert!(dcz[40..].len() < plain.len() / 2);
+
+        assert!(dictionary
+            .is_available(&HeaderValue::from_str(&dictionary.available_dictionary()).unwrap()));
+        assert!(!dictionary.is_available(&HeaderValue::from_static(":AAAA:")));
+    }
+}
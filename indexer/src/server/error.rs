@@ -1,12 +1,18 @@
 use {
+    super::syncing::RETRY_AFTER_SECS,
     crate::{
         api::{content::ContentError, ApiError},
-        bitcoin_rpc::{RpcClientError, RpcClientPoolError},
-        index::{IndexError, StoreError},
+        bitcoin_rpc::{is_transient_rpc_error, RpcClientError, RpcClientPoolError},
+        index::{Index, IndexError, ReindexError, StoreError, UpdaterError},
+    },
+    axum::{
+        extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+        response::{IntoResponse, Response},
+        Json,
     },
-    axum::response::{IntoResponse, Response},
     http::{header, HeaderValue, StatusCode},
     std::fmt::Write,
+    titan_types::{query, ErrorCode, ErrorResponse, NetworkMismatch},
     tracing::error,
 };
 
@@ -29,50 +35,181 @@ pub(super) enum ServerError {
 
     #[error("not found: {0}")]
     NotFound(String),
+
+    /// The node couldn't answer a request that needs it.
+    #[error("bad gateway: {0}")]
+    BadGateway(String),
+
+    #[error("blocking task failed: {0}")]
+    TaskError(#[from] tokio::task::JoinError),
+
+    #[error("wrong network: {message}")]
+    WrongNetwork {
+        message: String,
+        network: NetworkMismatch,
+    },
+
+    /// Not found, but the index hasn't reached the height it could be at.
+    #[error("{message}")]
+    NotIndexedYet {
+        message: String,
+        indexed_height: Option<u64>,
+    },
+
+    /// An extractor couldn't make sense of the request, e.g. a malformed
+    /// query string or JSON body.
+    #[error("{message}")]
+    Rejected { status: StatusCode, message: String },
+
+    /// The client expected the server to index another chain.
+    #[error("unexpected chain: {message}")]
+    UnexpectedChain {
+        message: String,
+        network: NetworkMismatch,
+    },
+}
+
+impl From<JsonRejection> for ServerError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::Rejected {
+            status: rejection.status(),
+            message: rejection.body_text(),
+        }
+    }
+}
+
+impl From<PathRejection> for ServerError {
+    fn from(rejection: PathRejection) -> Self {
+        Self::Rejected {
+            status: rejection.status(),
+            message: rejection.body_text(),
+        }
+    }
+}
+
+impl From<QueryRejection> for ServerError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::Rejected {
+            status: rejection.status(),
+            message: rejection.body_text(),
+        }
+    }
 }
 
 pub(super) type ServerResult<T = Response> = Result<T, ServerError>;
 
+impl ServerError {
+    /// Whether the node couldn't be reached, so the request may succeed if
+    /// retried.
+    pub(super) fn is_transient_rpc_error(&self) -> bool {
+        match self {
+            Self::ApiError(ApiError::RpcError(error))
+            | Self::ApiError(ApiError::IndexError(IndexError::RpcApiError(error))) => {
+                is_transient_rpc_error(error)
+            }
+            _ => false,
+        }
+    }
+
+    fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::NotFound(_)
+                | Self::ApiError(ApiError::IndexError(IndexError::StoreError(
+                    StoreError::NotFound(_)
+                )))
+        )
+    }
+}
+
+/// JSON error body with a machine-readable `code`.
+pub(super) fn error_response(
+    status: StatusCode,
+    code: ErrorCode,
+    error: impl Into<String>,
+) -> Response {
+    (status, Json(ErrorResponse::new(code, error.into()))).into_response()
+}
+
+fn internal_error() -> Response {
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCode::InternalError,
+        StatusCode::INTERNAL_SERVER_ERROR
+            .canonical_reason()
+            .unwrap_or_default(),
+    )
+}
+
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
         match self {
-            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+            Self::BadRequest(message) => {
+                error_response(StatusCode::BAD_REQUEST, ErrorCode::BadRequest, message)
+            }
+            Self::Rejected { status, message } if status.is_client_error() => {
+                error_response(status, ErrorCode::BadRequest, message)
+            }
+            Self::Rejected { message, .. } => {
+                error!("error extracting request: {message}");
+                internal_error()
+            }
+            Self::BadGateway(message) => {
+                error!("rpc error: {message}");
+                error_response(StatusCode::BAD_GATEWAY, ErrorCode::RpcError, message)
+            }
             Self::ApiError(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(
                 message,
-            )))) => (StatusCode::NOT_FOUND, message).into_response(),
+            )))) => error_response(StatusCode::NOT_FOUND, ErrorCode::NotFound, message),
+            Self::ApiError(error @ ApiError::SubscriptionLimit(_)) => error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::SubscriptionLimit,
+                error.to_string(),
+            ),
+            Self::ApiError(error @ ApiError::InvalidCursor(_)) => error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidCursor,
+                error.to_string(),
+            ),
+            Self::ApiError(ApiError::IdempotencyKeyReused(error)) => error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::IdempotencyKeyReused,
+                error.to_string(),
+            ),
+            Self::ApiError(ApiError::BadRequest(message)) => {
+                error_response(StatusCode::BAD_REQUEST, ErrorCode::BadRequest, message)
+            }
             Self::ApiError(ApiError::RpcError(error)) => {
                 error!("rpc error: {error}");
-                (StatusCode::BAD_REQUEST, error.to_string()).into_response()
+                error_response(
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::RpcError,
+                    error.to_string(),
+                )
             }
+            Self::ApiError(ApiError::IndexError(IndexError::UpdaterError(
+                UpdaterError::Reindex(error),
+            ))) => match error {
+                ReindexError::Overlapping { .. } => {
+                    error_response(StatusCode::CONFLICT, ErrorCode::Conflict, error.to_string())
+                }
+                _ => error_response(
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::BadRequest,
+                    error.to_string(),
+                ),
+            },
             Self::ApiError(error) => {
                 error!("error serving request: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+                internal_error()
             }
             Self::RpcClientError(error) => {
                 error!("rpc client error: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+                internal_error()
             }
             Self::RpcClientPoolError(error) => {
                 error!("rpc client pool error: {error}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
-                )
-                    .into_response()
+                internal_error()
             }
             Self::ContentError(ContentError::NotAcceptable {
                 accept_encoding,
@@ -89,22 +226,52 @@ impl IntoResponse for ServerError {
                     write!(message, " `Accept-Encoding` header not present").unwrap();
                 };
 
-                (StatusCode::NOT_ACCEPTABLE, message).into_response()
+                error_response(
+                    StatusCode::NOT_ACCEPTABLE,
+                    ErrorCode::NotAcceptable,
+                    message,
+                )
             }
             Self::ContentError(_) => {
                 error!("content error");
+                internal_error()
+            }
+            Self::TaskError(error) => {
+                error!("blocking task failed: {error}");
+                internal_error()
+            }
+            Self::WrongNetwork { message, network } => {
+                let mut body = ErrorResponse::new(ErrorCode::WrongNetwork, message);
+                body.network = Some(network);
+                (StatusCode::BAD_REQUEST, Json(body)).into_response()
+            }
+            Self::UnexpectedChain { message, network } => {
+                let mut body = ErrorResponse::new(ErrorCode::WrongNetwork, message);
+                body.network = Some(network);
+                (StatusCode::CONFLICT, Json(body)).into_response()
+            }
+            Self::NotIndexedYet {
+                message,
+                indexed_height,
+            } => {
+                let mut body = ErrorResponse::new(ErrorCode::NotIndexedYet, message);
+                body.indexed_height = indexed_height;
                 (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                        .canonical_reason()
-                        .unwrap_or_default(),
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [
+                        (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+                        (
+                            header::RETRY_AFTER,
+                            HeaderValue::from_static(RETRY_AFTER_SECS),
+                        ),
+                    ],
+                    Json(body),
                 )
                     .into_response()
             }
             Self::NotFound(message) => (
-                StatusCode::NOT_FOUND,
                 [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
-                message,
+                error_response(StatusCode::NOT_FOUND, ErrorCode::NotFound, message),
             )
                 .into_response(),
         }
@@ -123,3 +290,97 @@ impl<T> OptionExt<T> for Option<T> {
         }
     }
 }
+
+/// Height `query` is found at once indexed, `None` for a hash, which could be
+/// any block. Blocks back from the tip are counted from the indexed tip, so
+/// they don't wait for anything.
+pub(super) fn block_query_height(query: &query::Block) -> Option<u64> {
+    match query {
+        query::Block::Height(height) => Some(*height),
+        query::Block::Hash(_) => None,
+        query::Block::FromTip(_) => Some(0),
+    }
+}
+
+pub(super) trait NotIndexedExt<T> {
+    /// Reports "not found" as `NotIndexedYet` while the index is below
+    /// `height`, or below the node's tip for lookups that could be in any
+    /// block, so clients don't cache a 404 for data that isn't indexed yet.
+    fn or_not_indexed_yet(self, index: &Index, height: Option<u64>) -> ServerResult<T>;
+
+    /// `or_not_indexed_yet` for a txid lookup. Only an index that stores
+    /// transactions can be behind on one, otherwise the node serves it.
+    fn or_transaction_not_indexed_yet(self, index: &Index) -> ServerResult<T>;
+}
+
+impl<T, E: Into<ServerError>> NotIndexedExt<T> for Result<T, E> {
+    fn or_not_indexed_yet(self, index: &Index, height: Option<u64>) -> ServerResult<T> {
+        let error = match self {
+            Ok(value) => return Ok(value),
+            Err(error) => error.into(),
+        };
+
+        if !error.is_not_found() {
+            return Err(error);
+        }
+
+        let indexed_height = index
+            .get_block_count()
+            .map_err(ApiError::from)?
+            .checked_sub(1);
+        let node_height = index
+            .get_node_block_count()
+            .and_then(|count| count.checked_sub(1));
+
+        if is_indexed(indexed_height, node_height, height) {
+            return Err(error);
+        }
+
+        Err(ServerError::NotIndexedYet {
+            message: match indexed_height {
+                Some(indexed_height) => {
+                    format!("not indexed yet, the index is at height {indexed_height}")
+                }
+                None => "not indexed yet, no blocks are indexed".to_string(),
+            },
+            indexed_height,
+        })
+    }
+
+    fn or_transaction_not_indexed_yet(self, index: &Index) -> ServerResult<T> {
+        if index.is_indexing_bitcoin_transactions() {
+            self.or_not_indexed_yet(index, None)
+        } else {
+            self.map_err(Into::into)
+        }
+    }
+}
+
+/// Whether the index reached `height`, or the node's tip if `None`. Heights
+/// past the node's tip don't exist on either yet.
+fn is_indexed(indexed_height: Option<u64>, node_height: Option<u64>, height: Option<u64>) -> bool {
+    let height = match (height, node_height) {
+        (Some(height), Some(node_height)) => Some(height.min(node_height)),
+        (height, node_height) => height.or(node_height),
+    };
+
+    matches!((indexed_height, height), (Some(indexed_height), Some(height)) if height <= indexed_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_found_is_final_once_the_height_is_indexed() {
+        assert!(is_indexed(Some(100), Some(110), Some(100)));
+        assert!(!is_indexed(Some(100), Some(110), Some(105)));
+        assert!(is_indexed(Some(110), Some(110), Some(200)));
+        assert!(!is_indexed(Some(100), None, Some(200)));
+
+        assert!(is_indexed(Some(110), Some(110), None));
+        assert!(!is_indexed(Some(100), Some(110), None));
+        assert!(!is_indexed(Some(100), None, None));
+        assert!(!is_indexed(None, Some(110), Some(0)));
+    }
+}
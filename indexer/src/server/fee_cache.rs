@@ -0,0 +1,71 @@
+use {
+    super::error::ServerResult,
+    std::{
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+    titan_types::FeeEstimates,
+};
+
+/// Last `/fees` response, so polling wallets don't each cost the node an
+/// `estimatesmartfee` call per target.
+pub(super) struct FeeCache {
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, FeeEstimates)>>,
+}
+
+impl FeeCache {
+    pub(super) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The cached estimates if they're younger than the TTL, otherwise
+    /// `fetch`'s. Requests arriving during a fetch wait for it instead of
+    /// fetching too. Errors aren't cached.
+    pub(super) fn get(
+        &self,
+        fetch: impl FnOnce() -> ServerResult<FeeEstimates>,
+    ) -> ServerResult<FeeEstimates> {
+        let mut cached = self.cached.lock().unwrap();
+        if let Some((fetched_at, fees)) = cached.as_ref() {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(fees.clone());
+            }
+        }
+
+        let fees = fetch()?;
+        *cached = Some((Instant::now(), fees.clone()));
+        Ok(fees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::server::error::ServerError};
+
+    fn fees() -> FeeEstimates {
+        FeeEstimates {
+            estimates: Vec::new(),
+            mempool_histogram: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn fetches_again_after_the_ttl() {
+        let cache = FeeCache::new(Duration::from_secs(60));
+        assert!(cache
+            .get(|| Err(ServerError::BadGateway("down".into())))
+            .is_err());
+        assert_eq!(cache.get(|| Ok(fees())).unwrap(), fees());
+        assert!(cache.get(|| panic!("fetched within the ttl")).is_ok());
+
+        let cache = FeeCache::new(Duration::ZERO);
+        cache.get(|| Ok(fees())).unwrap();
+        assert!(cache
+            .get(|| Err(ServerError::BadGateway("down".into())))
+            .is_err());
+    }
+}
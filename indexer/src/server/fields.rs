@@ -0,0 +1,93 @@
+use {
+    super::meta::is_json,
+    axum::{
+        body,
+        extract::{Query, Request},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    },
+    http::{header, Method, StatusCode},
+    serde::Deserialize,
+    std::collections::HashSet,
+    tracing::error,
+};
+
+#[derive(Deserialize)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Keeps the top-level fields of `value` named in `fields`. Anything but an
+/// object is returned as is.
+fn project(value: serde_json::Value, fields: &HashSet<&str>) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(mut object) => {
+            object.retain(|key, _| fields.contains(key.as_str()));
+            serde_json::Value::Object(object)
+        }
+        value => value,
+    }
+}
+
+/// With `?fields=a,b`, drops every other top-level field from successful JSON
+/// object responses. Unknown names are ignored. Runs inside `with_meta`, so it
+/// selects from the data rather than from the wrapper.
+pub(super) async fn select_fields(request: Request, next: Next) -> Response {
+    let fields = Query::<FieldsQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(query)| query.fields)
+        .filter(|fields| !fields.is_empty());
+    let Some(fields) = fields else {
+        return next.run(request).await;
+    };
+    let is_head = request.method() == Method::HEAD;
+
+    let response = next.run(request).await;
+    if !response.status().is_success() || !is_json(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    // The selected body is smaller, and so are its validators different.
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::ETAG);
+    if is_head {
+        return Response::from_parts(parts, body);
+    }
+
+    let value: serde_json::Value = match body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|err| err.to_string()))
+    {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to select response fields: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let fields: HashSet<&str> = fields.split(',').map(str::trim).collect();
+    let selected = Json(project(value, &fields)).into_response();
+    Response::from_parts(parts, selected.into_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn keeps_only_selected_top_level_fields() {
+        let fields = HashSet::from(["fee", "txid", "unknown"]);
+
+        assert_eq!(
+            project(
+                json!({ "txid": "00", "fee": 1, "vin": [{ "fee": 2, "txid": "01" }] }),
+                &fields
+            ),
+            json!({ "txid": "00", "fee": 1 })
+        );
+        assert_eq!(project(json!([1, 2]), &fields), json!([1, 2]));
+    }
+}
@@ -0,0 +1,43 @@
+use {
+    axum::{
+        body::{Body, Bytes, HttpBody},
+        extract::Request,
+        middleware::Next,
+        response::Response,
+    },
+    futures::stream,
+    http::{header, Method},
+    std::convert::Infallible,
+};
+
+/// Marks a response to a HEAD request whose body was never serialized, so
+/// its length isn't known either.
+#[derive(Clone, Copy)]
+pub(super) struct BodySkipped;
+
+/// Drops the body of responses to HEAD requests, which axum routes to the GET
+/// handlers. Done before the other middleware sees the response, so a body
+/// that's thrown away anyway isn't re-serialized by `with_meta` or compressed.
+/// The `Content-Length` of the GET response is kept when it's known. Most
+/// handlers don't produce a body for HEAD in the first place, see
+/// `ResponseFormat`.
+pub(super) async fn strip_head_body(request: Request, next: Next) -> Response {
+    if request.method() != Method::HEAD {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = next.run(request).await.into_parts();
+    if parts.extensions.get::<BodySkipped>().is_some() {
+        // An empty body would be sent as `Content-Length: 0`, which isn't the
+        // length the GET response has.
+        parts.headers.remove(header::CONTENT_LENGTH);
+        let unknown_length = stream::empty::<Result<Bytes, Infallible>>();
+        return Response::from_parts(parts, Body::from_stream(unknown_length));
+    }
+
+    if let Some(len) = body.size_hint().exact() {
+        parts.headers.insert(header::CONTENT_LENGTH, len.into());
+    }
+
+    Response::from_parts(parts, Body::empty())
+}
@@ -0,0 +1,112 @@
+use {
+    crate::{api, index::Index},
+    axum::{
+        body::{self, HttpBody},
+        extract::{Query, Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    },
+    http::{header, Method, StatusCode},
+    serde::Deserialize,
+    std::{
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    titan_types::{ResponseMeta, WithMeta},
+    tracing::error,
+};
+
+/// Largest body `with_meta` buffers to wrap.
+const MAX_WRAPPED_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct MetaQuery {
+    #[serde(default)]
+    with_meta: bool,
+}
+
+pub(super) fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/json"))
+}
+
+/// With `?with_meta=true`, wraps successful JSON responses in `{ data, meta }`
+/// so clients joining several responses can tell whether they were computed
+/// at the same tip. Other responses, e.g. errors, CBOR and streams, are left
+/// as they are, and so are JSON bodies over `MAX_WRAPPED_BODY_SIZE`.
+pub(super) async fn wrap_with_meta(
+    State(index): State<Arc<Index>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let with_meta =
+        Query::<MetaQuery>::try_from_uri(request.uri()).is_ok_and(|Query(query)| query.with_meta);
+    if !with_meta {
+        return next.run(request).await;
+    }
+
+    // A HEAD response has no body to wrap, but it mustn't claim the validators
+    // of the unwrapped one.
+    if request.method() == Method::HEAD {
+        let mut response = next.run(request).await;
+        if response.status().is_success() && is_json(&response) {
+            response.headers_mut().remove(header::CONTENT_LENGTH);
+            response.headers_mut().remove(header::ETAG);
+        }
+        return response;
+    }
+
+    let Ok(tip) = api::tip(index.clone()) else {
+        return next.run(request).await;
+    };
+
+    let response = next.run(request).await;
+    // Streamed bodies have no exact size.
+    let buffered = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|len| len <= MAX_WRAPPED_BODY_SIZE);
+    if !response.status().is_success() || !is_json(&response) || !buffered {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let data: serde_json::Value = match body::to_bytes(body, MAX_WRAPPED_BODY_SIZE as usize)
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|err| err.to_string()))
+    {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to wrap response with meta: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let tip_changed = match api::tip(index) {
+        Ok(tip_after) => tip_after.hash != tip.hash,
+        Err(_) => true,
+    };
+
+    let meta = ResponseMeta {
+        height: tip.height,
+        block_hash: tip.hash,
+        computed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        tip_changed,
+    };
+
+    // Validators of the bare body don't apply to the wrapped one.
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::ETAG);
+
+    let wrapped = Json(WithMeta { data, meta }).into_response();
+    Response::from_parts(parts, wrapped.into_body())
+}
@@ -0,0 +1,144 @@
+use {
+    crate::{
+        index::{Index, Metrics},
+        subscription::{TcpSubscriptionManager, WebhookSubscriptionManager},
+    },
+    axum::{
+        extract::{MatchedPath, Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    http::{header, StatusCode},
+    prometheus::{GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts},
+    std::{sync::Arc, time::Instant},
+    tracing::error,
+};
+
+/// Prometheus metrics exported by the HTTP server at `/metrics`.
+pub(super) struct ServerMetrics {
+    metrics: Metrics,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    indexed_block_height: IntGauge,
+    node_block_height: IntGauge,
+    index_lag_blocks: IntGauge,
+    active_subscriptions: GaugeVec,
+}
+
+impl ServerMetrics {
+    pub(super) fn new(max_subscriptions: usize) -> Self {
+        let metrics = Metrics::new();
+
+        // Never changes, but lets utilization be graphed. The registry keeps it.
+        metrics
+            .gauge(Opts::new(
+                "titan_max_webhook_subscriptions",
+                "Webhook subscriptions that can be registered",
+            ))
+            .set(max_subscriptions as i64);
+
+        Self {
+            requests: metrics.counter_vec(
+                Opts::new("titan_http_requests_total", "HTTP requests served"),
+                &["method", "route", "status"],
+            ),
+            request_duration: metrics.histogram_vec(
+                HistogramOpts::new(
+                    "titan_http_request_duration_seconds",
+                    "HTTP request latency in seconds",
+                ),
+                &["method", "route"],
+            ),
+            indexed_block_height: metrics.gauge(Opts::new(
+                "titan_indexed_block_height",
+                "Height of the last indexed block",
+            )),
+            node_block_height: metrics.gauge(Opts::new(
+                "titan_node_block_height",
+                "Height of the node tip as of the last sync",
+            )),
+            index_lag_blocks: metrics.gauge(Opts::new(
+                "titan_index_lag_blocks",
+                "Blocks the index is behind the node tip",
+            )),
+            active_subscriptions: metrics.gauge_vec(
+                Opts::new("titan_active_subscriptions", "Active event subscriptions"),
+                &["kind"],
+            ),
+            metrics,
+        }
+    }
+
+    /// Refreshes the gauges and encodes every metric in the Prometheus text format.
+    pub(super) async fn render(
+        &self,
+        index: &Index,
+        webhook_subscription_manager: &WebhookSubscriptionManager,
+        tcp_subscription_manager: &TcpSubscriptionManager,
+    ) -> Response {
+        let indexed_block_count = index.get_block_count().ok();
+        let node_block_count = index.get_node_block_count();
+
+        if let Some(count) = indexed_block_count {
+            self.indexed_block_height
+                .set(count.saturating_sub(1) as i64);
+        }
+
+        if let Some(count) = node_block_count {
+            self.node_block_height.set(count.saturating_sub(1) as i64);
+        }
+
+        if let (Some(indexed), Some(node)) = (indexed_block_count, node_block_count) {
+            self.index_lag_blocks
+                .set(node.saturating_sub(indexed) as i64);
+        }
+
+        if let Ok(subscriptions) = webhook_subscription_manager.get_subscriptions() {
+            self.active_subscriptions
+                .with_label_values(&["webhook"])
+                .set(subscriptions.len() as f64);
+        }
+
+        self.active_subscriptions
+            .with_label_values(&["tcp"])
+            .set(tcp_subscription_manager.subscription_count().await as f64);
+
+        match self.metrics.encode() {
+            Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+            Err(err) => {
+                error!("failed to encode metrics: {err}");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+/// Records the count and latency of every request, labelled by its matched route.
+pub(super) async fn track_metrics(
+    State(metrics): State<Arc<ServerMetrics>>,
+    matched_path: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Label by route template rather than raw path to keep cardinality bounded.
+    let route = matched_path
+        .as_ref()
+        .map(MatchedPath::as_str)
+        .unwrap_or("unmatched")
+        .to_string();
+    let method = request.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+
+    metrics
+        .request_duration
+        .with_label_values(&[&method, &route])
+        .observe(start.elapsed().as_secs_f64());
+    metrics
+        .requests
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+
+    response
+}
@@ -1,6 +1,32 @@
-pub use {server::Server, server_config::ServerConfig};
+pub(crate) use {compression::parse_compression_level, rpc_retry::RpcRetry};
+pub use {compression::CompressionAlgorithm, server::Server, server_config::ServerConfig};
 
+mod address_path;
+mod auth;
+mod batch;
+mod blocking;
+mod compression;
+mod content_dictionary;
 mod deserialize_from_str;
 mod error;
+mod extract;
+mod fee_cache;
+mod fields;
+mod head;
+mod meta;
+mod metrics;
+mod negotiated;
+mod network;
+#[cfg(feature = "openapi")]
+mod openapi;
+mod outpoint_path;
+mod page;
+mod pagination_links;
+mod rate_limit;
+mod request_id;
+mod rpc_retry;
 mod server;
 mod server_config;
+mod syncing;
+mod timeout;
+mod tx_cache;
@@ -0,0 +1,143 @@
+use {
+    super::head::BodySkipped,
+    axum::{
+        extract::FromRequestParts,
+        response::{IntoResponse, Response},
+        Json,
+    },
+    http::{header, HeaderValue, Method, StatusCode},
+    serde::Serialize,
+    std::convert::Infallible,
+    tracing::error,
+};
+
+const APPLICATION_CBOR: &str = "application/cbor";
+
+/// Response format requested through the `Accept` header. Defaults to JSON.
+/// A HEAD request only gets the headers, its body isn't serialized.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ResponseFormat {
+    encoding: Encoding,
+    head: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    #[default]
+    Json,
+    Cbor,
+}
+
+impl Encoding {
+    fn from_accept(accept: &str) -> Self {
+        let accepts_cbor = accept.split(',').any(|media_range| {
+            let mut params = media_range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default();
+
+            // `q=0` means "not acceptable".
+            let rejected = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+
+            media_type.eq_ignore_ascii_case(APPLICATION_CBOR) && !rejected
+        });
+
+        if accepts_cbor {
+            Self::Cbor
+        } else {
+            Self::Json
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for ResponseFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            encoding: parts
+                .headers
+                .get(header::ACCEPT)
+                .and_then(|value| value.to_str().ok())
+                .map(Encoding::from_accept)
+                .unwrap_or_default(),
+            head: parts.method == Method::HEAD,
+        })
+    }
+}
+
+/// Serializes `T` as JSON or CBOR depending on the requested `ResponseFormat`.
+pub(super) struct Negotiated<T>(pub ResponseFormat, pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let Negotiated(format, value) = self;
+
+        let mut response = match format.encoding {
+            // Axum answers HEAD with the GET handler, but the body would
+            // only be thrown away.
+            _ if format.head => {
+                let content_type = match format.encoding {
+                    Encoding::Json => "application/json",
+                    Encoding::Cbor => APPLICATION_CBOR,
+                };
+                let mut response = [(header::CONTENT_TYPE, HeaderValue::from_static(content_type))]
+                    .into_response();
+                response.extensions_mut().insert(BodySkipped);
+                response
+            }
+            Encoding::Json => Json(value).into_response(),
+            Encoding::Cbor => {
+                let mut body = Vec::new();
+                if let Err(err) = ciborium::into_writer(&value, &mut body) {
+                    error!("failed to serialize CBOR response: {err}");
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+
+                (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(APPLICATION_CBOR),
+                    )],
+                    body,
+                )
+                    .into_response()
+            }
+        };
+
+        response
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("accept"));
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_from_accept() {
+        assert_eq!(Encoding::from_accept("*/*"), Encoding::Json);
+        assert_eq!(Encoding::from_accept("application/json"), Encoding::Json);
+        assert_eq!(Encoding::from_accept("application/cbor"), Encoding::Cbor);
+        assert_eq!(
+            Encoding::from_accept("application/json;q=0.5, application/cbor"),
+            Encoding::Cbor
+        );
+        assert_eq!(
+            Encoding::from_accept("application/cbor;q=0"),
+            Encoding::Json
+        );
+    }
+}
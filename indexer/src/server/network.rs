@@ -0,0 +1,174 @@
+use {
+    super::error::{ServerError, ServerResult},
+    crate::index::Chain,
+    bitcoin::{address::NetworkUnchecked, Address},
+    serde::Deserialize,
+    titan_types::NetworkMismatch,
+};
+
+/// Chains to tell an address's chain by, in order. Testnet addresses are also
+/// valid on testnet4 and signet, and legacy ones on regtest, so they're
+/// reported as testnet.
+const CHAINS: [Chain; 5] = [
+    Chain::Mainnet,
+    Chain::Testnet,
+    Chain::Testnet4,
+    Chain::Signet,
+    Chain::Regtest,
+];
+
+/// `?network=` of the address endpoints. Only accepted if it's the chain the
+/// server indexes.
+#[derive(Deserialize)]
+pub(super) struct NetworkQuery {
+    pub(super) network: Option<Chain>,
+}
+
+fn wrong_network(message: String, expected: Chain, parsed: Chain) -> ServerError {
+    ServerError::WrongNetwork {
+        message,
+        network: NetworkMismatch::new(expected.to_string(), parsed.to_string()),
+    }
+}
+
+/// Rejects queries for another network than `chain`.
+pub(super) fn check_network(requested: Option<Chain>, chain: Chain) -> ServerResult<()> {
+    match requested {
+        Some(requested) if requested != chain => Err(wrong_network(
+            format!("this server indexes {chain}, it can't answer {requested} queries"),
+            chain,
+            requested,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `?expect_chain=` of `/status`, for clients to check they're connected to
+/// the right network.
+#[derive(Deserialize)]
+pub(super) struct ExpectChainQuery {
+    pub(super) expect_chain: Option<Chain>,
+}
+
+/// Rejects with 409 if the client expects another chain than `chain`.
+pub(super) fn check_expected_chain(expected: Option<Chain>, chain: Chain) -> ServerResult<()> {
+    match expected {
+        Some(expected) if expected != chain => Err(ServerError::UnexpectedChain {
+            message: format!("this server indexes {chain}, not {expected}"),
+            network: NetworkMismatch::new(chain.to_string(), expected.to_string()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Like `Address::require_network`, but the error names both the network the
+/// server indexes and the one the address is for.
+pub(super) fn require_network(
+    address: Address<NetworkUnchecked>,
+    chain: Chain,
+) -> ServerResult<Address> {
+    if address.is_valid_for_network(chain.network()) {
+        return Ok(address.assume_checked());
+    }
+
+    match CHAINS
+        .into_iter()
+        .find(|parsed| address.is_valid_for_network(parsed.network()))
+    {
+        Some(parsed) => Err(wrong_network(
+            format!(
+                "address {} is a {parsed} address, but this server indexes {chain}",
+                address.assume_checked_ref()
+            ),
+            chain,
+            parsed,
+        )),
+        None => Err(ServerError::BadRequest(
+            address
+                .require_network(chain.network())
+                .unwrap_err()
+                .to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_the_address_network() {
+        let address: Address<NetworkUnchecked> = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+            .parse()
+            .unwrap();
+
+        assert!(require_network(address.clone(), Chain::Signet).is_ok());
+
+        match require_network(address, Chain::Mainnet) {
+            Err(ServerError::WrongNetwork { message, network }) => {
+                assert_eq!(
+                    message,
+                    "address tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx is a testnet address, \
+                     but this server indexes mainnet"
+                );
+                assert_eq!(network.expected, "mainnet");
+                assert_eq!(network.parsed, "testnet");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn handles_regtest_addresses() {
+        let parsed_network =
+            |address: &str, chain| match require_network(address.parse().unwrap(), chain) {
+                Err(ServerError::WrongNetwork { network, .. }) => network.parsed,
+                other => panic!("unexpected result: {other:?}"),
+            };
+
+        let bech32 = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+        let address = require_network(bech32.parse().unwrap(), Chain::Regtest).unwrap();
+        assert_eq!(address.to_string(), bech32);
+        // Outputs are indexed under the address `Chain` derives from the script.
+        assert_eq!(
+            Chain::Regtest
+                .address_from_script(&address.script_pubkey())
+                .unwrap(),
+            address
+        );
+        assert_eq!(
+            require_network(bech32.to_uppercase().parse().unwrap(), Chain::Regtest)
+                .unwrap()
+                .to_string(),
+            bech32
+        );
+
+        assert!(require_network(
+            "mfcGAzvis9JQAb6avB6WBGiGrgWzLxuGaC".parse().unwrap(),
+            Chain::Regtest
+        )
+        .is_ok());
+
+        assert_eq!(parsed_network(bech32, Chain::Mainnet), "regtest");
+        assert_eq!(parsed_network(bech32, Chain::Signet), "regtest");
+        assert_eq!(
+            parsed_network("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", Chain::Regtest),
+            "testnet"
+        );
+    }
+
+    #[test]
+    fn rejects_other_networks() {
+        assert!(check_network(None, Chain::Mainnet).is_ok());
+        assert!(check_network(Some(Chain::Mainnet), Chain::Mainnet).is_ok());
+        assert!(matches!(
+            check_network(Some(Chain::Testnet), Chain::Mainnet),
+            Err(ServerError::WrongNetwork { .. })
+        ));
+        assert!(check_expected_chain(Some(Chain::Regtest), Chain::Regtest).is_ok());
+        assert!(matches!(
+            check_expected_chain(Some(Chain::Signet), Chain::Regtest),
+            Err(ServerError::UnexpectedChain { .. })
+        ));
+    }
+}
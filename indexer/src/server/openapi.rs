@@ -0,0 +1,36 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+/// Hand-written OpenAPI 3 document describing the main routes and their
+/// `titan_types` responses. Keep it in sync when those types change.
+const OPENAPI_JSON: &str = include_str!("openapi.json");
+
+pub(super) async fn openapi() -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_JSON).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_is_valid_json() {
+        let document: serde_json::Value = serde_json::from_str(OPENAPI_JSON).unwrap();
+
+        assert_eq!(document["info"]["version"], env!("CARGO_PKG_VERSION"));
+
+        // Every reference points to a defined schema.
+        for reference in OPENAPI_JSON
+            .split("\"#/components/schemas/")
+            .skip(1)
+            .map(|rest| &rest[..rest.find('"').unwrap()])
+        {
+            assert!(
+                document["components"]["schemas"].get(reference).is_some(),
+                "missing schema {reference}"
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use {
+    super::{
+        error::{ServerError, ServerResult},
+        extract::Path,
+    },
+    axum::{
+        extract::FromRequestParts,
+        response::{IntoResponse, Response},
+    },
+    bitcoin::OutPoint,
+};
+
+/// `OutPoint` path segment. Unlike `Path<OutPoint>`, a malformed one is
+/// rejected with the usual JSON error.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct OutPointPath(pub OutPoint);
+
+fn parse_outpoint(outpoint: &str) -> ServerResult<OutPoint> {
+    outpoint.parse().map_err(|error| {
+        ServerError::BadRequest(format!(
+            "invalid outpoint `{outpoint}`: {error}, expected <txid>:<vout>"
+        ))
+    })
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for OutPointPath {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let Path(outpoint) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        parse_outpoint(&outpoint)
+            .map(OutPointPath)
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_outpoints_are_bad_requests() {
+        let txid = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+        assert_eq!(
+            parse_outpoint(&format!("{txid}:1")).unwrap(),
+            OutPoint {
+                txid: txid.parse().unwrap(),
+                vout: 1,
+            }
+        );
+
+        match parse_outpoint(txid) {
+            Err(ServerError::BadRequest(message)) => {
+                assert!(message.ends_with("expected <txid>:<vout>"), "{message}")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
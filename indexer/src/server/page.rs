@@ -0,0 +1,80 @@
+use {
+    super::{
+        error::{ServerError, ServerResult},
+        extract::Query,
+        ServerConfig,
+    },
+    axum::{
+        extract::{FromRef, FromRequestParts},
+        response::{IntoResponse, Response},
+    },
+    std::sync::Arc,
+    titan_types::Pagination,
+};
+
+/// `Pagination` query parameters whose limit has been checked against
+/// `--max-page-size`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Page(pub Pagination);
+
+/// Rejects a page larger than `max_page_size` instead of silently clamping it,
+/// so clients don't mistake a short page for the end of the results.
+pub(super) fn check_page_size(
+    pagination: Pagination,
+    max_page_size: u64,
+) -> ServerResult<Pagination> {
+    if pagination.limit > max_page_size {
+        return Err(ServerError::BadRequest(format!(
+            "limit {} exceeds the maximum page size of {max_page_size}",
+            pagination.limit
+        )));
+    }
+
+    Ok(pagination)
+}
+
+impl<S> FromRequestParts<S> for Page
+where
+    S: Send + Sync,
+    Arc<ServerConfig>: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(pagination) = Query::<Pagination>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let config = Arc::<ServerConfig>::from_ref(state);
+
+        check_page_size(pagination, config.max_page_size)
+            .map(Page)
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_size_over_limit_is_rejected() {
+        let pagination = Pagination {
+            skip: 0,
+            limit: 1000,
+        };
+        assert_eq!(check_page_size(pagination, 1000).unwrap().limit, 1000);
+
+        let pagination = Pagination {
+            skip: 0,
+            limit: 1001,
+        };
+        assert!(matches!(
+            check_page_size(pagination, 1000),
+            Err(ServerError::BadRequest(_))
+        ));
+    }
+}
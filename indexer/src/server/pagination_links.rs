@@ -0,0 +1,112 @@
+use {
+    http::{header, HeaderName, Uri},
+    titan_types::Pagination,
+};
+
+/// RFC 8288 `Link` header with `next` and `prev` pages for a paginated
+/// response with `items` entries. A full page is assumed to have a next page.
+/// Query parameters other than `skip` and `limit` are kept as they are.
+pub(super) fn link_header(
+    uri: &Uri,
+    pagination: Pagination,
+    items: usize,
+) -> Option<[(HeaderName, String); 1]> {
+    let Pagination { skip, limit } = pagination;
+
+    let mut links = Vec::new();
+
+    if limit > 0 && items as u64 >= limit {
+        links.push(link(uri, skip.saturating_add(limit), limit, "next"));
+    }
+
+    if skip > 0 {
+        links.push(link(uri, skip.saturating_sub(limit), limit, "prev"));
+    }
+
+    if links.is_empty() {
+        None
+    } else {
+        Some([(header::LINK, links.join(", "))])
+    }
+}
+
+/// `Link` header with the `next` page of a response paginated with
+/// `?cursor=`. There's no `prev`, cursors only go forward.
+pub(super) fn cursor_link_header(
+    uri: &Uri,
+    next_cursor: Option<&str>,
+) -> Option<[(HeaderName, String); 1]> {
+    let next_cursor = next_cursor?;
+    let mut params = params_except(uri, &["skip", "cursor"]);
+    params.push(format!("cursor={next_cursor}"));
+
+    Some([(
+        header::LINK,
+        format!("<{}?{}>; rel=\"next\"", uri.path(), params.join("&")),
+    )])
+}
+
+fn link(uri: &Uri, skip: u64, limit: u64, rel: &str) -> String {
+    let mut params = params_except(uri, &["skip", "limit"]);
+    params.push(format!("skip={skip}"));
+    params.push(format!("limit={limit}"));
+
+    format!("<{}?{}>; rel=\"{rel}\"", uri.path(), params.join("&"))
+}
+
+fn params_except(uri: &Uri, names: &[&str]) -> Vec<String> {
+    uri.query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !param.is_empty() && !names.contains(&name)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_and_prev_links() {
+        let uri: Uri = "/rune/840000:1/transactions?order=asc&skip=10&limit=10"
+            .parse()
+            .unwrap();
+
+        let [(_, value)] = link_header(
+            &uri,
+            Pagination {
+                skip: 10,
+                limit: 10,
+            },
+            10,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value,
+            "</rune/840000:1/transactions?order=asc&skip=20&limit=10>; rel=\"next\", \
+             </rune/840000:1/transactions?order=asc&skip=0&limit=10>; rel=\"prev\""
+        );
+    }
+
+    #[test]
+    fn no_links_for_single_page() {
+        let uri: Uri = "/runes".parse().unwrap();
+
+        assert!(link_header(&uri, Pagination { skip: 0, limit: 50 }, 3).is_none());
+    }
+
+    #[test]
+    fn next_link_with_cursor() {
+        let uri: Uri = "/runes?cursor=&limit=10".parse().unwrap();
+
+        let [(_, value)] = cursor_link_header(&uri, Some("AAAAAAAAAAAq")).unwrap();
+        assert_eq!(value, "</runes?limit=10&cursor=AAAAAAAAAAAq>; rel=\"next\"");
+
+        assert!(cursor_link_header(&uri, None).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use {
+    super::error::error_response,
+    axum::{
+        extract::{ConnectInfo, Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    http::{header, StatusCode},
+    std::{
+        collections::HashMap,
+        net::{IpAddr, SocketAddr},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    titan_types::ErrorCode,
+};
+
+/// Above this many tracked clients, idle buckets are dropped.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket rate limiter keyed by client IP.
+#[derive(Clone)]
+pub(super) struct RateLimiter {
+    per_sec: f64,
+    burst: f64,
+    buckets: Arc<Mutex<HashMap<IpAddr, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub(super) fn new(per_sec: u32, burst: u32) -> Self {
+        Self {
+            per_sec: per_sec.max(1) as f64,
+            burst: burst.max(1) as f64,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Takes a token for `ip`, or returns how long to wait for the next one.
+    fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            let (per_sec, burst) = (self.per_sec, self.burst);
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens + elapsed * per_sec < burst
+            });
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_sec).min(self.burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_sec,
+            ))
+        }
+    }
+}
+
+pub(super) async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match limiter.check(addr.ip(), Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    ErrorCode::RateLimited,
+                    "rate limit exceeded",
+                ),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_allows_burst_then_limits() {
+        let limiter = RateLimiter::new(1, 2);
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let other_ip: IpAddr = "127.0.0.2".parse().unwrap();
+        let now = Instant::now();
+
+        assert!(limiter.check(ip, now).is_ok());
+        assert!(limiter.check(ip, now).is_ok());
+        assert!(limiter.check(ip, now).is_err());
+        assert!(limiter.check(other_ip, now).is_ok());
+        assert!(limiter.check(ip, now + Duration::from_secs(1)).is_ok());
+    }
+}
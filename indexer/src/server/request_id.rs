@@ -0,0 +1,80 @@
+use {
+    axum::{
+        extract::{MatchedPath, Request},
+        middleware::Next,
+        response::Response,
+    },
+    http::{HeaderName, HeaderValue},
+    std::time::Instant,
+    tracing::{debug, field, info_span, Instrument},
+    uuid::Uuid,
+};
+
+pub(super) const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest incoming `X-Request-Id` that's kept. Longer ones are replaced so
+/// clients can't put arbitrary amounts of text in the logs.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Runs the request in a `request` span carrying its id, method, route and
+/// path, and once answered its status and duration. The id is the request's
+/// `X-Request-Id` if it has a usable one, or a new UUID, and is sent back in
+/// the response's `X-Request-Id`.
+pub(super) async fn trace_request(
+    matched_path: Option<MatchedPath>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .filter(|id| is_usable(id))
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .expect("uuid is a valid header value")
+        });
+    request.headers_mut().insert(REQUEST_ID, request_id.clone());
+
+    let span = info_span!(
+        "request",
+        id = request_id.to_str().unwrap_or_default(),
+        method = %request.method(),
+        route = matched_path.as_ref().map(MatchedPath::as_str).unwrap_or("unmatched"),
+        path = request.uri().path(),
+        status = field::Empty,
+        duration_ms = field::Empty,
+    );
+
+    let start = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+
+    span.record("status", response.status().as_u16());
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+    span.in_scope(|| debug!("request finished"));
+
+    response.headers_mut().insert(REQUEST_ID, request_id);
+    response
+}
+
+/// Visible ASCII only, so the id logs and echoes as is.
+fn is_usable(id: &HeaderValue) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.as_bytes().iter().all(u8::is_ascii_graphic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_short_printable_ids() {
+        assert!(is_usable(&HeaderValue::from_static("req-42")));
+        assert!(!is_usable(&HeaderValue::from_static("")));
+        assert!(!is_usable(&HeaderValue::from_static("two words")));
+        assert!(!is_usable(
+            &HeaderValue::from_str(&"a".repeat(MAX_REQUEST_ID_LEN + 1)).unwrap()
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+use {
+    super::error::ServerResult,
+    crate::bitcoin_rpc::{PooledClient, RpcClientPool},
+    std::{thread, time::Duration},
+    tracing::warn,
+};
+
+/// Retries RPC reads that failed because the node was briefly unreachable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RpcRetry {
+    /// Retries after the first attempt.
+    pub(crate) attempts: u32,
+    /// Wait before the first retry, doubled for each following one.
+    pub(crate) backoff: Duration,
+}
+
+impl RpcRetry {
+    /// Runs `call` with a client from `pool`. While it fails with a transient
+    /// RPC error, retries it with a newly created client. Only use it for
+    /// idempotent calls, and from a blocking thread since it sleeps.
+    pub(super) fn run<T>(
+        &self,
+        pool: &RpcClientPool,
+        mut call: impl FnMut(PooledClient) -> ServerResult<T>,
+    ) -> ServerResult<T> {
+        let mut result = call(pool.get()?);
+
+        for attempt in 1..=self.attempts {
+            match &result {
+                Err(err) if err.is_transient_rpc_error() => {
+                    let backoff = self.backoff * 2u32.saturating_pow(attempt - 1);
+                    warn!(
+                        "Transient RPC error, retrying in {backoff:?} ({attempt}/{}): {err}",
+                        self.attempts
+                    );
+                    thread::sleep(backoff);
+                    result = call(pool.get_new()?);
+                }
+                _ => break,
+            }
+        }
+
+        result
+    }
+}
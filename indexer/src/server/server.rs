@@ -1,31 +1,80 @@
 use {
     super::{
+        address_path::{AddressPath, ScriptPubkeyPath},
+        auth::{self, ApiKeys},
+        batch,
+        blocking::BlockingPool,
+        compression::{self, compression_layer, SkipCompression},
+        content_dictionary::ContentDictionary,
         deserialize_from_str::DeserializeFromStr,
-        error::{OptionExt, ServerError, ServerResult},
+        error::{
+            block_query_height, error_response, NotIndexedExt, OptionExt, ServerError, ServerResult,
+        },
+        extract::{Json, Path, Query},
+        fee_cache::FeeCache,
+        fields, head, meta,
+        metrics::{self, ServerMetrics},
+        negotiated::{Negotiated, ResponseFormat},
+        network::{self, ExpectChainQuery},
+        outpoint_path::OutPointPath,
+        page::{check_page_size, Page},
+        pagination_links,
+        rate_limit::{self, RateLimiter},
+        request_id::{self, REQUEST_ID},
+        syncing::{self, SyncGuard},
+        timeout,
+        tx_cache::TxCache,
         ServerConfig,
     },
     crate::{
-        api::{self, content::AcceptEncoding},
-        bitcoin_rpc::{RpcClientPool, RpcClientProvider},
-        index::Index,
-        subscription::WebhookSubscriptionManager,
+        api::{
+            self,
+            content::{byte_range, etag_matches, AcceptEncoding, ByteRange, ContentError},
+            cursor::{Cursor, InvalidCursor},
+            ApiError,
+        },
+        bitcoin_rpc::{is_transient_rpc_error, RpcClientPool},
+        index::{Chain, Index},
+        subscription::{self, TcpSubscriptionManager, WebhookSubscriptionManager},
     },
     axum::{
-        body::Bytes,
-        extract::{DefaultBodyLimit, Extension, FromRef, Json, Path, Query},
-        response::IntoResponse,
+        body::{Body, Bytes},
+        extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Extension, FromRef},
+        middleware,
+        response::{
+            sse::{KeepAlive, Sse},
+            IntoResponse, Response,
+        },
         routing::{get, post},
         Router,
     },
     axum_server::Handle,
-    bitcoin::{address::NetworkUnchecked, Address, OutPoint, Txid},
-    http::{header, StatusCode},
-    std::{io, net::ToSocketAddrs, sync::Arc},
-    titan_types::{query, InscriptionId, Pagination, Subscription},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    bitcoin::{address::NetworkUnchecked, consensus, Address, OutPoint, Txid, Wtxid},
+    futures::{stream, StreamExt},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
+    ordinals::{Rune, RuneId, Sat},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::HashSet,
+        io,
+        net::{SocketAddr, ToSocketAddrs},
+        path::PathBuf,
+        str::FromStr,
+        sync::Arc,
+        time::Duration,
+    },
+    titan_types::{
+        query, AddressUtxosQuery, CursorQuery, ErrorCode, EventType, EventsQuery, InscriptionId,
+        InscriptionsQuery, Pagination, ReindexRange, RuneChartQuery, RuneSearchQuery,
+        RuneTransactionsQuery, RunesQuery, SpentStatus, Subscription, SubscriptionBatchResult,
+        TransactionQuery, TxOutResponse,
+    },
     tokio::task,
     tower_http::{
-        compression::CompressionLayer,
-        cors::{Any, CorsLayer},
+        cors::{AllowOrigin, CorsLayer},
+        decompression::RequestDecompressionLayer,
+        limit::RequestBodyLimitLayer,
     },
     tracing::{error, info},
     uuid::Uuid,
@@ -39,10 +88,63 @@ pub enum SpawnError {
     SocketAddrError(#[from] std::net::AddrParseError),
     #[error("no socket address found")]
     NoSocketAddr,
+    #[error("failed to bind to any address: {}", format_bind_errors(.0))]
+    NoListener(Vec<(SocketAddr, std::io::Error)>),
+    #[error("invalid CORS origin `{0}`, expected <scheme>://<host>[:<port>]")]
+    InvalidCorsOrigin(String),
+    #[error("failed to read inscription content dictionary {}: {}", .0.display(), .1)]
+    ContentDictionary(PathBuf, std::io::Error),
+    #[error("admin routes need API keys, and can't be exempt from them")]
+    AdminWithoutApiKeys,
+}
+
+fn format_bind_errors(errors: &[(SocketAddr, std::io::Error)]) -> String {
+    errors
+        .iter()
+        .map(|(addr, error)| format!("{addr}: {error}"))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
+/// Maximum size of a request body after decompression.
+const MAX_REQUEST_BODY_SIZE: usize = 32 * 1024 * 1024;
+
+/// Runes read from the index at a time by `/runes/export`.
+const RUNES_EXPORT_CHUNK: u64 = 1000;
+
+/// Header that makes a retried `POST /subscription` return the first result.
+const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Txids serialized at a time by `/mempool/txids/export`.
+const MEMPOOL_EXPORT_CHUNK: usize = 1000;
+
+/// Request header naming the dictionary a browser has for `dcz` responses.
+const AVAILABLE_DICTIONARY: HeaderName = HeaderName::from_static("available-dictionary");
+
+/// Cursor to pass as `since_seq` to only get the mempool txids indexed since.
+const MEMPOOL_SEQ: HeaderName = HeaderName::from_static("x-mempool-seq");
+
 type SpawnResult<T> = std::result::Result<T, SpawnError>;
 
+#[derive(Deserialize)]
+struct MempoolTxidsQuery {
+    address: Option<Address<NetworkUnchecked>>,
+    network: Option<Chain>,
+    rune: Option<DeserializeFromStr<query::Rune>>,
+    since_seq: Option<u64>,
+    // Not `Page`, since the txids aren't paginated unless asked to.
+    skip: Option<u64>,
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SseQuery {
+    /// Comma-separated event types, e.g. `NewBlock,RuneMinted`.
+    subscribe: String,
+}
+
 pub struct Server;
 
 impl Server {
@@ -50,39 +152,87 @@ impl Server {
         &self,
         index: Arc<Index>,
         webhook_subscription_manager: Arc<WebhookSubscriptionManager>,
+        tcp_subscription_manager: Arc<TcpSubscriptionManager>,
         bitcoin_rpc_pool: RpcClientPool,
         config: Arc<ServerConfig>,
         handle: Handle,
     ) -> SpawnResult<task::JoinHandle<io::Result<()>>> {
-        let router = Router::new()
+        let mut router = Router::new()
             // Status
             .route("/status", get(Self::status))
+            .route("/healthz", get(Self::healthz))
+            .route("/readyz", get(Self::readyz))
             // Blocks
             .route("/tip", get(Self::tip))
             .route("/block/{query}", get(Self::block))
             .route("/block/{height}/hash", get(Self::block_hash_by_height))
             .route("/block/{query}/txids", get(Self::block_txids))
+            .route("/block/{query}/runes", get(Self::block_runes))
+            // Sats
+            .route("/sat/{sat}", get(Self::sat))
+            .route("/sat/{sat}/inscriptions", get(Self::sat_inscriptions))
             // Addresses
             .route("/address/{address}", get(Self::address))
+            .route("/address/{address}/utxos", get(Self::address_utxos))
+            .route("/address/{address}/balance", get(Self::address_balance))
+            .route("/address/{address}/runes", get(Self::address_runes))
+            .route("/script/{script}/outputs", get(Self::script_outputs))
             // Transactions
             .route("/tx/broadcast", post(Self::broadcast_transaction))
+            .route("/tx/decode", post(Self::decode_transaction))
+            .route("/tx/test-accept", post(Self::test_accept_transaction))
             .route("/tx/{txid}", get(Self::transaction))
+            .route("/tx/wtxid/{wtxid}", get(Self::transaction_by_wtxid))
             .route("/tx/{txid}/raw", get(Self::transaction_raw))
             .route("/tx/{txid}/hex", get(Self::transaction_hex))
             .route("/tx/{txid}/status", get(Self::transaction_status))
+            .route("/tx/{txid}/runestone", get(Self::transaction_runestone))
+            .route("/tx/{txid}/data", get(Self::transaction_data))
+            .route("/txs", post(Self::transactions))
+            // Batch
+            .route("/batch", post(Self::batch))
             .route("/output/{outpoint}", get(Self::output))
+            .route(
+                "/output/{outpoint}/inscriptions",
+                get(Self::output_inscriptions),
+            )
+            .route("/outputs", post(Self::outputs))
             // Inscriptions
+            .route("/inscriptions", get(Self::inscriptions))
+            .route("/inscriptions/dictionary", get(Self::content_dictionary))
             .route("/inscription/{inscription_id}", get(Self::inscription))
+            .route(
+                "/inscription/{inscription_id}/metadata",
+                get(Self::inscription_metadata),
+            )
+            .route(
+                "/inscription/{inscription_id}/parents",
+                get(Self::inscription_parents),
+            )
+            .route(
+                "/inscription/{inscription_id}/children",
+                get(Self::inscription_children),
+            )
             // Runes
             .route("/runes", get(Self::runes))
+            .route("/runes/search", get(Self::search_runes))
+            .route("/runes/export", get(Self::runes_export))
             .route("/rune/{rune}", get(Self::rune))
+            .route("/rune/id/{rune_id}", get(Self::rune_by_id))
             .route("/rune/{rune}/transactions", get(Self::rune_transactions))
+            .route("/rune/{rune}/holders", get(Self::rune_holders))
+            .route("/rune/{rune}/mints", get(Self::rune_mints))
+            .route("/rune/{rune}/etching", get(Self::rune_etching))
+            .route("/rune/{rune}/chart", get(Self::rune_chart))
             // Mempool
             .route("/mempool/txids", get(Self::mempool_txids))
+            .route("/mempool/txids/export", get(Self::mempool_txids_export))
             // Mempool entries
             .route("/mempool/entry/{txid}", get(Self::mempool_tx))
             .route("/mempool/entries", post(Self::mempool_entries))
             .route("/mempool/entries/all", get(Self::mempool_all_entries))
+            // Fees
+            .route("/fees", get(Self::fees))
             // Subscriptions
             .route(
                 "/subscription/{id}",
@@ -90,310 +240,3153 @@ impl Server {
             )
             .route("/subscription", post(Self::add_subscription))
             .route("/subscriptions", get(Self::subscriptions))
+            .route("/subscriptions/batch", post(Self::add_subscriptions_batch))
+            .route("/ws", get(Self::ws_subscription))
+            .route("/sse", get(Self::sse_subscription))
+            .route("/tip/sse", get(Self::tip_sse))
+            .route("/events", get(Self::events));
+
+        #[cfg(feature = "openapi")]
+        {
+            router = router.route("/openapi.json", get(super::openapi::openapi));
+        }
+
+        // Before the layers so these responses go through them too.
+        router = router.fallback(Self::route_not_found);
+
+        router = router.layer(middleware::from_fn(head::strip_head_body));
+
+        if config.request_timeout_secs > 0 {
+            router = router.layer(middleware::from_fn_with_state(
+                Duration::from_secs(config.request_timeout_secs),
+                timeout::request_timeout,
+            ));
+        }
+
+        router = router
+            .layer(middleware::from_fn(fields::select_fields))
+            .layer(middleware::from_fn_with_state(
+                index.clone(),
+                meta::wrap_with_meta,
+            ));
+
+        if let Some(max_behind_blocks) = config.max_behind_blocks {
+            router = router.layer(middleware::from_fn_with_state(
+                SyncGuard {
+                    index: index.clone(),
+                    max_behind_blocks,
+                },
+                syncing::reject_while_syncing,
+            ));
+        }
+
+        if config.enable_debug {
+            router = router.route("/debug/index-stats", get(Self::index_stats));
+        }
+
+        if config.enable_admin {
+            if config.api_keys.is_none()
+                || config
+                    .api_key_exempt_paths
+                    .iter()
+                    .any(|path| path.starts_with("/admin/"))
+            {
+                return Err(SpawnError::AdminWithoutApiKeys);
+            }
+            router = router.route("/admin/reindex", post(Self::reindex));
+        }
+
+        if config.enable_metrics {
+            let metrics = Arc::new(ServerMetrics::new(config.max_subscriptions));
+            router = router
+                .route("/metrics", get(Self::metrics))
+                .layer(middleware::from_fn_with_state(
+                    metrics.clone(),
+                    metrics::track_metrics,
+                ))
+                .layer(Extension(metrics));
+        }
+
+        // Only applies to the routes that already exist, so after the last.
+        router = router.method_not_allowed_fallback(Self::method_not_allowed);
+
+        // Inside the CORS layer so preflight requests don't need a key.
+        if let Some(api_keys) = config.api_keys.clone() {
+            info!(
+                "Requiring an API key on all routes except {:?}",
+                config.api_key_exempt_paths
+            );
+            router = router.layer(middleware::from_fn_with_state(
+                ApiKeys::new(api_keys, config.api_key_exempt_paths.clone()),
+                auth::require_api_key,
+            ));
+        }
+
+        let content_dictionary = match &config.inscription_content_dictionary {
+            Some(path) => {
+                let dictionary = ContentDictionary::load(path, config.compression_level)
+                    .map_err(|err| SpawnError::ContentDictionary(path.clone(), err))?;
+                info!(
+                    "Compressing inscription content with dictionary {} ({} bytes)",
+                    path.display(),
+                    dictionary.bytes().len()
+                );
+                Some(Arc::new(dictionary))
+            }
+            None => None,
+        };
+
+        let router = router
             .layer(Extension(index))
             .layer(Extension(webhook_subscription_manager))
+            .layer(Extension(tcp_subscription_manager))
             .layer(Extension(config.clone()))
             .layer(Extension(bitcoin_rpc_pool))
+            .layer(Extension(BlockingPool::new(config.blocking_threads)))
+            .layer(Extension(Arc::new(TxCache::new(
+                config.tx_cache_entries,
+                config.tx_cache_min_confirmations,
+            ))))
+            .layer(Extension(Arc::new(FeeCache::new(config.fee_estimates_ttl))))
+            .layer(Extension(content_dictionary))
             .layer(
                 CorsLayer::new()
-                    .allow_methods([http::Method::GET])
-                    .allow_origin(Any),
+                    .allow_methods([http::Method::GET, http::Method::HEAD])
+                    .allow_headers([header::AUTHORIZATION])
+                    .expose_headers([header::LINK, header::ETAG, MEMPOOL_SEQ, REQUEST_ID])
+                    .allow_origin(Self::cors_allow_origin(&config)?),
             )
             .layer(DefaultBodyLimit::disable())
-            .layer(CompressionLayer::new())
+            // The limit sits inside the decompression layer so it applies to
+            // the decompressed body, not to what went over the wire.
+            .layer(RequestBodyLimitLayer::new(MAX_REQUEST_BODY_SIZE))
+            .layer(RequestDecompressionLayer::new())
+            .layer(compression_layer(&config))
             .with_state(config.clone());
 
+        let router = match config.rate_limit_per_sec {
+            Some(per_sec) => {
+                let burst = config.rate_limit_burst.unwrap_or(per_sec);
+                info!("Rate limiting requests to {per_sec}/s per client IP (burst {burst})");
+                router.layer(middleware::from_fn_with_state(
+                    RateLimiter::new(per_sec, burst),
+                    rate_limit::rate_limit,
+                ))
+            }
+            None => router,
+        };
+
+        // Outermost, so rejected and rate-limited requests get an id too.
+        let router = router.layer(middleware::from_fn(request_id::trace_request));
+
         let jh = self.spawn(&config, router, handle)?;
 
         Ok(jh)
     }
 
+    /// Closes every TCP and WebSocket subscription, then stops accepting
+    /// connections and waits up to `timeout` for in-flight requests to finish.
+    pub async fn shutdown(
+        &self,
+        handle: &Handle,
+        tcp_subscription_manager: &TcpSubscriptionManager,
+        timeout: Duration,
+    ) {
+        tcp_subscription_manager.close_all().await;
+        handle.graceful_shutdown(Some(timeout));
+    }
+
+    fn cors_allow_origin(config: &ServerConfig) -> SpawnResult<AllowOrigin> {
+        let Some(origins) = &config.cors_allow_origins else {
+            return Ok(AllowOrigin::any());
+        };
+
+        let origins = origins
+            .iter()
+            .map(|origin| {
+                let invalid = || SpawnError::InvalidCorsOrigin(origin.clone());
+                let uri: Uri = origin.parse().map_err(|_| invalid())?;
+                if uri.scheme().is_none()
+                    || uri.authority().is_none()
+                    || !matches!(uri.path(), "" | "/")
+                    || uri.query().is_some()
+                {
+                    return Err(invalid());
+                }
+
+                HeaderValue::from_str(origin.trim_end_matches('/')).map_err(|_| invalid())
+            })
+            .collect::<SpawnResult<Vec<HeaderValue>>>()?;
+
+        Ok(AllowOrigin::list(origins))
+    }
+
     fn spawn(
         &self,
         config: &ServerConfig,
         router: Router,
         handle: Handle,
     ) -> SpawnResult<task::JoinHandle<io::Result<()>>> {
-        let addr = config
-            .http_listen
-            .to_socket_addrs()?
-            .next()
-            .ok_or(SpawnError::NoSocketAddr)?;
+        let mut addrs = Vec::new();
+        for listen in &config.http_listen {
+            for addr in listen.to_socket_addrs()? {
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+        }
+        if addrs.is_empty() {
+            return Err(SpawnError::NoSocketAddr);
+        }
+
+        // Bound here so failures surface before serving. Some addresses may
+        // fail, e.g. `0.0.0.0` after a dual-stack `[::]` took the port.
+        let mut listeners = Vec::new();
+        let mut bind_errors = Vec::new();
+        for addr in addrs {
+            match std::net::TcpListener::bind(addr).and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(listener)
+            }) {
+                Ok(listener) => {
+                    info!("Listening on http://{addr}");
+                    listeners.push(listener);
+                }
+                Err(e) => {
+                    error!("Failed to bind to {addr}: {e}");
+                    bind_errors.push((addr, e));
+                }
+            }
+        }
+        if listeners.is_empty() {
+            return Err(SpawnError::NoListener(bind_errors));
+        }
 
-        info!("Listening on http://{addr}");
+        let servers: Vec<_> = listeners
+            .into_iter()
+            .map(|listener| {
+                let router = router.clone();
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    axum_server::from_tcp(listener)
+                        .handle(handle)
+                        .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+                        .await
+                })
+            })
+            .collect();
 
+        // Finishes when every server has, with the first error.
         Ok(tokio::spawn(async move {
-            axum_server::Server::bind(addr)
-                .handle(handle)
-                .serve(router.into_make_service())
-                .await
+            let mut result = Ok(());
+            for server in servers {
+                let server_result = server.await.unwrap_or_else(|e| Err(io::Error::other(e)));
+                if let (Ok(()), Err(e)) = (&result, server_result) {
+                    result = Err(e);
+                }
+            }
+            result
         }))
     }
 
-    async fn tip(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::tip(index)?).into_response()))
+    async fn tip(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        request_headers: HeaderMap,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let tip = api::tip(index)?;
+                let etag = format!("\"{}\"", tip.hash);
+                Ok(Self::revalidated_json(&request_headers, &etag, tip))
+            })
+            .await?
+    }
+
+    async fn metrics(
+        Extension(metrics): Extension<Arc<ServerMetrics>>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(webhook_subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(tcp_subscription_manager): Extension<Arc<TcpSubscriptionManager>>,
+    ) -> impl IntoResponse {
+        metrics
+            .render(
+                &index,
+                &webhook_subscription_manager,
+                &tcp_subscription_manager,
+            )
+            .await
+    }
+
+    async fn healthz() -> StatusCode {
+        StatusCode::OK
+    }
+
+    async fn readyz(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+    ) -> impl IntoResponse {
+        // Only cheap reads here: the indexed block count and the node block
+        // count cached by the updater on its last sync.
+        let lag = match (index.get_block_count(), index.get_node_block_count()) {
+            (Ok(indexed), Some(node)) => node.saturating_sub(indexed),
+            _ => {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "index not ready".to_string(),
+                )
+            }
+        };
+
+        if lag > config.ready_lag_blocks {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("index is {lag} blocks behind the node"),
+            )
+        } else {
+            (StatusCode::OK, "ok".to_string())
+        }
+    }
+
+    async fn status(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Query(ExpectChainQuery { expect_chain }): Query<ExpectChainQuery>,
+        request_headers: HeaderMap,
+    ) -> ServerResult {
+        network::check_expected_chain(expect_chain, config.chain)?;
+
+        blocking
+            .run(move || {
+                let status = api::status(index, config.chain)?;
+                // The mempool count changes between blocks, so it is part of the tag.
+                let etag = format!(
+                    "\"{}-{}-{}\"",
+                    status.block_tip.hash, status.runes_count, status.mempool_tx_count
+                );
+                Ok(Self::revalidated_json(&request_headers, &etag, status))
+            })
+            .await?
+    }
+
+    async fn route_not_found(uri: Uri) -> ServerError {
+        ServerError::NotFound(format!("route {} not found", uri.path()))
+    }
+
+    async fn method_not_allowed(method: http::Method, uri: Uri) -> Response {
+        error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::MethodNotAllowed,
+            format!("method {method} not allowed on {}", uri.path()),
+        )
+    }
+
+    /// Serves `value` as JSON with an `ETag`, or an empty 304 without
+    /// serializing it when the request's `If-None-Match` already matches.
+    fn revalidated_json<T: Serialize>(
+        request_headers: &HeaderMap,
+        etag: &str,
+        value: T,
+    ) -> Response {
+        let Ok(etag) = HeaderValue::from_str(etag) else {
+            return Json(value).into_response();
+        };
+
+        let headers = [
+            (header::ETAG, etag.clone()),
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-cache")),
+        ];
+
+        if request_headers
+            .get(header::IF_NONE_MATCH)
+            .is_some_and(|if_none_match| etag_matches(if_none_match, &etag))
+        {
+            return (StatusCode::NOT_MODIFIED, headers).into_response();
+        }
+
+        (headers, Json(value)).into_response()
+    }
+
+    async fn sat(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<u64>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        let sat = Self::sat_in_range(sat)?;
+
+        blocking
+            .run(move || Ok(Negotiated(format, api::sat(index, sat)?).into_response()))
+            .await?
+    }
+
+    async fn sat_inscriptions(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<u64>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        let sat = Self::sat_in_range(sat)?;
+
+        if !config.index_sats {
+            return Err(ServerError::BadRequest(
+                "sats are not indexed. Enable --index-sats to index sats".to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || Ok(Negotiated(format, api::sat_inscriptions(index, sat)?).into_response()))
+            .await?
     }
 
-    async fn status(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::status(index)?).into_response()))
+    fn sat_in_range(sat: u64) -> Result<Sat, ServerError> {
+        if sat > Sat::LAST.n() {
+            return Err(ServerError::BadRequest(format!(
+                "sat {sat} is out of range, the last sat is {}",
+                Sat::LAST.n()
+            )));
+        }
+
+        Ok(Sat(sat))
     }
 
     async fn block(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+        format: ResponseFormat,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block(index, &query)?).into_response()))
+        blocking
+            .run(move || {
+                let block = api::block(index.clone(), &query)
+                    .or_not_indexed_yet(&index, block_query_height(&query))?;
+                Ok(Negotiated(format, block).into_response())
+            })
+            .await?
     }
 
     async fn block_hash_by_height(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(height)): Path<DeserializeFromStr<u64>>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block_hash_by_height(index, height)?).into_response()))
+        blocking
+            .run(move || {
+                let hash = api::block_hash_by_height(index.clone(), height)
+                    .or_not_indexed_yet(&index, Some(height))?;
+                Ok(Json(hash).into_response())
+            })
+            .await?
     }
 
     async fn block_txids(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let txids = api::block_txids(index.clone(), &query)
+                    .or_not_indexed_yet(&index, block_query_height(&query))?;
+                Ok(Json(txids).into_response())
+            })
+            .await?
+    }
+
+    async fn index_stats(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Json(api::index_stats(index)?).into_response()))
+            .await?
+    }
+
+    async fn reindex(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Json(range): Json<ReindexRange>,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                api::reindex(index, range)?;
+                Ok((StatusCode::ACCEPTED, Json(range)).into_response())
+            })
+            .await?
+    }
+
+    async fn block_runes(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::block_txids(index, &query)?).into_response()))
+        blocking
+            .run(move || {
+                let runes = api::block_runes(index.clone(), &query)
+                    .or_not_indexed_yet(&index, block_query_height(&query))?;
+                Ok(Json(runes).into_response())
+            })
+            .await?
     }
 
     async fn broadcast_transaction(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
         hex: String,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            let txid = api::broadcast_transaction(index, bitcoin_rpc_pool.get()?, &hex)?;
+        blocking
+            .run(move || {
+                let txid = api::broadcast_transaction(index, bitcoin_rpc_pool.get()?, &hex)?;
 
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "text/plain")],
-                txid.to_string(),
-            )
-                .into_response())
-        })
+                Ok((
+                    StatusCode::OK,
+                    [(header::CONTENT_TYPE, "text/plain")],
+                    txid.to_string(),
+                )
+                    .into_response())
+            })
+            .await?
     }
 
-    async fn transaction(
+    /// Takes a hex or base64 encoded raw transaction.
+    async fn decode_transaction(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        raw: String,
+    ) -> ServerResult {
+        let raw = raw.trim();
+        let bytes = if raw.chars().all(|c| c.is_ascii_hexdigit()) {
+            hex::decode(raw).map_err(|err| ServerError::BadRequest(err.to_string()))?
+        } else {
+            BASE64_STANDARD
+                .decode(raw)
+                .map_err(|err| ServerError::BadRequest(err.to_string()))?
+        };
+
+        let transaction: bitcoin::Transaction = consensus::deserialize(&bytes)
+            .map_err(|err| ServerError::BadRequest(format!("invalid transaction: {err}")))?;
+
+        blocking
+            .run(move || Ok(Json(api::decode_transaction(index, transaction)?).into_response()))
+            .await?
+    }
+
+    async fn fees(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        Path(txid): Path<Txid>,
+        Extension(fee_cache): Extension<Arc<FeeCache>>,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            let transaction = api::transaction(index, bitcoin_rpc_pool.get()?, &txid)?;
-            Ok(Json(transaction).into_response())
-        })
+        blocking
+            .run(move || {
+                let fees = fee_cache.get(|| {
+                    let unavailable = |err: String| {
+                        ServerError::BadGateway(format!(
+                            "couldn't get fee estimates from the node: {err}"
+                        ))
+                    };
+                    let client = bitcoin_rpc_pool
+                        .get()
+                        .map_err(|err| unavailable(err.to_string()))?;
+                    match api::fee_estimates(index, &client) {
+                        Err(ApiError::RpcError(err)) => Err(unavailable(err.to_string())),
+                        result => Ok(result?),
+                    }
+                })?;
+                Ok(Json(fees).into_response())
+            })
+            .await?
     }
 
-    async fn transaction_raw(
+    /// Takes a hex encoded raw transaction, which isn't broadcast.
+    async fn test_accept_transaction(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
-        Path(txid): Path<Txid>,
+        hex: String,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            let raw_tx = api::bitcoin_transaction_raw(index, bitcoin_rpc_pool.get()?, &txid)?;
+        let bytes =
+            hex::decode(hex.trim()).map_err(|err| ServerError::BadRequest(err.to_string()))?;
+        let transaction: bitcoin::Transaction = consensus::deserialize(&bytes)
+            .map_err(|err| ServerError::BadRequest(format!("invalid transaction: {err}")))?;
 
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "application/octet-stream")],
-                Bytes::from(raw_tx),
-            )
-                .into_response())
-        })
+        blocking
+            .run(move || {
+                let client = bitcoin_rpc_pool
+                    .get()
+                    .map_err(|err| ServerError::BadGateway(err.to_string()))?;
+                let response = match api::test_accept_transaction(index, client, transaction) {
+                    // The node answered, rejecting the transaction, e.g. as
+                    // undecodable. That's a 400 like any API `RpcError`.
+                    Err(ApiError::RpcError(
+                        err @ bitcoincore_rpc::Error::JsonRpc(
+                            bitcoincore_rpc::jsonrpc::error::Error::Rpc(_),
+                        ),
+                    )) if !is_transient_rpc_error(&err) => {
+                        return Err(ApiError::RpcError(err).into())
+                    }
+                    Err(ApiError::RpcError(err)) => {
+                        return Err(ServerError::BadGateway(err.to_string()))
+                    }
+                    result => result?,
+                };
+                Ok(Json(response).into_response())
+            })
+            .await?
     }
 
-    async fn transaction_hex(
+    async fn transaction(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Extension(tx_cache): Extension<Arc<TxCache>>,
         Path(txid): Path<Txid>,
+        Query(TransactionQuery { prevouts }): Query<TransactionQuery>,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            let hex_string = api::bitcoin_transaction_hex(index, bitcoin_rpc_pool.get()?, &txid)?;
+        blocking
+            .run(move || {
+                if prevouts {
+                    let transaction = config
+                        .rpc_retry
+                        .run(&bitcoin_rpc_pool, |client| {
+                            Ok(api::transaction_with_prevouts(
+                                &index,
+                                &client,
+                                &txid,
+                                config.chain.network(),
+                            )?)
+                        })
+                        .or_not_indexed_yet(&index, None)?;
+                    return Ok(Json(transaction).into_response());
+                }
 
-            Ok((
-                StatusCode::OK,
-                [(header::CONTENT_TYPE, "text/plain")],
-                hex_string,
-            )
-                .into_response())
-        })
+                let transaction = tx_cache
+                    .transaction(&index, &txid, || {
+                        config.rpc_retry.run(&bitcoin_rpc_pool, |client| {
+                            Ok(api::transaction(index.clone(), client, &txid)?)
+                        })
+                    })
+                    .or_not_indexed_yet(&index, None)?;
+                Ok(Json(transaction).into_response())
+            })
+            .await?
     }
 
-    async fn transaction_status(
+    async fn transaction_runestone(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
         Path(txid): Path<Txid>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::transaction_status(index, &txid)?).into_response()))
+        blocking
+            .run(move || {
+                let runestone = config
+                    .rpc_retry
+                    .run(&bitcoin_rpc_pool, |client| {
+                        Ok(api::transaction_runestone(index.clone(), client, &txid)?)
+                    })
+                    .or_transaction_not_indexed_yet(&index)?;
+                Ok(Json(runestone).into_response())
+            })
+            .await?
     }
 
-    async fn output(
+    async fn transaction_data(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Path(outpoint): Path<OutPoint>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Path(txid): Path<Txid>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::output(index, &outpoint)?).into_response()))
+        blocking
+            .run(move || {
+                let data = config
+                    .rpc_retry
+                    .run(&bitcoin_rpc_pool, |client| {
+                        Ok(api::transaction_data(index.clone(), client, &txid)?)
+                    })
+                    .or_transaction_not_indexed_yet(&index)?;
+                Ok(Json(data).into_response())
+            })
+            .await?
     }
 
-    async fn runes(
+    async fn transaction_by_wtxid(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Query(pagination): Query<Pagination>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Path(wtxid): Path<Wtxid>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::runes(index, pagination)?).into_response()))
+        if !index.is_indexing_bitcoin_transactions() {
+            return Err(ServerError::BadRequest(
+                "transactions are not indexed. Enable --index-bitcoin-transactions to look up transactions by wtxid"
+                    .to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || {
+                let transaction =
+                    api::transaction_by_wtxid(index.clone(), bitcoin_rpc_pool.get()?, &wtxid)
+                        .or_not_indexed_yet(&index, None)?;
+                Ok(Json(transaction).into_response())
+            })
+            .await?
     }
 
-    async fn rune(
+    async fn transactions(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        format: ResponseFormat,
+        Json(txids): Json<Vec<Txid>>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::rune(index, &rune)?).into_response()))
+        if txids.len() > api::MAX_TRANSACTIONS_BATCH_SIZE {
+            return Err(ServerError::BadRequest(format!(
+                "too many txids: {} (max {})",
+                txids.len(),
+                api::MAX_TRANSACTIONS_BATCH_SIZE
+            )));
+        }
+
+        blocking
+            .run(move || {
+                let transactions = config.rpc_retry.run(&bitcoin_rpc_pool, |client| {
+                    Ok(api::transactions(index.clone(), client, &txids)?)
+                })?;
+                Ok(Negotiated(format, transactions).into_response())
+            })
+            .await?
     }
 
-    async fn rune_transactions(
+    async fn transaction_raw(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
-        Query(pagination): Query<Pagination>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Path(txid): Path<Txid>,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            Ok(Json(api::last_rune_transactions(index, &rune, Some(pagination))?).into_response())
-        })
+        blocking
+            .run(move || {
+                let raw_tx = config
+                    .rpc_retry
+                    .run(&bitcoin_rpc_pool, |client| {
+                        Ok(api::bitcoin_transaction_raw(index.clone(), client, &txid)?)
+                    })
+                    .or_transaction_not_indexed_yet(&index)?;
+
+                Ok((
+                    StatusCode::OK,
+                    [(header::CONTENT_TYPE, "application/octet-stream")],
+                    Bytes::from(raw_tx),
+                )
+                    .into_response())
+            })
+            .await?
     }
 
-    async fn inscription(
+    async fn transaction_hex(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Extension(config): Extension<Arc<ServerConfig>>,
-        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
-        accept_encoding: AcceptEncoding,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Path(txid): Path<Txid>,
     ) -> ServerResult {
-        task::block_in_place(|| {
-            Ok(api::inscription_content(
-                index,
-                &inscription_id,
-                accept_encoding,
-                config.csp_origin.clone(),
-                config.decompress,
-            )?
-            .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
-            .into_response())
-        })
-    }
+        blocking
+            .run(move || {
+                let hex_string = config
+                    .rpc_retry
+                    .run(&bitcoin_rpc_pool, |client| {
+                        Ok(api::bitcoin_transaction_hex(index.clone(), client, &txid)?)
+                    })
+                    .or_transaction_not_indexed_yet(&index)?;
 
-    async fn mempool_txids(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_txids(index)?).into_response()))
+                Ok((
+                    StatusCode::OK,
+                    [(header::CONTENT_TYPE, "text/plain")],
+                    hex_string,
+                )
+                    .into_response())
+            })
+            .await?
     }
 
-    async fn mempool_tx(
+    async fn transaction_status(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
         Path(txid): Path<Txid>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_tx(index, &txid)?).into_response()))
+        blocking
+            .run(move || Ok(Json(api::transaction_status(index, &txid)?).into_response()))
+            .await?
     }
 
-    async fn mempool_entries(
+    async fn batch(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Json(txids): Json<Vec<Txid>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Json(calls): Json<Vec<serde_json::Value>>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_entries(index, &txids)?).into_response()))
-    }
+        if calls.len() > batch::MAX_BATCH_SIZE {
+            return Err(ServerError::BadRequest(format!(
+                "too many calls: {} (max {})",
+                calls.len(),
+                batch::MAX_BATCH_SIZE
+            )));
+        }
 
-    async fn mempool_all_entries(Extension(index): Extension<Arc<Index>>) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::mempool_entries_all(index)?).into_response()))
+        Ok(
+            Json(batch::run(index, config, bitcoin_rpc_pool, &blocking, calls).await?)
+                .into_response(),
+        )
     }
 
-    async fn address(
+    async fn output(
+        Extension(blocking): Extension<BlockingPool>,
         Extension(index): Extension<Arc<Index>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(address): Path<Address<NetworkUnchecked>>,
+        Extension(tx_cache): Extension<Arc<TxCache>>,
+        OutPointPath(outpoint): OutPointPath,
+        request_headers: HeaderMap,
     ) -> ServerResult {
-        if !config.index_addresses {
-            return Err(ServerError::BadRequest(
-                "addresses are not indexed. Enable --index-addresses to index addresses"
-                    .to_string(),
-            ));
-        }
+        blocking
+            .run(move || {
+                let output = tx_cache
+                    .output(&index, &outpoint, || {
+                        Ok(api::output(index.clone(), &outpoint)?)
+                    })
+                    .or_not_indexed_yet(&index, None)?;
+                let etag = Self::output_etag(&outpoint, &output);
+                Ok(Self::revalidated_json(&request_headers, &etag, output))
+            })
+            .await?
+    }
 
-        let address = address
-            .require_network(config.chain.network())
-            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+    async fn output_inscriptions(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        OutPointPath(outpoint): OutPointPath,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let inscriptions = api::output_inscriptions(index.clone(), &outpoint)
+                    .or_not_indexed_yet(&index, None)?;
+                Ok(Negotiated(format, inscriptions).into_response())
+            })
+            .await?
+    }
 
-        task::block_in_place(|| Ok(Json(api::address(index, &address)?).into_response()))
+    /// An output's value and runes are fixed, so the tag only has to change
+    /// with its spend state, and when its risky runes are confirmed.
+    fn output_etag(outpoint: &OutPoint, output: &TxOutResponse) -> String {
+        let spent = match &output.spent {
+            SpentStatus::Unspent => "unspent".to_string(),
+            SpentStatus::Spent(spender) => match output.spending_height {
+                Some(height) => format!("{}:{}-{height}", spender.txid, spender.vin),
+                None => format!("{}:{}", spender.txid, spender.vin),
+            },
+        };
+        let mempool = if output.spent_in_mempool {
+            "-mempool"
+        } else {
+            ""
+        };
+        format!(
+            "\"{outpoint}-{spent}{mempool}-{}\"",
+            output.risky_runes.len()
+        )
     }
 
-    async fn subscriptions(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
+    async fn outputs(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        format: ResponseFormat,
+        Json(outpoints): Json<Vec<OutPoint>>,
     ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
+        if outpoints.len() > api::MAX_OUTPUTS_BATCH_SIZE {
+            return Err(ServerError::BadRequest(format!(
+                "too many outpoints: {} (max {})",
+                outpoints.len(),
+                api::MAX_OUTPUTS_BATCH_SIZE
+            )));
         }
 
-        task::block_in_place(|| Ok(Json(api::subscriptions(subscription_manager)?).into_response()))
+        blocking
+            .run(move || Ok(Negotiated(format, api::outputs(index, &outpoints)?).into_response()))
+            .await?
     }
 
-    async fn add_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Json(subscription): Json<Subscription>,
-    ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
+    /// `?cursor=` of a page. An empty cursor asks for the first page.
+    fn cursor(cursor: &str, pagination: Pagination) -> ServerResult<Option<Cursor>> {
+        if pagination.skip > 0 {
             return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
+                "skip can't be combined with cursor".to_string(),
             ));
         }
 
-        task::block_in_place(|| {
-            Ok(Json(api::add_subscription(subscription_manager, subscription)?).into_response())
-        })
+        if cursor.is_empty() {
+            return Ok(None);
+        }
+
+        cursor
+            .parse()
+            .map(Some)
+            .map_err(|err: InvalidCursor| ApiError::from(err).into())
     }
 
-    async fn delete_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(id): Path<Uuid>,
+    /// Paginated by offset, or with `?cursor=` by key. Cursors only page
+    /// through the default order.
+    async fn runes(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Page(pagination): Page,
+        Query(query): Query<RunesQuery>,
+        Query(CursorQuery { cursor }): Query<CursorQuery>,
+        uri: Uri,
+        format: ResponseFormat,
     ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
+        if let Some(cursor) = cursor {
+            if query != RunesQuery::default() {
+                return Err(ServerError::BadRequest(
+                    "cursor can't be combined with sort or order".to_string(),
+                ));
+            }
+
+            let cursor = Self::cursor(&cursor, pagination)?;
+            return blocking
+                .run(move || {
+                    let runes = api::runes_after(index, cursor, pagination.limit)?;
+                    let link =
+                        pagination_links::cursor_link_header(&uri, runes.next_cursor.as_deref());
+                    Ok((link, Negotiated(format, runes)).into_response())
+                })
+                .await?;
         }
 
-        task::block_in_place(|| {
-            Ok(Json(api::delete_subscription(subscription_manager, id)?).into_response())
-        })
+        blocking
+            .run(move || {
+                let runes = api::runes(index, query, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, runes.items.len());
+                Ok((link, Negotiated(format, runes)).into_response())
+            })
+            .await?
     }
 
-    async fn get_subscription(
-        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(id): Path<Uuid>,
+    async fn search_runes(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Query(RuneSearchQuery { q, limit }): Query<RuneSearchQuery>,
+        format: ResponseFormat,
     ) -> ServerResult {
-        if !config.enable_webhook_subscriptions {
-            return Err(ServerError::BadRequest(
-                "subscriptions are not enabled".to_string(),
-            ));
+        // Search is case-insensitive and ignores spacers, the rest follows rune name parsing.
+        let name = q.to_uppercase().replace(['.', '•'], "");
+        if name.is_empty() {
+            return Err(ServerError::BadRequest("search query is empty".to_string()));
         }
 
-        task::block_in_place(|| {
-            Ok(Json(api::get_subscription(subscription_manager, id)?).into_response())
-        })
+        let rune = Rune::from_str(&name).map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+        blocking
+            .run(move || {
+                Ok(Negotiated(format, api::search_runes(index, &rune, limit)?).into_response())
+            })
+            .await?
     }
-}
 
-impl<S> axum::extract::FromRequestParts<S> for AcceptEncoding
-where
-    Arc<ServerConfig>: FromRef<S>,
-    S: Send + Sync,
-{
-    type Rejection = (StatusCode, &'static str);
+    /// Streams every rune etched before the request as NDJSON, oldest first.
+    /// An index error midway aborts the connection instead of ending the body,
+    /// so an export that doesn't end cleanly is incomplete.
+    async fn runes_export(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+    ) -> ServerResult {
+        let (runes_count, block_count) = {
+            let index = index.clone();
+            blocking
+                .run(move || -> ServerResult<_> {
+                    let runes_count = index.get_runes_count().map_err(ApiError::from)?;
+                    let block_count = index.get_block_count().map_err(ApiError::from)?;
+                    Ok((runes_count, block_count))
+                })
+                .await??
+        };
 
-    async fn from_request_parts(
-        parts: &mut http::request::Parts,
-        _state: &S,
-    ) -> Result<Self, Self::Rejection> {
-        Ok(Self(parts.headers.get("accept-encoding").map(|value| {
-            value.to_str().unwrap_or_default().to_owned()
-        })))
+        let chunks = (0..runes_count)
+            .step_by(RUNES_EXPORT_CHUNK as usize)
+            .map(move |start| start..(start + RUNES_EXPORT_CHUNK).min(runes_count));
+
+        let body = stream::iter(chunks).then(move |numbers| {
+            let (blocking, index) = (blocking.clone(), index.clone());
+            async move {
+                let runes = blocking
+                    .run(move || api::runes_by_numbers(index, numbers, block_count))
+                    .await
+                    .and_then(|runes| Ok(runes?))
+                    .map_err(|error| {
+                        error!("Failed to export runes: {error}");
+                        io::Error::other(error.to_string())
+                    })?;
+
+                let mut lines = Vec::new();
+                for rune in runes {
+                    serde_json::to_writer(&mut lines, &rune)?;
+                    lines.push(b'\n');
+                }
+                Ok::<_, io::Error>(Bytes::from(lines))
+            }
+        });
+
+        Ok((
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            Body::from_stream(body),
+        )
+            .into_response())
+    }
+
+    async fn rune(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Json(api::rune(index, &rune)?).into_response()))
+            .await?
+    }
+
+    /// Like `/rune/{rune}`, but only takes an id.
+    async fn rune_by_id(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune_id)): Path<DeserializeFromStr<RuneId>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Json(api::rune(index, &query::Rune::Id(rune_id))?).into_response()))
+            .await?
+    }
+
+    async fn rune_mints(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Negotiated(format, api::rune_mints(index, &rune)?).into_response()))
+            .await?
+    }
+
+    async fn rune_etching(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Negotiated(format, api::rune_etching(index, &rune)?).into_response()))
+            .await?
+    }
+
+    async fn rune_chart(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Query(chart_query): Query<RuneChartQuery>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(
+                    Negotiated(format, api::rune_chart(index, &rune, &chart_query)?)
+                        .into_response(),
+                )
+            })
+            .await?
+    }
+
+    async fn rune_transactions(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Page(pagination): Page,
+        Query(RuneTransactionsQuery {
+            order,
+            kind,
+            address,
+            ..
+        }): Query<RuneTransactionsQuery>,
+        Query(CursorQuery { cursor }): Query<CursorQuery>,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        let address = match address {
+            Some(address) => {
+                if !config.index_addresses {
+                    return Err(ServerError::BadRequest(
+                        "addresses are not indexed. Enable --index-addresses to index addresses"
+                            .to_string(),
+                    ));
+                }
+
+                Some(network::require_network(address, config.chain)?)
+            }
+            None => None,
+        };
+
+        if let Some(cursor) = cursor {
+            let cursor = Self::cursor(&cursor, pagination)?;
+            return blocking
+                .run(move || {
+                    let transactions = api::rune_transactions_after(
+                        index,
+                        &rune,
+                        cursor,
+                        pagination.limit,
+                        order,
+                        &kind,
+                        address.as_ref(),
+                    )?;
+                    let link = pagination_links::cursor_link_header(
+                        &uri,
+                        transactions.next_cursor.as_deref(),
+                    );
+                    Ok((link, Negotiated(format, transactions)).into_response())
+                })
+                .await?;
+        }
+
+        blocking
+            .run(move || {
+                let transactions = api::rune_transactions(
+                    index,
+                    &rune,
+                    Some(pagination),
+                    order,
+                    &kind,
+                    address.as_ref(),
+                )?;
+                let link =
+                    pagination_links::link_header(&uri, pagination, transactions.items.len());
+                Ok((link, Negotiated(format, transactions)).into_response())
+            })
+            .await?
+    }
+
+    async fn rune_holders(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Page(pagination): Page,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        if !config.index_addresses {
+            return Err(ServerError::BadRequest(
+                "addresses are not indexed. Enable --index-addresses to index addresses"
+                    .to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || {
+                let holders = api::rune_holders(index, &rune, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, holders.items.len());
+                Ok((link, Negotiated(format, holders)).into_response())
+            })
+            .await?
+    }
+
+    async fn inscription(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(content_dictionary): Extension<Option<Arc<ContentDictionary>>>,
+        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
+        accept_encoding: AcceptEncoding,
+        request_headers: HeaderMap,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let (mut headers, body) = api::inscription_content(
+                    index,
+                    &inscription_id,
+                    accept_encoding.clone(),
+                    config.csp_origin.clone(),
+                    config.decompress,
+                )?
+                .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
+
+                // The 304 keeps the same headers (CSP, ETag, caching) as the full response.
+                if let (Some(if_none_match), Some(etag)) = (
+                    request_headers.get(header::IF_NONE_MATCH),
+                    headers.get(header::ETAG),
+                ) {
+                    if etag_matches(if_none_match, etag) {
+                        headers.remove(header::CONTENT_LENGTH);
+                        return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+                    }
+                }
+
+                // Ranges are served uncompressed, so they're of the same bytes
+                // whatever the request's `Accept-Encoding`. `If-Range` asks for
+                // the whole body unless the content is still the same.
+                let range = request_headers
+                    .get(header::RANGE)
+                    .filter(|_| match request_headers.get(header::IF_RANGE) {
+                        Some(if_range) => Some(if_range) == headers.get(header::ETAG),
+                        None => true,
+                    })
+                    .and_then(|range| byte_range(range, body.len()));
+
+                match range {
+                    Some(ByteRange::Satisfiable { start, end }) => {
+                        headers.insert(
+                            header::CONTENT_RANGE,
+                            HeaderValue::from_str(&format!("bytes {start}-{end}/{}", body.len()))
+                                .expect("content range is a valid header value"),
+                        );
+                        headers.insert(header::CONTENT_LENGTH, (end - start + 1).into());
+
+                        let mut response = (
+                            StatusCode::PARTIAL_CONTENT,
+                            headers,
+                            body[start..=end].to_vec(),
+                        )
+                            .into_response();
+                        response.extensions_mut().insert(SkipCompression);
+                        return Ok(response);
+                    }
+                    Some(ByteRange::Unsatisfiable) => {
+                        let mut response = error_response(
+                            StatusCode::RANGE_NOT_SATISFIABLE,
+                            ErrorCode::BadRequest,
+                            format!("range starts past the {} byte content", body.len()),
+                        );
+                        response.headers_mut().insert(
+                            header::CONTENT_RANGE,
+                            HeaderValue::from_str(&format!("bytes */{}", body.len()))
+                                .expect("content range is a valid header value"),
+                        );
+                        return Ok(response);
+                    }
+                    None => {}
+                }
+
+                // Compressed here rather than by the compression layer so the
+                // response keeps its `Content-Length`.
+                let body = if config.compress_inscription_content {
+                    compression::precompress(
+                        &config,
+                        &accept_encoding,
+                        content_dictionary.as_deref(),
+                        request_headers.get(AVAILABLE_DICTIONARY),
+                        &mut headers,
+                        body,
+                    )
+                    .map_err(ContentError::CompressionError)?
+                } else {
+                    body
+                };
+
+                let mut response = (headers, body).into_response();
+                response.extensions_mut().insert(SkipCompression);
+
+                Ok(response)
+            })
+            .await?
+    }
+
+    /// The dictionary `/inscription/{inscription_id}` compresses `dcz`
+    /// responses with. `Use-As-Dictionary` has browsers keep it and announce
+    /// it when fetching inscription content.
+    async fn content_dictionary(
+        Extension(content_dictionary): Extension<Option<Arc<ContentDictionary>>>,
+    ) -> ServerResult {
+        let content_dictionary =
+            content_dictionary.ok_or_not_found(|| "inscription content dictionary")?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        headers.insert(
+            HeaderName::from_static("use-as-dictionary"),
+            HeaderValue::from_static("match=\"/inscription/*\""),
+        );
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=86400"),
+        );
+        headers.insert(
+            header::ETAG,
+            HeaderValue::from_str(&format!(
+                "\"{}\"",
+                content_dictionary.available_dictionary()
+            ))
+            .expect("dictionary hash is a valid header value"),
+        );
+
+        Ok((headers, content_dictionary.bytes()).into_response())
+    }
+
+    async fn inscriptions(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Page(pagination): Page,
+        Query(query): Query<InscriptionsQuery>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        if let (Some(from_height), Some(to_height)) = (query.from_height, query.to_height) {
+            if from_height > to_height {
+                return Err(ServerError::BadRequest(
+                    "from_height must not be greater than to_height".to_string(),
+                ));
+            }
+        }
+
+        blocking
+            .run(move || {
+                Ok(
+                    Negotiated(format, api::inscriptions(index, &query, pagination)?)
+                        .into_response(),
+                )
+            })
+            .await?
+    }
+
+    async fn inscription_parents(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(
+                    Negotiated(format, api::inscription_parents(index, &inscription_id)?)
+                        .into_response(),
+                )
+            })
+            .await?
+    }
+
+    async fn inscription_children(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
+        Page(pagination): Page,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let children = api::inscription_children(index, &inscription_id, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, children.items.len());
+                Ok((link, Negotiated(format, children)).into_response())
+            })
+            .await?
+    }
+
+    async fn inscription_metadata(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(inscription_id)): Path<DeserializeFromStr<InscriptionId>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(Json(api::inscription_metadata(index, &inscription_id)?).into_response())
+            })
+            .await?
+    }
+
+    /// Checks the address and rune filters of the mempool txid routes.
+    fn mempool_filters(
+        config: &ServerConfig,
+        query: MempoolTxidsQuery,
+    ) -> ServerResult<(Option<Address>, Option<query::Rune>)> {
+        network::check_network(query.network, config.chain)?;
+
+        let address = match query.address {
+            Some(address) => {
+                if !config.index_addresses {
+                    return Err(ServerError::BadRequest(
+                        "addresses are not indexed. Enable --index-addresses to index addresses"
+                            .to_string(),
+                    ));
+                }
+
+                Some(network::require_network(address, config.chain)?)
+            }
+            None => None,
+        };
+
+        Ok((address, query.rune.map(|DeserializeFromStr(rune)| rune)))
+    }
+
+    async fn mempool_txids(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Query(query): Query<MempoolTxidsQuery>,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        let pagination = match (query.skip, query.limit) {
+            (None, None) => None,
+            (skip, limit) => Some(check_page_size(
+                Pagination {
+                    skip: skip.unwrap_or_default(),
+                    limit: limit.unwrap_or(Pagination::default().limit),
+                },
+                config.max_page_size,
+            )?),
+        };
+        let since_seq = query.since_seq;
+        let (address, rune) = Self::mempool_filters(&config, query)?;
+
+        blocking
+            .run(move || {
+                let (mut txids, next_seq) =
+                    api::mempool_txids(index, address.as_ref(), rune.as_ref(), since_seq)?;
+
+                let link = match pagination {
+                    Some(pagination) => {
+                        txids = txids
+                            .into_iter()
+                            .skip(pagination.skip as usize)
+                            .take(pagination.limit as usize)
+                            .collect();
+                        pagination_links::link_header(&uri, pagination, txids.len())
+                    }
+                    None => None,
+                };
+
+                Ok((
+                    link,
+                    [(MEMPOOL_SEQ, next_seq.to_string())],
+                    Negotiated(format, txids),
+                )
+                    .into_response())
+            })
+            .await?
+    }
+
+    /// Like `/mempool/txids`, as one JSON string per line.
+    async fn mempool_txids_export(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Query(query): Query<MempoolTxidsQuery>,
+    ) -> ServerResult {
+        let since_seq = query.since_seq;
+        let (address, rune) = Self::mempool_filters(&config, query)?;
+
+        let (txids, next_seq) = blocking
+            .run(move || -> ServerResult<_> {
+                Ok(api::mempool_txids(
+                    index,
+                    address.as_ref(),
+                    rune.as_ref(),
+                    since_seq,
+                )?)
+            })
+            .await??;
+
+        let body = stream::iter(txids)
+            .chunks(MEMPOOL_EXPORT_CHUNK)
+            .map(|txids| {
+                let mut lines = Vec::new();
+                for txid in txids {
+                    serde_json::to_writer(&mut lines, &txid)?;
+                    lines.push(b'\n');
+                }
+                Ok::<_, io::Error>(Bytes::from(lines))
+            });
+
+        Ok((
+            [
+                (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+                (MEMPOOL_SEQ, next_seq.to_string()),
+            ],
+            Body::from_stream(body),
+        )
+            .into_response())
+    }
+
+    async fn mempool_tx(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(txid): Path<Txid>,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Json(api::mempool_tx(index, &txid)?).into_response()))
+            .await?
+    }
+
+    async fn mempool_entries(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        format: ResponseFormat,
+        Json(txids): Json<Vec<Txid>>,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(Negotiated(format, api::mempool_entries(index, &txids)?).into_response())
+            })
+            .await?
+    }
+
+    async fn mempool_all_entries(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || Ok(Negotiated(format, api::mempool_entries_all(index)?).into_response()))
+            .await?
+    }
+
+    async fn address(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        AddressPath(address): AddressPath,
+        Page(pagination): Page,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(Negotiated(format, api::address(index, &address, pagination)?).into_response())
+            })
+            .await?
+    }
+
+    async fn address_balance(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        AddressPath(address): AddressPath,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                Ok(Negotiated(format, api::address_balance(index, &address)?).into_response())
+            })
+            .await?
+    }
+
+    async fn address_runes(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        AddressPath(address): AddressPath,
+        Page(pagination): Page,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let runes = api::address_runes(index, &address, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, runes.items.len());
+                Ok((link, Negotiated(format, runes)).into_response())
+            })
+            .await?
+    }
+
+    async fn address_utxos(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        AddressPath(address): AddressPath,
+        Page(pagination): Page,
+        Query(AddressUtxosQuery { include_mempool }): Query<AddressUtxosQuery>,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let utxos = api::address_utxos(index, &address, include_mempool, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, utxos.items.len());
+                Ok((link, Negotiated(format, utxos)).into_response())
+            })
+            .await?
+    }
+
+    async fn script_outputs(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(index): Extension<Arc<Index>>,
+        ScriptPubkeyPath(script_pubkey): ScriptPubkeyPath,
+        Page(pagination): Page,
+        Query(AddressUtxosQuery { include_mempool }): Query<AddressUtxosQuery>,
+        uri: Uri,
+        format: ResponseFormat,
+    ) -> ServerResult {
+        blocking
+            .run(move || {
+                let outputs =
+                    api::script_outputs(index, &script_pubkey, include_mempool, pagination)?;
+                let link = pagination_links::link_header(&uri, pagination, outputs.items.len());
+                Ok((link, Negotiated(format, outputs)).into_response())
+            })
+            .await?
+    }
+
+    async fn subscriptions(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || Ok(Json(api::subscriptions(subscription_manager)?).into_response()))
+            .await?
+    }
+
+    async fn add_subscription(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        request_headers: HeaderMap,
+        Json(subscription): Json<Subscription>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        let idempotency_key = Self::idempotency_key(&request_headers)?;
+        let subscription = Self::checked_subscription(&config, subscription)?;
+
+        blocking
+            .run(move || {
+                Ok(Json(api::add_subscription(
+                    subscription_manager,
+                    subscription,
+                    config.max_subscriptions,
+                    idempotency_key.as_deref(),
+                )?)
+                .into_response())
+            })
+            .await?
+    }
+
+    fn idempotency_key(request_headers: &HeaderMap) -> ServerResult<Option<String>> {
+        request_headers
+            .get(IDEMPOTENCY_KEY)
+            .map(|key| match key.to_str() {
+                Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN => {
+                    Ok(key.to_string())
+                }
+                _ => Err(ServerError::BadRequest(format!(
+                    "{IDEMPOTENCY_KEY} must be 1 to {MAX_IDEMPOTENCY_KEY_LEN} visible ASCII characters"
+                ))),
+            })
+            .transpose()
+    }
+
+    /// Checks the webhook endpoint and canonicalizes the addresses of a
+    /// subscription to add.
+    fn checked_subscription(
+        config: &ServerConfig,
+        mut subscription: Subscription,
+    ) -> ServerResult<Subscription> {
+        match reqwest::Url::parse(&subscription.endpoint) {
+            Ok(url) if url.scheme() == "https" => {}
+            Ok(url) if url.scheme() == "http" && config.webhook_allow_http => {}
+            Ok(url) => {
+                return Err(ServerError::BadRequest(format!(
+                    "unsupported webhook endpoint scheme `{}`, expected https",
+                    url.scheme()
+                )))
+            }
+            Err(err) => {
+                return Err(ServerError::BadRequest(format!(
+                    "invalid webhook endpoint {}: {err}",
+                    subscription.endpoint
+                )))
+            }
+        }
+
+        // Store addresses in their canonical form so they match event addresses.
+        subscription.addresses = subscription
+            .addresses
+            .iter()
+            .map(|address| {
+                let address = address
+                    .parse::<Address<NetworkUnchecked>>()
+                    .map_err(|err| {
+                        ServerError::BadRequest(format!("invalid address {address}: {err}"))
+                    })?;
+                Ok(network::require_network(address, config.chain)?.to_string())
+            })
+            .collect::<ServerResult<_>>()?;
+
+        Ok(subscription)
+    }
+
+    /// Adds every subscription it can and reports the others' errors, in the
+    /// order they were sent.
+    async fn add_subscriptions_batch(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        request_headers: HeaderMap,
+        Json(subscriptions): Json<Vec<serde_json::Value>>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        if subscriptions.len() > api::MAX_SUBSCRIPTIONS_BATCH_SIZE {
+            return Err(ServerError::BadRequest(format!(
+                "too many subscriptions: {} (max {})",
+                subscriptions.len(),
+                api::MAX_SUBSCRIPTIONS_BATCH_SIZE
+            )));
+        }
+
+        let idempotency_key = Self::idempotency_key(&request_headers)?;
+        let checked: Vec<ServerResult<Subscription>> = subscriptions
+            .into_iter()
+            .map(|subscription| {
+                let subscription = serde_json::from_value(subscription)
+                    .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+                Self::checked_subscription(&config, subscription)
+            })
+            .collect();
+
+        let valid = checked
+            .iter()
+            .filter_map(|subscription| subscription.as_ref().ok().cloned())
+            .collect();
+        let mut added = blocking
+            .run(move || {
+                api::add_subscriptions(
+                    subscription_manager,
+                    valid,
+                    config.max_subscriptions,
+                    idempotency_key.as_deref(),
+                )
+            })
+            .await??
+            .into_iter();
+
+        let mut results = Vec::with_capacity(checked.len());
+        for subscription in checked {
+            let result = subscription
+                .and_then(|_| Ok(added.next().expect("one result per valid subscription")?));
+            results.push(match result {
+                Ok(subscription) => SubscriptionBatchResult::Success { subscription },
+                Err(err) => {
+                    let (code, error_code, error) = batch::rendered_error(err).await;
+                    SubscriptionBatchResult::Error {
+                        code,
+                        error_code,
+                        error,
+                    }
+                }
+            });
+        }
+
+        Ok(Json(results).into_response())
+    }
+
+    async fn delete_subscription(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(id): Path<Uuid>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || {
+                Ok(Json(api::delete_subscription(subscription_manager, id)?).into_response())
+            })
+            .await?
+    }
+
+    async fn get_subscription(
+        Extension(blocking): Extension<BlockingPool>,
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(id): Path<Uuid>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        blocking
+            .run(move || Ok(Json(api::get_subscription(subscription_manager, id)?).into_response()))
+            .await?
+    }
+
+    async fn ws_subscription(
+        Extension(tcp_subscription_manager): Extension<Arc<TcpSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        ws: WebSocketUpgrade,
+    ) -> ServerResult {
+        if !config.enable_ws_subscriptions {
+            return Err(ServerError::BadRequest(
+                "websocket subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        Ok(ws
+            .on_upgrade(move |socket| async move {
+                if let Err(e) =
+                    subscription::handle_ws_connection(socket, tcp_subscription_manager).await
+                {
+                    error!("Error handling WebSocket connection: {:?}", e);
+                }
+            })
+            .into_response())
+    }
+
+    async fn sse_subscription(
+        Extension(tcp_subscription_manager): Extension<Arc<TcpSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Query(query): Query<SseQuery>,
+        request_headers: HeaderMap,
+    ) -> ServerResult {
+        if !config.enable_sse_subscriptions {
+            return Err(ServerError::BadRequest(
+                "sse subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        let event_types = query
+            .subscribe
+            .split(',')
+            .map(str::trim)
+            .filter(|event_type| !event_type.is_empty())
+            .map(|event_type| {
+                serde_json::from_value(serde_json::Value::String(event_type.to_string())).map_err(
+                    |_| ServerError::BadRequest(format!("unknown event type `{event_type}`")),
+                )
+            })
+            .collect::<ServerResult<HashSet<EventType>>>()?;
+
+        let last_event_id = request_headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        let stream =
+            subscription::sse_stream(tcp_subscription_manager, event_types, last_event_id).await;
+
+        Ok(Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response())
+    }
+
+    async fn tip_sse(
+        Extension(tcp_subscription_manager): Extension<Arc<TcpSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        request_headers: HeaderMap,
+    ) -> ServerResult {
+        if !config.enable_sse_subscriptions {
+            return Err(ServerError::BadRequest(
+                "sse subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        let last_event_id = request_headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        let stream = subscription::tip_sse_stream(tcp_subscription_manager, last_event_id).await;
+
+        Ok(Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response())
+    }
+
+    async fn events(
+        Extension(tcp_subscription_manager): Extension<Arc<TcpSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Query(query): Query<EventsQuery>,
+    ) -> ServerResult {
+        if !config.enable_http_events {
+            return Err(ServerError::BadRequest(
+                "http events are not enabled".to_string(),
+            ));
+        }
+
+        let wait = query
+            .wait
+            .map_or(Duration::ZERO, Duration::from_secs)
+            .min(config.events_max_wait);
+
+        Ok(Json(
+            tcp_subscription_manager
+                .events_since(query.from, wait)
+                .await,
+        )
+        .into_response())
+    }
+}
+
+impl<S> axum::extract::FromRequestParts<S> for AcceptEncoding
+where
+    Arc<ServerConfig>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self(parts.headers.get("accept-encoding").map(|value| {
+            value.to_str().unwrap_or_default().to_owned()
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            db::RocksDB,
+            index::Settings,
+            models::{BatchUpdate, OutputInscription, RuneEntry},
+            options::Options,
+        },
+        bitcoin::{hashes::Hash, BlockHash, ScriptBuf},
+        clap::Parser,
+        std::path::PathBuf,
+        titan_types::{
+            AddressUtxo, ErrorResponse, PaginationResponse, SpenderReference, TxOutEntry, WithMeta,
+        },
+    };
+
+    /// A regtest index in a fresh data dir, removed again on drop.
+    struct TestIndex {
+        db: Arc<RocksDB>,
+        index: Arc<Index>,
+        config: Arc<ServerConfig>,
+        bitcoin_rpc_pool: RpcClientPool,
+        data_dir: PathBuf,
+    }
+
+    impl TestIndex {
+        fn new(name: &str, args: &[&str]) -> Self {
+            Self::with_options(name, args, |_| {})
+        }
+
+        /// For settings without a flag to change them.
+        fn with_options(name: &str, args: &[&str], configure: impl FnOnce(&mut Options)) -> Self {
+            let data_dir =
+                std::env::temp_dir().join(format!("titan-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&data_dir);
+            let mut options = Options::parse_from(
+                ["titan", "--chain", "regtest", "--data-dir"]
+                    .into_iter()
+                    .chain([data_dir.to_str().unwrap()])
+                    .chain(args.iter().copied()),
+            );
+            configure(&mut options);
+            let settings = Settings::from(options.clone());
+            let config = Arc::new(ServerConfig::from(options));
+
+            let db = Arc::new(RocksDB::open(data_dir.to_str().unwrap()).unwrap());
+            let bitcoin_rpc_pool = RpcClientPool::new(Arc::new(settings.clone()), 1);
+            let index = Arc::new(Index::new(
+                db.clone(),
+                bitcoin_rpc_pool.clone(),
+                settings,
+                None,
+            ));
+
+            Self {
+                db,
+                index,
+                config,
+                bitcoin_rpc_pool,
+                data_dir,
+            }
+        }
+
+        /// Serves `routes` on a local port, returning its URL.
+        async fn serve(&self, routes: Router<Arc<ServerConfig>>) -> String {
+            let router = routes
+                .layer(Extension(self.index.clone()))
+                .layer(Extension(self.config.clone()))
+                .layer(Extension(self.bitcoin_rpc_pool.clone()))
+                .layer(Extension(BlockingPool::new(1)))
+                .with_state(self.config.clone());
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, router).await });
+            url
+        }
+
+        /// Starts the whole server on a free local port, returning its URL.
+        fn start(&self) -> SpawnResult<String> {
+            let addr = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+            let config = Arc::new(ServerConfig {
+                http_listen: vec![addr.to_string()],
+                ..(*self.config).clone()
+            });
+            Server.start(
+                self.index.clone(),
+                Arc::new(WebhookSubscriptionManager::new(self.db.clone())),
+                Arc::new(TcpSubscriptionManager::new()),
+                self.bitcoin_rpc_pool.clone(),
+                config,
+                Handle::new(),
+            )?;
+            Ok(format!("http://{addr}"))
+        }
+    }
+
+    impl Drop for TestIndex {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.data_dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn address_route_only_accepts_addresses_of_the_indexed_network() {
+        let test = TestIndex::new("address-route", &["--index-addresses"]);
+        let url = test
+            .serve(Router::new().route("/address/{address}", get(Server::address)))
+            .await;
+
+        let get_address = |address: &str| reqwest::get(format!("{url}/address/{address}"));
+
+        let response = get_address("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::WrongNetwork);
+        let network = error.network.unwrap();
+        assert_eq!(network.expected, "regtest");
+        assert_eq!(network.parsed, "mainnet");
+    }
+
+    #[tokio::test]
+    async fn content_dictionary_route_serves_the_dictionary() {
+        let path =
+            std::env::temp_dir().join(format!("titan-inscriptions-{}.dict", std::process::id()));
+        let dictionary = b"<!DOCTYPE html><html><head><meta charset=\"utf-8\">".repeat(8);
+        std::fs::write(&path, &dictionary).unwrap();
+        let test = TestIndex::new(
+            "content-dictionary-route",
+            &["--inscription-content-dictionary", path.to_str().unwrap()],
+        );
+        let url = test.start().unwrap();
+
+        for _ in 0..2 {
+            let response = reqwest::get(format!("{url}/inscriptions/dictionary"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers()["use-as-dictionary"],
+                "match=\"/inscription/*\""
+            );
+            let hash = bitcoin::hashes::sha256::Hash::hash(&dictionary);
+            assert_eq!(
+                response.headers()[header::ETAG],
+                format!(
+                    "\":{}:\"",
+                    base64::prelude::BASE64_STANDARD.encode(hash.to_byte_array())
+                )
+            );
+            assert_eq!(response.bytes().await.unwrap(), dictionary);
+        }
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn admin_routes_need_api_keys() {
+        let test = TestIndex::new("admin-without-api-keys", &["--enable-admin"]);
+        assert!(matches!(test.start(), Err(SpawnError::AdminWithoutApiKeys)));
+
+        let test = TestIndex::new(
+            "admin-exempt-from-api-keys",
+            &[
+                "--enable-admin",
+                "--api-keys",
+                "secret",
+                "--api-key-exempt-paths",
+                "/admin/reindex",
+            ],
+        );
+        assert!(matches!(test.start(), Err(SpawnError::AdminWithoutApiKeys)));
+
+        let test = TestIndex::new(
+            "admin-with-api-keys",
+            &["--enable-admin", "--api-keys", "secret"],
+        );
+        let url = test.start().unwrap();
+        let response = reqwest::Client::new()
+            .post(format!("{url}/admin/reindex"))
+            .json(&serde_json::json!({ "from_height": 0, "to_height": 0 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn errors_of_every_route_are_json() {
+        let test = TestIndex::new("json-errors", &["--enable-debug", "--enable-metrics"]);
+        let url = test.start().unwrap();
+        let client = reqwest::Client::new();
+
+        // Added after the other routes and their layers.
+        for path in ["/debug/index-stats", "/metrics", "/tip"] {
+            let response = client.delete(format!("{url}{path}")).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{path}");
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert_eq!(error.code, ErrorCode::MethodNotAllowed, "{path}");
+        }
+
+        let response = client.get(format!("{url}/nowhere")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::NotFound);
+
+        // Extractor rejections.
+        for request in [
+            client.get(format!("{url}/runes?limit=many")),
+            client.get(format!("{url}/block/many/hash")),
+            client
+                .post(format!("{url}/outputs"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body("{"),
+        ] {
+            let response = request.send().await.unwrap();
+            assert!(response.status().is_client_error());
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert_eq!(error.code, ErrorCode::BadRequest);
+        }
+    }
+
+    #[tokio::test]
+    async fn with_meta_wraps_json_in_the_tip_it_was_computed_at() {
+        let test = TestIndex::new("with-meta", &[]);
+        let tip = |height: u64| {
+            let mut update = BatchUpdate::new(0, height + 1, 0);
+            update
+                .block_hashes
+                .insert(height, BlockHash::from_byte_array([height as u8; 32]));
+            update
+        };
+        test.db.batch_update(&tip(0), false).unwrap();
+
+        let db = test.db.clone();
+        let url = test
+            .serve(
+                Router::new()
+                    .route(
+                        "/value",
+                        get(|| async { ([(header::ETAG, "\"1\"")], Json(1)) }),
+                    )
+                    .route(
+                        "/advance",
+                        get(move || async move {
+                            db.batch_update(&tip(1), false).unwrap();
+                            Json(2)
+                        }),
+                    )
+                    .route(
+                        "/stream",
+                        get(|| async {
+                            (
+                                [(header::CONTENT_TYPE, "application/json")],
+                                Body::from_stream(stream::iter([Ok::<_, io::Error>("3")])),
+                            )
+                        }),
+                    )
+                    .layer(middleware::from_fn_with_state(
+                        test.index.clone(),
+                        meta::wrap_with_meta,
+                    )),
+            )
+            .await;
+        let client = reqwest::Client::new();
+        let get = |path: &str| client.get(format!("{url}{path}")).send();
+
+        let response = get("/value").await.unwrap();
+        assert!(response.headers().contains_key(header::ETAG));
+        assert_eq!(response.text().await.unwrap(), "1");
+
+        let response = get("/value?with_meta=true").await.unwrap();
+        assert_eq!(response.headers().get(header::ETAG), None);
+        let content_length = response.content_length().unwrap();
+        let body = response.bytes().await.unwrap();
+        assert_eq!(content_length, body.len() as u64);
+        let wrapped: WithMeta<u64> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(wrapped.data, 1);
+        assert_eq!(wrapped.meta.height, 0);
+        assert_eq!(
+            wrapped.meta.block_hash,
+            BlockHash::from_byte_array([0; 32]).to_string()
+        );
+        assert!(!wrapped.meta.tip_changed);
+
+        let response = client
+            .head(format!("{url}/value?with_meta=true"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers().get(header::ETAG), None);
+
+        let wrapped: WithMeta<u64> = get("/advance?with_meta=true")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(wrapped.data, 2);
+        assert_eq!(wrapped.meta.height, 0);
+        assert!(wrapped.meta.tip_changed);
+
+        let response = get("/stream?with_meta=true").await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "3");
+    }
+
+    /// A node answering every JSON-RPC call with `result`, or with `error`
+    /// if it isn't null. Returns the arguments pointing an index at it.
+    async fn fake_node(result: serde_json::Value, error: serde_json::Value) -> Vec<String> {
+        let node = Router::new().route(
+            "/",
+            post(move |Json(request): Json<serde_json::Value>| async move {
+                Json(serde_json::json!({
+                    "result": result,
+                    "error": error,
+                    "id": request["id"],
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, node).await });
+
+        node_args(&url)
+    }
+
+    fn node_args(url: &str) -> Vec<String> {
+        ["--bitcoin-rpc-url", url]
+            .into_iter()
+            .chain(["--bitcoin-rpc-username", "titan"])
+            .chain(["--bitcoin-rpc-password", "titan"])
+            .map(String::from)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn only_txids_the_index_serves_can_be_not_indexed_yet() {
+        let txid = Txid::from_byte_array([7; 32]);
+        let routes = || {
+            Router::new()
+                .route("/tx/{txid}", get(Server::transaction))
+                .route("/tx/{txid}/hex", get(Server::transaction_hex))
+                .route("/tx/{txid}/runestone", get(Server::transaction_runestone))
+                .layer(Extension(Arc::new(TxCache::new(0, 0))))
+        };
+        let node = fake_node(
+            serde_json::Value::Null,
+            serde_json::json!({
+                "code": -5,
+                "message": "No such mempool or blockchain transaction",
+            }),
+        )
+        .await;
+        let node: Vec<&str> = node.iter().map(String::as_str).collect();
+
+        // Nothing is indexed yet, so whatever the index doesn't have may
+        // still come.
+        let indexed = TestIndex::new("txid-lookups-indexed", &node);
+        let url = indexed.serve(routes()).await;
+        for path in ["", "/hex", "/runestone"] {
+            let response = reqwest::get(format!("{url}/tx/{txid}{path}"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{path}");
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert_eq!(error.code, ErrorCode::NotIndexedYet, "{path}");
+        }
+
+        // The node is at its tip, and doesn't have it.
+        let from_node = TestIndex::with_options("txid-lookups-from-node", &node, |options| {
+            options.index_bitcoin_transactions = false;
+        });
+        let url = from_node.serve(routes()).await;
+        for path in ["", "/hex", "/runestone"] {
+            let response = reqwest::get(format!("{url}/tx/{txid}{path}"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{path}");
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert_eq!(error.code, ErrorCode::RpcError, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accept_route_reports_the_verdict_of_the_node_and_rune_warnings() {
+        use {
+            bitcoin::{
+                absolute::LockTime, opcodes::all::OP_RETURN, transaction::Version, Amount,
+                Transaction, TxIn, TxOut,
+            },
+            ordinals::{Flaw, Runestone},
+            titan_types::TestAcceptResponse,
+        };
+
+        let spent = OutPoint::new(Txid::from_byte_array([9; 32]), 0);
+        let transaction = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: spent,
+                ..Default::default()
+            }],
+            // A truncated varint.
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::builder()
+                    .push_opcode(OP_RETURN)
+                    .push_opcode(Runestone::MAGIC_NUMBER)
+                    .push_slice([0xff])
+                    .into_script(),
+            }],
+        };
+        let txid = transaction.compute_txid();
+        // The served index outlives `TestIndex`, so each needs its own name.
+        let test_accept = |name: &'static str, args: Vec<String>, body: String| async move {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let test = TestIndex::new(name, &args);
+            let url = test
+                .serve(
+                    Router::new().route("/tx/test-accept", post(Server::test_accept_transaction)),
+                )
+                .await;
+            reqwest::Client::new()
+                .post(format!("{url}/tx/test-accept"))
+                .body(body)
+                .send()
+                .await
+                .unwrap()
+        };
+        let hex = consensus::encode::serialize_hex(&transaction);
+
+        let node = fake_node(
+            serde_json::json!([{
+                "txid": txid,
+                "allowed": true,
+                "vsize": 94,
+                "fees": { "base": 0.00001 },
+            }]),
+            serde_json::Value::Null,
+        )
+        .await;
+        let response = test_accept("test-accept-allowed", node, hex.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: TestAcceptResponse = response.json().await.unwrap();
+        assert_eq!(response.txid, txid);
+        assert!(response.allowed);
+        assert_eq!(response.fee, Some(1000));
+        assert_eq!(
+            response.rune_warnings,
+            [
+                format!("input {spent} is not indexed, so its runes are unknown"),
+                format!(
+                    "runestone is a cenotaph ({}), so all input runes are burned",
+                    Flaw::Varint
+                ),
+            ]
+        );
+
+        let node = fake_node(
+            serde_json::Value::Null,
+            serde_json::json!({ "code": -22, "message": "TX decode failed" }),
+        )
+        .await;
+        let response = test_accept("test-accept-rejected", node, hex.clone()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::RpcError);
+        assert!(error.error.contains("TX decode failed"), "{}", error.error);
+
+        // Nothing listens there anymore.
+        let unreachable = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let node = node_args(&format!("http://{unreachable}"));
+        let response = test_accept("test-accept-unreachable", node, hex).await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::RpcError);
+
+        let response = test_accept("test-accept-malformed", Vec::new(), "00".into()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn head_requests_get_the_get_headers_without_a_body() {
+        let test = TestIndex::new("head-route", &["--index-addresses"]);
+        let url = test
+            .serve(
+                Router::new()
+                    .route("/address/{address}", get(Server::address))
+                    .layer(middleware::from_fn(head::strip_head_body)),
+            )
+            .await;
+        let url = format!("{url}/address/bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080");
+        let client = reqwest::Client::new();
+
+        let get = client.get(&url).send().await.unwrap();
+        let head = client.head(&url).send().await.unwrap();
+        assert_eq!(head.status(), StatusCode::OK);
+        for name in [header::CONTENT_TYPE, header::VARY] {
+            assert_eq!(
+                head.headers().get(&name),
+                get.headers().get(&name),
+                "{name}"
+            );
+        }
+        assert_eq!(head.headers().get(header::CONTENT_LENGTH), None);
+        assert!(head.bytes().await.unwrap().is_empty());
+        assert!(!get.bytes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn script_outputs_route_only_lists_unspent_outputs() {
+        let test = TestIndex::new("script-outputs-route", &["--index-addresses"]);
+        let script_pubkey = ScriptBuf::from_hex(
+            "5121020202020202020202020202020202020202020202020202020202020202020251ae",
+        )
+        .unwrap();
+        let outpoint = |n: u8| OutPoint::new(Txid::from_byte_array([n; 32]), 0);
+        let tx_out = |spent| TxOutEntry {
+            runes: vec![],
+            risky_runes: vec![],
+            value: 10_000,
+            spent,
+        };
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        for n in [1, 2] {
+            update
+                .txouts
+                .insert(outpoint(n), tx_out(SpentStatus::Unspent));
+            update
+                .script_pubkeys_outpoints
+                .insert(outpoint(n), script_pubkey.clone());
+        }
+        update.script_pubkeys.insert(
+            script_pubkey.clone(),
+            (vec![outpoint(1), outpoint(2)], vec![]),
+        );
+        test.db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.txouts.insert(
+            outpoint(1),
+            tx_out(SpentStatus::Spent(SpenderReference {
+                txid: outpoint(3).txid,
+                vin: 0,
+            })),
+        );
+        update
+            .script_pubkeys
+            .insert(script_pubkey.clone(), (vec![], vec![outpoint(1)]));
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(Router::new().route("/script/{script}/outputs", get(Server::script_outputs)))
+            .await;
+        let get_outputs = |script: &str| reqwest::get(format!("{url}/script/{script}/outputs"));
+
+        let response = get_outputs(&script_pubkey.to_hex_string()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let outputs: PaginationResponse<AddressUtxo> = response.json().await.unwrap();
+        let outpoints: Vec<OutPoint> = outputs
+            .items
+            .iter()
+            .map(|utxo| OutPoint::new(utxo.txid, utxo.vout))
+            .collect();
+        assert_eq!(outpoints, [outpoint(2)]);
+
+        let response = get_outputs("51ae0").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn address_balance_route_lists_runes_by_id() {
+        use titan_types::{AddressBalance, RuneAmount};
+
+        let test = TestIndex::new("address-balance-route", &["--index-addresses"]);
+        let address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+        let script_pubkey = address
+            .parse::<Address<NetworkUnchecked>>()
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        let outpoint = |n: u8| OutPoint::new(Txid::from_byte_array([n; 32]), 0);
+        let rune = |block, tx, amount| RuneAmount {
+            rune_id: RuneId { block, tx },
+            amount,
+        };
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        let outputs = [
+            vec![rune(840_010, 2, 5), rune(840_000, 7, 1)],
+            vec![rune(840_000, 3, 2), rune(840_010, 2, 5)],
+            vec![rune(839_999, 9, 4)],
+        ];
+        for (n, runes) in (1..).zip(outputs) {
+            update.txouts.insert(
+                outpoint(n),
+                TxOutEntry {
+                    runes,
+                    risky_runes: vec![],
+                    value: 1_000,
+                    spent: SpentStatus::Unspent,
+                },
+            );
+            update
+                .script_pubkeys_outpoints
+                .insert(outpoint(n), script_pubkey.clone());
+        }
+        update.script_pubkeys.insert(
+            script_pubkey,
+            (vec![outpoint(1), outpoint(2), outpoint(3)], vec![]),
+        );
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(Router::new().route("/address/{address}/balance", get(Server::address_balance)))
+            .await;
+        let balance: AddressBalance = reqwest::get(format!("{url}/address/{address}/balance"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(balance.confirmed_sats, 3_000);
+        let runes: Vec<(RuneId, u128)> = balance
+            .runes
+            .iter()
+            .map(|rune| (rune.rune_id, rune.amount))
+            .collect();
+        assert_eq!(
+            runes,
+            [
+                (RuneId::new(839_999, 9).unwrap(), 4),
+                (RuneId::new(840_000, 3).unwrap(), 2),
+                (RuneId::new(840_000, 7).unwrap(), 1),
+                (RuneId::new(840_010, 2).unwrap(), 10),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rune_transactions_route_filters_by_address() {
+        let test = TestIndex::new("rune-transactions-address", &["--index-addresses"]);
+        let address: Address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"
+            .parse::<Address<NetworkUnchecked>>()
+            .unwrap()
+            .assume_checked();
+        let rune_id = RuneId {
+            block: 840_000,
+            tx: 1,
+        };
+        let txid = |n: u8| Txid::from_byte_array([n; 32]);
+        let received = OutPoint::new(txid(2), 0);
+
+        let mut update = BatchUpdate::new(1, 0, 0);
+        update.runes.insert(rune_id, RuneEntry::fixture());
+        update
+            .rune_transactions
+            .insert(rune_id, vec![txid(1), txid(2), txid(3)]);
+        update.txouts.insert(
+            received,
+            TxOutEntry {
+                runes: vec![],
+                risky_runes: vec![],
+                value: 10_000,
+                spent: SpentStatus::Unspent,
+            },
+        );
+        update
+            .script_pubkeys
+            .insert(address.script_pubkey(), (vec![received], vec![]));
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(Router::new().route("/rune/{rune}/transactions", get(Server::rune_transactions)))
+            .await;
+        let get_transactions = |address: &str| {
+            reqwest::get(format!(
+                "{url}/rune/{rune_id}/transactions?address={address}"
+            ))
+        };
+
+        let response = get_transactions(&address.to_string()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let transactions: PaginationResponse<Txid> = response.json().await.unwrap();
+        assert_eq!(transactions.items, [txid(2)]);
+
+        let response = get_transactions("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::WrongNetwork);
+
+        let response = get_transactions("nowhere").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn output_inscriptions_route_lists_the_inscriptions_an_output_holds() {
+        let test = TestIndex::new("output-inscriptions-route", &[]);
+        let txid = |n: u8| Txid::from_byte_array([n; 32]);
+        let inscription_id = |index| InscriptionId {
+            txid: txid(1),
+            index,
+        };
+        let tx_out = |spent| TxOutEntry {
+            runes: vec![],
+            risky_runes: vec![],
+            value: 10_000,
+            spent,
+        };
+        let held = OutPoint::new(txid(2), 0);
+        let spent = OutPoint::new(txid(1), 0);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.txouts.insert(held, tx_out(SpentStatus::Unspent));
+        update.txouts.insert(
+            spent,
+            tx_out(SpentStatus::Spent(SpenderReference {
+                txid: txid(2),
+                vin: 0,
+            })),
+        );
+        update.output_inscriptions.insert(
+            held,
+            vec![
+                OutputInscription {
+                    id: inscription_id(1),
+                    offset: 0,
+                },
+                OutputInscription {
+                    id: inscription_id(0),
+                    offset: 330,
+                },
+            ],
+        );
+        update.output_inscriptions.insert(
+            spent,
+            vec![OutputInscription {
+                id: inscription_id(0),
+                offset: 0,
+            }],
+        );
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(Router::new().route(
+                "/output/{outpoint}/inscriptions",
+                get(Server::output_inscriptions),
+            ))
+            .await;
+        let get_inscriptions =
+            |outpoint: OutPoint| reqwest::get(format!("{url}/output/{outpoint}/inscriptions"));
+
+        let response = get_inscriptions(held).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let inscriptions: Vec<InscriptionId> = response.json().await.unwrap();
+        assert_eq!(inscriptions, [inscription_id(1), inscription_id(0)]);
+
+        let response = get_inscriptions(spent).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let inscriptions: Vec<InscriptionId> = response.json().await.unwrap();
+        assert!(inscriptions.is_empty());
+
+        // Like `/output`, while the node's tip is unknown.
+        let response = get_inscriptions(OutPoint::new(txid(3), 0)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, ErrorCode::NotIndexedYet);
+    }
+
+    #[tokio::test]
+    async fn sat_inscriptions_route_lists_the_inscriptions_on_a_sat() {
+        let test = TestIndex::new("sat-inscriptions-route", &["--index-sats"]);
+        let inscription_id = |index| InscriptionId {
+            txid: Txid::from_byte_array([1; 32]),
+            index,
+        };
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.inscription_sats.insert(inscription_id(1), 50);
+        update.inscription_sats.insert(inscription_id(0), 50);
+        update.inscription_sats.insert(inscription_id(2), 51);
+        test.db.batch_update(&update, false).unwrap();
+
+        let routes =
+            || Router::new().route("/sat/{sat}/inscriptions", get(Server::sat_inscriptions));
+        let url = test.serve(routes()).await;
+        let get_inscriptions = |sat: u64| reqwest::get(format!("{url}/sat/{sat}/inscriptions"));
+
+        let response = get_inscriptions(50).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let inscriptions: Vec<InscriptionId> = response.json().await.unwrap();
+        assert_eq!(inscriptions, [inscription_id(0), inscription_id(1)]);
+
+        let response = get_inscriptions(52).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let inscriptions: Vec<InscriptionId> = response.json().await.unwrap();
+        assert!(inscriptions.is_empty());
+
+        let response = get_inscriptions(Sat::LAST.n() + 1).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let test = TestIndex::new("sat-inscriptions-route-unindexed", &[]);
+        let url = test.serve(routes()).await;
+        let response = reqwest::get(format!("{url}/sat/50/inscriptions"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert!(error.error.contains("--index-sats"), "{}", error.error);
+    }
+
+    #[tokio::test]
+    async fn transaction_data_route_lists_envelopes_and_op_returns() {
+        use bitcoin::{
+            absolute::LockTime,
+            opcodes::{
+                all::{OP_ENDIF, OP_IF, OP_PUSHNUM_13, OP_RETURN},
+                OP_FALSE,
+            },
+            transaction::Version,
+            Amount, Transaction, TxIn, TxOut, Witness,
+        };
+        use titan_types::TransactionData;
+
+        let tapscript = ScriptBuf::builder()
+            .push_opcode(OP_FALSE)
+            .push_opcode(OP_IF)
+            .push_slice(b"ord")
+            .push_slice([1])
+            .push_slice(b"text/plain")
+            .push_slice([])
+            .push_slice(b"hello")
+            .push_opcode(OP_ENDIF)
+            .into_script();
+        let op_return = |data: &[u8]| TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::from_bytes([&[OP_RETURN.to_u8()], data].concat()),
+        };
+        let runestone = [OP_PUSHNUM_13.to_u8(), 0x01, 0x00];
+        let transaction = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                witness: Witness::from_slice(&[tapscript.into_bytes(), vec![0xc0; 33]]),
+                ..Default::default()
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1_000),
+                    script_pubkey: ScriptBuf::new(),
+                },
+                op_return(&[0x02, 0xbe, 0xef]),
+                op_return(&runestone),
+                op_return(&runestone),
+            ],
+        };
+        let txid = transaction.compute_txid();
+
+        let node = fake_node(serde_json::Value::Null, serde_json::Value::Null).await;
+        let node: Vec<&str> = node.iter().map(String::as_str).collect();
+        let test = TestIndex::new("transaction-data-route", &node);
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.transactions.insert(txid, transaction);
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(Router::new().route("/tx/{txid}/data", get(Server::transaction_data)))
+            .await;
+        let data: TransactionData = reqwest::get(format!("{url}/tx/{txid}/data"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(data.envelopes.len(), 1);
+        assert_eq!(data.envelopes[0].input, 0);
+        assert_eq!(
+            data.envelopes[0].payload,
+            ["01", &hex::encode("text/plain"), "", &hex::encode("hello")]
+        );
+
+        let op_returns: Vec<(u32, &str, bool)> = data
+            .op_returns
+            .iter()
+            .map(|op_return| (op_return.vout, op_return.data.as_str(), op_return.runestone))
+            .collect();
+        assert_eq!(
+            op_returns,
+            [
+                (1, "02beef", false),
+                (2, "5d0100", true),
+                (3, "5d0100", false)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn output_route_revalidates_until_the_output_is_spent() {
+        let test = TestIndex::new("output-etag", &[]);
+        let outpoint = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let store = |spent| {
+            let mut update = BatchUpdate::new(0, 0, 0);
+            update.txouts.insert(
+                outpoint,
+                TxOutEntry {
+                    runes: vec![],
+                    risky_runes: vec![],
+                    value: 10_000,
+                    spent,
+                },
+            );
+            test.db.batch_update(&update, false).unwrap();
+        };
+        store(SpentStatus::Unspent);
+
+        let url = test
+            .serve(
+                Router::new()
+                    .route("/output/{outpoint}", get(Server::output))
+                    .layer(Extension(Arc::new(TxCache::new(0, 0)))),
+            )
+            .await;
+        let client = reqwest::Client::new();
+        let get_output = |etag: Option<&HeaderValue>| {
+            let request = client.get(format!("{url}/output/{outpoint}"));
+            match etag {
+                Some(etag) => request.header(header::IF_NONE_MATCH, etag),
+                None => request,
+            }
+            .send()
+        };
+
+        let response = get_output(None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+
+        let response = get_output(Some(&etag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert!(response.bytes().await.unwrap().is_empty());
+
+        store(SpentStatus::Spent(SpenderReference {
+            txid: Txid::from_byte_array([2; 32]),
+            vin: 0,
+        }));
+
+        let response = get_output(Some(&etag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+        let output: TxOutResponse = response.json().await.unwrap();
+        assert!(matches!(output.spent, SpentStatus::Spent(_)));
+    }
+
+    #[tokio::test]
+    async fn metrics_route_counts_requests_by_route() {
+        let test = TestIndex::new("metrics-route", &["--enable-metrics"]);
+        let url = test.start().unwrap();
+
+        let status = reqwest::get(format!("{url}/tx/{}", Txid::all_zeros()))
+            .await
+            .unwrap()
+            .status();
+
+        let response = reqwest::get(format!("{url}/metrics")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = response.text().await.unwrap();
+
+        let requests = body
+            .lines()
+            .find(|line| {
+                line.starts_with("titan_http_requests_total{")
+                    && line.contains(r#"route="/tx/{txid}""#)
+            })
+            .unwrap();
+        assert!(requests.contains(r#"method="GET""#), "{requests}");
+        assert!(
+            requests.contains(&format!(r#"status="{}""#, status.as_u16())),
+            "{requests}"
+        );
+        assert_eq!(requests.rsplit(' ').next(), Some("1"));
+        assert!(body
+            .lines()
+            .any(|line| line == r#"titan_active_subscriptions{kind="tcp"} 0"#));
+    }
+
+    #[tokio::test]
+    async fn readyz_route_fails_while_the_index_lags_the_node() {
+        let test = TestIndex::new("probes", &[]);
+        let url = test.start().unwrap();
+
+        let response = reqwest::get(format!("{url}/healthz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Before the updater first synced with the node.
+        let response = reqwest::get(format!("{url}/readyz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.text().await.unwrap(), "index not ready");
+
+        test.index
+            .set_node_block_count(test.config.ready_lag_blocks);
+        let response = reqwest::get(format!("{url}/readyz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        test.index
+            .set_node_block_count(test.config.ready_lag_blocks + 1);
+        let response = reqwest::get(format!("{url}/readyz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.text().await.unwrap(),
+            format!(
+                "index is {} blocks behind the node",
+                test.config.ready_lag_blocks + 1
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn data_routes_are_unavailable_while_the_index_lags() {
+        let test = TestIndex::new("max-behind-blocks", &["--max-behind-blocks", "2"]);
+        let url = test.start().unwrap();
+
+        test.index.set_node_block_count(5);
+        let response = reqwest::get(format!("{url}/tip")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "index_not_ready");
+        assert_eq!(body["indexed_height"], serde_json::Value::Null);
+        assert_eq!(body["node_height"], 4);
+
+        let response = reqwest::get(format!("{url}/healthz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        test.index.set_node_block_count(2);
+        let response = reqwest::get(format!("{url}/tip")).await.unwrap();
+        assert_ne!(
+            response.json::<ErrorResponse>().await.unwrap().code,
+            ErrorCode::IndexNotReady
+        );
+    }
+
+    #[tokio::test]
+    async fn compressed_request_bodies_are_decompressed_up_to_the_limit() {
+        use {flate2::write::GzEncoder, std::io::Write, titan_types::OutputsResponse};
+
+        let test = TestIndex::new("compressed-requests", &[]);
+        let url = test.start().unwrap();
+        let client = reqwest::Client::new();
+        let post_outputs = |encoding: &'static str, body: Vec<u8>| {
+            client
+                .post(format!("{url}/outputs"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::CONTENT_ENCODING, encoding)
+                .body(body)
+                .send()
+        };
+
+        let outpoint = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let body = serde_json::to_vec(&[outpoint]).unwrap();
+        let mut gzip = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&body).unwrap();
+
+        for (encoding, compressed) in [
+            ("gzip", gzip.finish().unwrap()),
+            ("zstd", zstd::encode_all(&body[..], 0).unwrap()),
+        ] {
+            let response = post_outputs(encoding, compressed).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{encoding}");
+            let outputs: OutputsResponse = response.json().await.unwrap();
+            assert_eq!(outputs.missing, [outpoint], "{encoding}");
+        }
+
+        // A few KiB that decompress past the limit.
+        let padding = vec![b' '; MAX_REQUEST_BODY_SIZE + 1];
+        let bomb = zstd::encode_all(&padding[..], 0).unwrap();
+        assert!(bomb.len() < 64 * 1024);
+        let response = post_outputs("zstd", bomb).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn wtxid_route_finds_segwit_and_legacy_transactions() {
+        use bitcoin::{
+            absolute::LockTime, transaction::Version, Amount, Transaction, TxIn, TxOut, Witness,
+        };
+
+        let transaction = |witness: Witness, value: u64| Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                witness,
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(value),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        let segwit = transaction(Witness::from_slice(&[[1; 64]]), 1_000);
+        let legacy = transaction(Witness::new(), 2_000);
+        assert_ne!(
+            segwit.compute_wtxid().to_raw_hash(),
+            segwit.compute_txid().to_raw_hash()
+        );
+
+        let node = fake_node(serde_json::Value::Null, serde_json::Value::Null).await;
+        let node: Vec<&str> = node.iter().map(String::as_str).collect();
+        let routes = || Router::new().route("/tx/wtxid/{wtxid}", get(Server::transaction_by_wtxid));
+
+        let test = TestIndex::new("wtxid-route", &node);
+        let mut update = BatchUpdate::new(0, 0, 0);
+        for transaction in [&segwit, &legacy] {
+            let txid = transaction.compute_txid();
+            update.transactions.insert(txid, transaction.clone());
+            update.transaction_confirming_block.insert(
+                txid,
+                crate::models::BlockId {
+                    hash: BlockHash::all_zeros(),
+                    height: 0,
+                },
+            );
+        }
+        test.db.batch_update(&update, false).unwrap();
+        let url = test.serve(routes()).await;
+
+        for transaction in [&segwit, &legacy] {
+            let response = reqwest::get(format!("{url}/tx/wtxid/{}", transaction.compute_wtxid()))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let found: titan_types::Transaction = response.json().await.unwrap();
+            assert_eq!(found.txid, transaction.compute_txid());
+        }
+
+        let response = reqwest::get(format!("{url}/tx/wtxid/{}", Txid::all_zeros()))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().code,
+            ErrorCode::NotIndexedYet
+        );
+
+        let test = TestIndex::with_options("wtxid-route-not-indexed", &node, |options| {
+            options.index_bitcoin_transactions = false;
+        });
+        let url = test.serve(routes()).await;
+        let response = reqwest::get(format!("{url}/tx/wtxid/{}", segwit.compute_wtxid()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn rune_id_route_resolves_ids_and_names() {
+        let test = TestIndex::new("rune-by-id", &[]);
+        let rune_id = RuneId {
+            block: 840_000,
+            tx: 1,
+        };
+        let entry = RuneEntry::fixture();
+
+        let mut update = BatchUpdate::new(1, 1, 0);
+        update.rune_ids.insert(entry.spaced_rune.rune.0, rune_id);
+        update.runes.insert(rune_id, entry.clone());
+        test.db.batch_update(&update, false).unwrap();
+
+        let url = test
+            .serve(
+                Router::new()
+                    .route("/rune/{rune}", get(Server::rune))
+                    .route("/rune/id/{rune_id}", get(Server::rune_by_id)),
+            )
+            .await;
+
+        let response = reqwest::get(format!("{url}/rune/id/{rune_id}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let rune: titan_types::RuneResponse = response.json().await.unwrap();
+        assert_eq!(rune.id, rune_id);
+        assert_eq!(rune.spaced_rune, entry.spaced_rune);
+
+        let response = reqwest::get(format!("{url}/rune/{}", entry.spaced_rune))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let rune: titan_types::RuneResponse = response.json().await.unwrap();
+        assert_eq!(rune.id, rune_id);
+
+        let response = reqwest::get(format!("{url}/rune/id/840000:2"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = reqwest::get(format!("{url}/rune/id/UNCOMMONGOODS"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 }
@@ -1,24 +1,57 @@
 use {
+    super::{CompressionAlgorithm, RpcRetry},
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientProvider},
         index::Chain,
     },
     bitcoincore_rpc::{Auth, Client},
+    std::{path::PathBuf, time::Duration},
+    tower_http::compression::CompressionLevel,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ServerConfig {
     pub(crate) chain: Chain,
     pub(crate) csp_origin: Option<String>,
+    pub(crate) cors_allow_origins: Option<Vec<String>>,
+    pub(crate) rate_limit_per_sec: Option<u32>,
+    pub(crate) rate_limit_burst: Option<u32>,
+    pub(crate) api_keys: Option<Vec<String>>,
+    pub(crate) api_key_exempt_paths: Vec<String>,
     pub(crate) decompress: bool,
+    pub(crate) max_page_size: u64,
+    pub(crate) blocking_threads: usize,
+    pub(crate) compression_algorithms: Vec<CompressionAlgorithm>,
+    pub(crate) compression_level: CompressionLevel,
+    pub(crate) compress_inscription_content: bool,
+    pub(crate) inscription_content_dictionary: Option<PathBuf>,
+    pub(crate) amounts_as_strings: bool,
+    pub(crate) tx_cache_entries: usize,
+    pub(crate) tx_cache_min_confirmations: u64,
+    pub(crate) request_timeout_secs: u64,
+    pub(crate) fee_estimates_ttl: Duration,
 
-    pub(crate) http_listen: String,
+    pub(crate) http_listen: Vec<String>,
 
     pub(crate) bitcoin_rpc_url: String,
     pub(crate) bitcoin_rpc_auth: Auth,
+    pub(crate) rpc_retry: RpcRetry,
 
     pub(crate) index_addresses: bool,
+    pub(crate) index_sats: bool,
     pub(crate) enable_webhook_subscriptions: bool,
+    pub(crate) webhook_allow_http: bool,
+    pub(crate) max_subscriptions: usize,
+    pub(crate) enable_ws_subscriptions: bool,
+    pub(crate) enable_sse_subscriptions: bool,
+    pub(crate) enable_http_events: bool,
+    pub(crate) events_max_wait: Duration,
+    pub(crate) enable_metrics: bool,
+    pub(crate) enable_debug: bool,
+    pub(crate) enable_admin: bool,
+    pub(crate) ready_lag_blocks: u64,
+    pub(crate) max_behind_blocks: Option<u64>,
+    pub(crate) shutdown_timeout: Duration,
 }
 
 impl RpcClientProvider for ServerConfig {
@@ -0,0 +1,69 @@
+use {
+    crate::index::Index,
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    },
+    http::{header, StatusCode},
+    serde::Serialize,
+    std::sync::Arc,
+    titan_types::ErrorCode,
+};
+
+/// Routes that stay reachable while the index is catching up, so clients can
+/// observe progress.
+const ALWAYS_AVAILABLE: &[&str] = &["/status", "/healthz", "/readyz", "/metrics"];
+
+pub(super) const RETRY_AFTER_SECS: &str = "30";
+
+#[derive(Clone)]
+pub(super) struct SyncGuard {
+    pub(super) index: Arc<Index>,
+    pub(super) max_behind_blocks: u64,
+}
+
+#[derive(Serialize)]
+struct SyncingResponse {
+    code: ErrorCode,
+    error: &'static str,
+    indexed_height: Option<u64>,
+    node_height: Option<u64>,
+}
+
+/// Rejects data requests with 503 while the index is more than
+/// `max_behind_blocks` behind the node tip.
+pub(super) async fn reject_while_syncing(
+    State(guard): State<SyncGuard>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if ALWAYS_AVAILABLE.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let indexed_block_count = guard.index.get_block_count().ok();
+    let node_block_count = guard.index.get_node_block_count();
+
+    let behind = match (indexed_block_count, node_block_count) {
+        (Some(indexed), Some(node)) => node.saturating_sub(indexed),
+        _ => u64::MAX,
+    };
+
+    if behind <= guard.max_behind_blocks {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, RETRY_AFTER_SECS)],
+        Json(SyncingResponse {
+            code: ErrorCode::IndexNotReady,
+            error: "index is syncing",
+            indexed_height: indexed_block_count.and_then(|count| count.checked_sub(1)),
+            node_height: node_block_count.and_then(|count| count.checked_sub(1)),
+        }),
+    )
+        .into_response()
+}
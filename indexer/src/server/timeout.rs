@@ -0,0 +1,74 @@
+use {
+    super::error::error_response,
+    axum::{
+        extract::{Request, State},
+        middleware::Next,
+        response::Response,
+    },
+    http::StatusCode,
+    std::time::Duration,
+    titan_types::ErrorCode,
+};
+
+/// Long-poll and streaming routes, which are meant to stay open.
+const STREAMING: &[&str] = &["/events", "/sse", "/tip/sse", "/ws"];
+
+/// Answers requests that take longer than the timeout with 504. A read that
+/// already runs on the blocking pool finishes in the background, but requests
+/// still waiting for a slot give theirs up.
+pub(super) async fn request_timeout(
+    State(timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if STREAMING.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => error_response(
+            StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::Timeout,
+            format!("request took longer than {}s", timeout.as_secs()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        axum::{middleware, routing::get, Router},
+        titan_types::ErrorResponse,
+    };
+
+    #[tokio::test]
+    async fn slow_requests_time_out_except_streams() {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            "done"
+        };
+        let router = Router::new()
+            .route("/slow", get(slow))
+            .route("/sse", get(slow))
+            .layer(middleware::from_fn_with_state(
+                Duration::from_millis(100),
+                request_timeout,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = reqwest::get(format!("{url}/slow")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().code,
+            ErrorCode::Timeout
+        );
+
+        let response = reqwest::get(format!("{url}/sse")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "done");
+    }
+}
@@ -0,0 +1,208 @@
+use {
+    super::error::ServerResult,
+    crate::{api::ApiError, index::Index},
+    bitcoin::{OutPoint, Txid},
+    std::{
+        collections::{BTreeMap, HashMap},
+        hash::Hash,
+        sync::Mutex,
+    },
+    titan_types::{SpentStatus, Transaction, TxOutResponse},
+};
+
+/// Least recently used entries are evicted first.
+struct Lru<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    by_tick: BTreeMap<u64, K>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            by_tick: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, tick) = self.entries.get_mut(key)?;
+        self.by_tick.remove(tick);
+        *tick = self.tick;
+        self.by_tick.insert(self.tick, key.clone());
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if let Some((_, tick)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.by_tick.remove(&tick);
+        }
+        self.by_tick.insert(self.tick, key);
+
+        while self.entries.len() > self.capacity {
+            let Some((_, key)) = self.by_tick.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.by_tick.clear();
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Transaction(Txid),
+    Output(OutPoint),
+}
+
+#[derive(Clone)]
+enum Value {
+    Transaction(Transaction),
+    Output(TxOutResponse),
+}
+
+struct Entries {
+    /// `Index::get_reorg_count` the entries were read at.
+    reorgs: u64,
+    lru: Lru<Key, Value>,
+}
+
+/// Caches `/tx/{txid}` and `/output/{outpoint}` responses that can no longer
+/// change: transactions with at least `min_confirmations`, and outputs whose
+/// spending transaction has that many. Cached transactions still get their
+/// outputs' spent status from the index. Everything is dropped after a reorg.
+pub(super) struct TxCache {
+    min_confirmations: u64,
+    entries: Option<Mutex<Entries>>,
+}
+
+impl TxCache {
+    /// A cache of `capacity` entries, disabled if it's zero.
+    pub(super) fn new(capacity: usize, min_confirmations: u64) -> Self {
+        Self {
+            min_confirmations,
+            entries: (capacity > 0).then(|| {
+                Mutex::new(Entries {
+                    reorgs: 0,
+                    lru: Lru::new(capacity),
+                })
+            }),
+        }
+    }
+
+    fn get(&self, index: &Index, key: &Key) -> Option<Value> {
+        let mut entries = self.entries.as_ref()?.lock().unwrap();
+        let reorgs = index.get_reorg_count();
+        if entries.reorgs != reorgs {
+            entries.lru.clear();
+            entries.reorgs = reorgs;
+            return None;
+        }
+        entries.lru.get(key)
+    }
+
+    fn insert(&self, reorgs: u64, key: Key, value: Value) {
+        let Some(entries) = self.entries.as_ref() else {
+            return;
+        };
+        let mut entries = entries.lock().unwrap();
+        // Read before a reorg that has started since.
+        if entries.reorgs == reorgs {
+            entries.lru.insert(key, value);
+        }
+    }
+
+    fn is_deep(&self, index: &Index, block_height: Option<u64>) -> ServerResult<bool> {
+        let Some(block_height) = block_height else {
+            return Ok(false);
+        };
+        let block_count = index.get_block_count().map_err(ApiError::from)?;
+        Ok(block_count.saturating_sub(block_height) >= self.min_confirmations)
+    }
+
+    pub(super) fn transaction(
+        &self,
+        index: &Index,
+        txid: &Txid,
+        fetch: impl FnOnce() -> ServerResult<Transaction>,
+    ) -> ServerResult<Transaction> {
+        let key = Key::Transaction(*txid);
+        if let Some(Value::Transaction(mut transaction)) = self.get(index, &key) {
+            // Outputs can still be spent, so that comes from the index.
+            let outpoints = (0..transaction.output.len() as u32)
+                .map(|vout| OutPoint { txid: *txid, vout })
+                .collect();
+            let tx_outs = index.get_tx_outs(&outpoints).map_err(ApiError::from)?;
+            for (output, outpoint) in transaction.output.iter_mut().zip(&outpoints) {
+                if let Some(tx_out) = tx_outs.get(outpoint) {
+                    output.spent = tx_out.spent.clone();
+                }
+            }
+            return Ok(transaction);
+        }
+
+        let reorgs = index.get_reorg_count();
+        let transaction = fetch()?;
+        if self.entries.is_some() && self.is_deep(index, transaction.status.block_height)? {
+            self.insert(reorgs, key, Value::Transaction(transaction.clone()));
+        }
+
+        Ok(transaction)
+    }
+
+    pub(super) fn output(
+        &self,
+        index: &Index,
+        outpoint: &OutPoint,
+        fetch: impl FnOnce() -> ServerResult<TxOutResponse>,
+    ) -> ServerResult<TxOutResponse> {
+        let key = Key::Output(*outpoint);
+        if let Some(Value::Output(output)) = self.get(index, &key) {
+            return Ok(output);
+        }
+
+        let reorgs = index.get_reorg_count();
+        let output = fetch()?;
+        if let (Some(_), SpentStatus::Spent(spender)) = (&self.entries, &output.spent) {
+            // Not knowing the spender just means it isn't cached.
+            if let Ok(status) = index.get_transaction_status(&spender.txid) {
+                if self.is_deep(index, status.block_height)? {
+                    self.insert(reorgs, key, Value::Output(output.clone()));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut lru = Lru::new(2);
+        lru.insert(1, "a");
+        lru.insert(2, "b");
+        assert_eq!(lru.get(&1), Some("a"));
+
+        lru.insert(3, "c");
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&1), Some("a"));
+        assert_eq!(lru.get(&3), Some("c"));
+
+        lru.insert(3, "d");
+        assert_eq!(lru.entries.len(), 2);
+        assert_eq!(lru.get(&3), Some("d"));
+    }
+}
@@ -40,6 +40,7 @@ pub async fn event_dispatcher(
                                 | Event::TransactionsReplaced { .. }
                                 | Event::NewBlock { .. }
                                 | Event::Reorg { .. }
+                                | Event::Reindex { .. }
                             ) {
                                 append_to_file("events.log", &format!("{:?}", event)).unwrap();
                             }
@@ -0,0 +1,181 @@
+use {
+    std::{
+        collections::VecDeque,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    titan_types::{Event, EventsResponse},
+};
+
+/// Number of recent events kept for replay.
+const EVENT_BUFFER_CAPACITY: usize = 10_000;
+
+/// Bounded buffer of recently broadcast events. Each event gets a sequential
+/// cursor and is tagged with the height of the last `NewBlock` seen when it was
+/// broadcast. Cursors start at the startup time in microseconds, so a cursor
+/// from a previous run is lower than every current one.
+#[derive(Debug)]
+pub struct EventBuffer {
+    events: VecDeque<(u64, u64, Event)>,
+    next_cursor: u64,
+    height: Option<u64>,
+    /// Lowest height from which every event is still buffered.
+    complete_from: Option<u64>,
+}
+
+impl EventBuffer {
+    pub fn new() -> Self {
+        Self::starting_at(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        )
+    }
+
+    fn starting_at(start: u64) -> Self {
+        Self {
+            events: VecDeque::new(),
+            next_cursor: start,
+            height: None,
+            complete_from: None,
+        }
+    }
+
+    pub fn push(&mut self, event: &Event) {
+        if let Event::NewBlock { block_height, .. } = event {
+            self.height = Some(*block_height);
+            self.complete_from.get_or_insert(*block_height);
+        }
+
+        if self.events.len() == EVENT_BUFFER_CAPACITY {
+            if let Some((_, height, _)) = self.events.pop_front() {
+                // Events at `height` may now be incomplete.
+                self.complete_from = Some(self.complete_from.unwrap_or(0).max(height + 1));
+            }
+        }
+
+        self.events.push_back((
+            self.next_cursor,
+            self.height.unwrap_or_default(),
+            event.clone(),
+        ));
+        self.next_cursor += 1;
+    }
+
+    /// Cursor the next event will get.
+    pub fn next_cursor(&self) -> u64 {
+        self.next_cursor
+    }
+
+    /// Events broadcast since block `from_height`, oldest first. Starts with an
+    /// `Event::Gap` when some of those events are no longer buffered.
+    pub fn replay(&self, from_height: u64) -> Vec<Event> {
+        let mut replay = Vec::new();
+
+        if self
+            .complete_from
+            .is_none_or(|complete_from| from_height < complete_from)
+        {
+            replay.push(Event::Gap {
+                from_height,
+                buffered_from_height: self.complete_from,
+            });
+        }
+
+        replay.extend(
+            self.events
+                .iter()
+                .filter(|(_, height, _)| *height >= from_height)
+                .map(|(_, _, event)| event.clone()),
+        );
+
+        replay
+    }
+
+    /// Events with a cursor of at least `cursor`, oldest first. A cursor from
+    /// before the oldest buffered event, or from a previous server run, is
+    /// reported as a gap and every buffered event is returned.
+    pub fn since(&self, cursor: u64) -> EventsResponse {
+        let oldest = self
+            .events
+            .front()
+            .map_or(self.next_cursor, |(cursor, _, _)| *cursor);
+        let gap = cursor < oldest || cursor > self.next_cursor;
+        let from = if gap { oldest } else { cursor };
+
+        EventsResponse {
+            events: self
+                .events
+                .iter()
+                .skip((from - oldest) as usize)
+                .map(|(_, _, event)| event.clone())
+                .collect(),
+            cursor: self.next_cursor,
+            gap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::BlockHash};
+
+    fn new_block(block_height: u64) -> Event {
+        Event::NewBlock {
+            block_hash: BlockHash::from_raw_hash(bitcoin::hashes::Hash::all_zeros()),
+            block_height,
+        }
+    }
+
+    #[test]
+    fn replay_from_height() {
+        let mut buffer = EventBuffer::new();
+        buffer.push(&new_block(10));
+        buffer.push(&Event::TransactionsAdded { txids: vec![] });
+        buffer.push(&new_block(11));
+
+        assert_eq!(buffer.replay(11), vec![new_block(11)]);
+        assert_eq!(buffer.replay(10).len(), 3);
+        assert!(matches!(
+            buffer.replay(9).first(),
+            Some(Event::Gap {
+                from_height: 9,
+                buffered_from_height: Some(10),
+            })
+        ));
+    }
+
+    #[test]
+    fn events_since_cursor() {
+        let mut buffer = EventBuffer::starting_at(100);
+        buffer.push(&new_block(10));
+        buffer.push(&new_block(11));
+
+        let response = buffer.since(101);
+        assert_eq!(response.events, vec![new_block(11)]);
+        assert_eq!(response.cursor, 102);
+        assert!(!response.gap);
+
+        assert!(buffer.since(102).events.is_empty());
+        assert!(buffer.since(105).gap);
+        assert_eq!(buffer.since(105).events.len(), 2);
+    }
+
+    #[test]
+    fn cursor_from_a_previous_run_is_a_gap() {
+        let mut before_restart = EventBuffer::starting_at(100);
+        for height in 0..5 {
+            before_restart.push(&new_block(height));
+        }
+        let cursor = before_restart.since(100).cursor;
+
+        let mut after_restart = EventBuffer::starting_at(200);
+        after_restart.push(&new_block(5));
+        after_restart.push(&new_block(6));
+
+        let response = after_restart.since(cursor);
+        assert!(response.gap);
+        assert_eq!(response.events, vec![new_block(5), new_block(6)]);
+        assert_eq!(response.cursor, 202);
+    }
+}
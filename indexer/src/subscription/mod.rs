@@ -1,9 +1,16 @@
 mod dispatcher;
+mod event_buffer;
 mod spawn;
+mod sse_subscription;
 mod tcp_subscription;
 mod webhook;
+mod ws_subscription;
 
 pub use spawn::*;
+pub use sse_subscription::{sse_stream, tip_sse_stream};
+pub use tcp_subscription::TcpSubscriptionManager;
 pub use webhook::{
-    StoreError as WebhookStoreError, SubscriptionManager as WebhookSubscriptionManager,
+    IdempotencyKeyReused, StoreError as WebhookStoreError,
+    SubscriptionManager as WebhookSubscriptionManager,
 };
+pub use ws_subscription::handle_ws_connection;
@@ -25,7 +25,7 @@ pub struct WebhookSubscriptionSpawnResult {
 }
 
 pub struct TcpSubscriptionSpawnResult {
-    pub tcp_server_handle: task::JoinHandle<()>,
+    pub tcp_server_handle: Option<task::JoinHandle<()>>,
     pub tcp_subscription_manager: Arc<TcpSubscriptionManager>,
 }
 
@@ -41,7 +41,11 @@ pub struct SubscriptionConfig {
     pub enable_webhook_subscriptions: bool,
     pub enable_tcp_subscriptions: bool,
     pub tcp_address: String,
+    pub enable_ws_subscriptions: bool,
+    pub enable_sse_subscriptions: bool,
+    pub enable_http_events: bool,
     pub enable_file_logging: bool,
+    pub heartbeat_secs: u64,
 }
 
 /// Spawns the subscription-related background tasks (dispatcher + cleanup).
@@ -49,31 +53,51 @@ pub fn spawn_subscription_tasks(
     db: Arc<RocksDB>,
     config: SubscriptionConfig,
 ) -> Option<SubscriptionSpawnResult> {
-    // If both webhook and TCP subscriptions are disabled, return None
-    if !config.enable_webhook_subscriptions && !config.enable_tcp_subscriptions {
+    // If webhook, TCP, WebSocket, SSE and HTTP subscriptions are all disabled, return None
+    if !config.enable_webhook_subscriptions
+        && !config.enable_tcp_subscriptions
+        && !config.enable_ws_subscriptions
+        && !config.enable_sse_subscriptions
+        && !config.enable_http_events
+    {
         return None;
     }
 
     // Create a watch channel for shutdown signaling
     let (shutdown_tx, shutdown_rx) = watch::channel(());
 
-    // Create the TCP subscription manager if enabled
-    let tcp_spawn_result = if config.enable_tcp_subscriptions {
-        let tcp_subscription_manager = Arc::new(TcpSubscriptionManager::new());
-        let tcp_subscription_manager_clone = tcp_subscription_manager.clone();
-        let shutdown_rx_clone = shutdown_rx.clone();
-
-        let tcp_server_handle = tokio::spawn(async move {
-            if let Err(e) = run_tcp_subscription_server(
-                &config.tcp_address,
-                tcp_subscription_manager_clone,
-                shutdown_rx_clone,
-            )
-            .await
-            {
-                error!("TCP subscription server error: {:?}", e);
-            }
-        });
+    // Create the TCP subscription manager if enabled. WebSocket and SSE
+    // subscribers and `GET /events` long polls use the same manager, the TCP
+    // listener is only spawned for TCP.
+    let tcp_spawn_result = if config.enable_tcp_subscriptions
+        || config.enable_ws_subscriptions
+        || config.enable_sse_subscriptions
+        || config.enable_http_events
+    {
+        let tcp_subscription_manager = Arc::new(
+            TcpSubscriptionManager::new()
+                .with_heartbeat_interval(Duration::from_secs(config.heartbeat_secs)),
+        );
+
+        let tcp_server_handle = if config.enable_tcp_subscriptions {
+            let tcp_subscription_manager_clone = tcp_subscription_manager.clone();
+            let shutdown_rx_clone = shutdown_rx.clone();
+            let tcp_address = config.tcp_address.clone();
+
+            Some(tokio::spawn(async move {
+                if let Err(e) = run_tcp_subscription_server(
+                    &tcp_address,
+                    tcp_subscription_manager_clone,
+                    shutdown_rx_clone,
+                )
+                .await
+                {
+                    error!("TCP subscription server error: {:?}", e);
+                }
+            }))
+        } else {
+            None
+        };
 
         Some(TcpSubscriptionSpawnResult {
             tcp_server_handle,
@@ -171,8 +195,8 @@ pub async fn shutdown_and_wait_subscription_tasks(spawn_result: SubscriptionSpaw
         }
     }
 
-    if let Some(tcp_spawn_result) = tcp_spawn_result {
-        if let Err(e) = tcp_spawn_result.tcp_server_handle.await {
+    if let Some(tcp_server_handle) = tcp_spawn_result.and_then(|r| r.tcp_server_handle) {
+        if let Err(e) = tcp_server_handle.await {
             error!("TCP server task join error: {:?}", e);
         } else {
             info!("TCP server task ended cleanly.");
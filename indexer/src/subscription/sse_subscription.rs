@@ -0,0 +1,250 @@
+use {
+    super::tcp_subscription::{is_interested, TcpSubscription, TcpSubscriptionManager},
+    axum::response::sse,
+    futures::{stream, Stream},
+    std::{
+        collections::{HashSet, VecDeque},
+        convert::Infallible,
+        sync::Arc,
+        time::Duration,
+    },
+    titan_types::{BlockTip, Event, EventType},
+    tokio::sync::mpsc,
+    tracing::{error, info},
+    uuid::Uuid,
+};
+
+struct SseSubscription {
+    manager: Arc<TcpSubscriptionManager>,
+    id: Uuid,
+    event_types: HashSet<EventType>,
+    /// Delivers live events. Only used as a wake-up, the events themselves are
+    /// read from the manager's buffer so that each one comes with its cursor.
+    receiver: mpsc::Receiver<Event>,
+    cursor: u64,
+    pending: VecDeque<sse::Event>,
+    /// Only send `tip` events for new blocks.
+    tip_only: bool,
+}
+
+impl SseSubscription {
+    /// Queues the buffered events since `cursor` the client is interested in.
+    async fn fill(&mut self) {
+        let response = self
+            .manager
+            .events_since(Some(self.cursor), Duration::ZERO)
+            .await;
+
+        if response.gap && !self.tip_only {
+            self.pending.push_back(
+                sse::Event::default()
+                    .event("gap")
+                    .data("some events since Last-Event-ID are no longer buffered"),
+            );
+        }
+
+        let first_cursor = response.cursor - response.events.len() as u64;
+        for (cursor, event) in (first_cursor..).zip(response.events) {
+            let sse_event = if self.tip_only {
+                let Event::NewBlock {
+                    block_hash,
+                    block_height,
+                } = event
+                else {
+                    continue;
+                };
+
+                sse::Event::default().event("tip").json_data(BlockTip {
+                    height: block_height,
+                    hash: block_hash.to_string(),
+                })
+            } else {
+                if !is_interested(&self.event_types, &EventType::from(event.clone())) {
+                    continue;
+                }
+
+                sse::Event::default().json_data(&event)
+            };
+
+            match sse_event {
+                Ok(sse_event) => self.pending.push_back(sse_event.id(cursor.to_string())),
+                Err(e) => error!("Failed to serialize event: {:?}", e),
+            }
+        }
+
+        self.cursor = response.cursor;
+    }
+}
+
+/// Streams the events of `event_types` as Server-Sent Events. Each event's
+/// `id` is its cursor in the manager's event buffer. A client that reconnects
+/// with `Last-Event-ID` gets the buffered events it missed first, preceded by
+/// a `gap` event if some are no longer buffered.
+pub async fn sse_stream(
+    manager: Arc<TcpSubscriptionManager>,
+    event_types: HashSet<EventType>,
+    last_event_id: Option<u64>,
+) -> impl Stream<Item = Result<sse::Event, Infallible>> {
+    stream(manager, event_types, last_event_id, false).await
+}
+
+/// Streams a `tip` event with the height and hash of every new block, and
+/// nothing else. Resumes after `Last-Event-ID` like `sse_stream`.
+pub async fn tip_sse_stream(
+    manager: Arc<TcpSubscriptionManager>,
+    last_event_id: Option<u64>,
+) -> impl Stream<Item = Result<sse::Event, Infallible>> {
+    stream(
+        manager,
+        HashSet::from([EventType::NewBlock]),
+        last_event_id,
+        true,
+    )
+    .await
+}
+
+async fn stream(
+    manager: Arc<TcpSubscriptionManager>,
+    event_types: HashSet<EventType>,
+    last_event_id: Option<u64>,
+    tip_only: bool,
+) -> impl Stream<Item = Result<sse::Event, Infallible>> {
+    // Read the cursor before registering so no event falls in between.
+    let cursor = last_event_id.map_or_else(|| manager.next_cursor(), |id| id + 1);
+
+    let (tx, receiver) = mpsc::channel::<Event>(100);
+    let id = Uuid::new_v4();
+    manager
+        .register(TcpSubscription {
+            id,
+            event_types: event_types.clone(),
+            sender: tx,
+        })
+        .await;
+    info!("Registered SSE subscription with id {}", id);
+
+    let mut subscription = SseSubscription {
+        manager,
+        id,
+        event_types,
+        receiver,
+        cursor,
+        pending: VecDeque::new(),
+        tip_only,
+    };
+    subscription.fill().await;
+
+    stream::unfold(subscription, |mut subscription| async move {
+        loop {
+            if let Some(sse_event) = subscription.pending.pop_front() {
+                return Some((Ok(sse_event), subscription));
+            }
+
+            if subscription.receiver.recv().await.is_none() {
+                // The manager dropped the subscription, e.g. on shutdown.
+                info!("Event channel closed for subscription {}", subscription.id);
+                return None;
+            }
+            while subscription.receiver.try_recv().is_ok() {}
+
+            subscription.fill().await;
+        }
+    })
+}
+
+impl Drop for SseSubscription {
+    fn drop(&mut self) {
+        let manager = self.manager.clone();
+        let id = self.id;
+        tokio::spawn(async move {
+            manager.unregister(id).await;
+            info!("Unregistered SSE subscription with id {}", id);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, axum::response::IntoResponse, bitcoin::BlockHash, futures::StreamExt,
+        std::collections::HashMap,
+    };
+
+    fn new_block(block_height: u64) -> Event {
+        Event::NewBlock {
+            block_hash: BlockHash::from_raw_hash(bitcoin::hashes::Hash::all_zeros()),
+            block_height,
+        }
+    }
+
+    /// Renders `event` the way a client receives it and returns its fields.
+    async fn fields(event: sse::Event) -> HashMap<String, String> {
+        let response = sse::Sse::new(stream::iter([Ok::<_, Infallible>(event)])).into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| {
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                (name.to_string(), value.to_string())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn resumes_after_last_event_id() {
+        let manager = Arc::new(TcpSubscriptionManager::new());
+        let start = manager.next_cursor();
+        manager.broadcast(&new_block(10)).await;
+        manager
+            .broadcast(&Event::TransactionsAdded { txids: vec![] })
+            .await;
+        manager.broadcast(&new_block(11)).await;
+
+        let event_types = HashSet::from([EventType::NewBlock]);
+        let mut stream = Box::pin(sse_stream(manager.clone(), event_types, Some(start)).await);
+
+        // The transactions event is filtered out, so the first event is the
+        // third one.
+        let sse_event = fields(stream.next().await.unwrap().unwrap()).await;
+        assert_eq!(sse_event["id"], (start + 2).to_string());
+        assert_eq!(
+            serde_json::from_str::<Event>(&sse_event["data"]).unwrap(),
+            new_block(11)
+        );
+
+        manager.broadcast(&new_block(12)).await;
+        let sse_event = fields(stream.next().await.unwrap().unwrap()).await;
+        assert_eq!(sse_event["id"], (start + 3).to_string());
+        assert_eq!(
+            serde_json::from_str::<Event>(&sse_event["data"]).unwrap(),
+            new_block(12)
+        );
+    }
+
+    #[tokio::test]
+    async fn tip_stream_only_sends_new_blocks() {
+        let manager = Arc::new(TcpSubscriptionManager::new());
+        let start = manager.next_cursor();
+        let mut stream = Box::pin(tip_sse_stream(manager.clone(), None).await);
+
+        manager
+            .broadcast(&Event::TransactionsAdded { txids: vec![] })
+            .await;
+        manager.broadcast(&new_block(10)).await;
+
+        let sse_event = fields(stream.next().await.unwrap().unwrap()).await;
+        assert_eq!(sse_event["event"], "tip");
+        assert_eq!(sse_event["id"], (start + 1).to_string());
+        let tip: BlockTip = serde_json::from_str(&sse_event["data"]).unwrap();
+        assert_eq!(tip.height, 10);
+        assert_eq!(
+            tip.hash,
+            BlockHash::from_raw_hash(bitcoin::hashes::Hash::all_zeros()).to_string()
+        );
+    }
+}
@@ -1,12 +1,15 @@
+use super::event_buffer::EventBuffer;
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
-use titan_types::{Event, EventType, TcpSubscriptionRequest};
+use titan_types::{Event, EventType, EventsResponse, TcpSubscriptionRequest};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     net::{TcpListener, TcpStream},
     sync::{mpsc, watch, RwLock},
+    time::{interval_at, timeout, Instant, Interval, MissedTickBehavior},
 };
 use tracing::{error, info};
 use uuid::Uuid;
@@ -22,28 +25,93 @@ pub struct TcpSubscription {
 }
 
 /// Manages all active TCP subscriptions.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct TcpSubscriptionManager {
     subscriptions: RwLock<HashMap<Uuid, TcpSubscription>>,
+    /// Recent events, replayed to subscribers that reconnect with `from_height`
+    /// and served to long polls on `GET /events`.
+    events: Mutex<EventBuffer>,
+    /// Next event cursor, used to wake up waiting long polls.
+    next_cursor: watch::Sender<u64>,
+    /// Silence after which TCP and WebSocket connections get a heartbeat.
+    heartbeat_interval: Option<Duration>,
+}
+
+impl Default for TcpSubscriptionManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TcpSubscriptionManager {
     pub fn new() -> Self {
+        let events = EventBuffer::new();
+        let next_cursor = watch::channel(events.next_cursor()).0;
         Self {
             subscriptions: RwLock::new(HashMap::new()),
+            events: Mutex::new(events),
+            next_cursor,
+            heartbeat_interval: None,
         }
     }
 
+    /// Sends a heartbeat on connections that were idle for `interval`, so
+    /// NATs and load balancers don't drop them. Zero disables heartbeats.
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = (!interval.is_zero()).then_some(interval);
+        self
+    }
+
+    /// Heartbeat timer of a new connection. Reset it whenever something is
+    /// sent.
+    pub(super) fn heartbeat(&self) -> Option<Interval> {
+        self.heartbeat_interval.map(|period| {
+            let mut heartbeat = interval_at(Instant::now() + period, period);
+            heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            heartbeat
+        })
+    }
+
     /// Register a new TCP subscription.
     pub async fn register(&self, sub: TcpSubscription) {
         self.subscriptions.write().await.insert(sub.id, sub);
     }
 
+    /// Register a new TCP subscription and return the buffered events since
+    /// `from_height` that it's interested in. Holding the write lock means no
+    /// event is broadcast between the snapshot and the registration.
+    pub async fn register_with_replay(&self, sub: TcpSubscription, from_height: u64) -> Vec<Event> {
+        let mut subs = self.subscriptions.write().await;
+
+        let replay = self
+            .events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .replay(from_height)
+            .into_iter()
+            .filter(|event| Self::is_interested(&sub, &EventType::from(event.clone())))
+            .collect();
+
+        subs.insert(sub.id, sub);
+        replay
+    }
+
     /// Unregister a subscription by its id.
     pub async fn unregister(&self, id: Uuid) {
         self.subscriptions.write().await.remove(&id);
     }
 
+    /// Drop every subscription's sender. Each connection then stops streaming
+    /// and closes its socket cleanly instead of being reset on exit.
+    pub async fn close_all(&self) {
+        self.subscriptions.write().await.clear();
+    }
+
+    /// Number of active subscriptions.
+    pub async fn subscription_count(&self) -> usize {
+        self.subscriptions.read().await.len()
+    }
+
     /// Broadcast an event to all subscriptions that have registered interest.
     pub async fn broadcast(&self, event: &Event) {
         // Assume you can derive a string event type from your event.
@@ -52,9 +120,19 @@ impl TcpSubscriptionManager {
 
         let subs = self.subscriptions.read().await;
         let mut failed_ids = Vec::new();
-        
+
+        let next_cursor = {
+            let mut events = self
+                .events
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            events.push(event);
+            events.next_cursor()
+        };
+        self.next_cursor.send_replace(next_cursor);
+
         for (id, sub) in subs.iter() {
-            if sub.event_types.contains(&event_type) {
+            if Self::is_interested(sub, &event_type) {
                 // Try sending the event; if it fails (e.g. channel closed) log the error.
                 if let Err(e) = sub.sender.send(event.clone()).await {
                     error!("Failed to send event to subscription {}: {:?}", id, e);
@@ -62,16 +140,70 @@ impl TcpSubscriptionManager {
                 }
             }
         }
-        
+
         // Drop the read lock before removing subscriptions
         drop(subs);
-        
+
         // Remove any subscriptions that failed to receive events
         for id in failed_ids {
             self.unregister(id).await;
             info!("Unregistered failed subscription with id {}", id);
         }
     }
+
+    /// Events broadcast since `cursor`, or since now if `cursor` is `None`.
+    /// Waits up to `wait` for an event when there are none yet.
+    pub async fn events_since(&self, cursor: Option<u64>, wait: Duration) -> EventsResponse {
+        // Subscribe before reading the buffer so no broadcast is missed.
+        let mut next_cursor = self.next_cursor.subscribe();
+        let cursor = cursor.unwrap_or_else(|| *next_cursor.borrow_and_update());
+
+        let events_since = || {
+            self.events
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .since(cursor)
+        };
+
+        let response = events_since();
+        if !response.events.is_empty() || response.gap {
+            return response;
+        }
+
+        match timeout(wait, next_cursor.changed()).await {
+            Ok(Ok(())) => events_since(),
+            _ => response,
+        }
+    }
+
+    /// Cursor the next broadcast event will get.
+    pub fn next_cursor(&self) -> u64 {
+        *self.next_cursor.borrow()
+    }
+
+    fn is_interested(sub: &TcpSubscription, event_type: &EventType) -> bool {
+        is_interested(&sub.event_types, event_type)
+    }
+}
+
+/// Completes on the next tick of `heartbeat`, never if heartbeats are off.
+pub(super) async fn heartbeat_tick(heartbeat: &mut Option<Interval>) {
+    match heartbeat {
+        Some(heartbeat) => {
+            heartbeat.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Whether a subscriber to `event_types` gets events of `event_type`.
+pub(super) fn is_interested(event_types: &HashSet<EventType>, event_type: &EventType) -> bool {
+    // Reorgs, reindexes and gaps invalidate earlier events, so every
+    // subscriber gets them.
+    matches!(
+        event_type,
+        EventType::Reorg | EventType::Reindex | EventType::Gap
+    ) || event_types.contains(event_type)
 }
 
 /// Run the TCP subscription server on the given address.
@@ -109,8 +241,10 @@ pub async fn run_tcp_subscription_server(
 
 /// Handle a single TCP connection:
 /// 1. Read a line (JSON) from the client specifying the event types to subscribe to.
-/// 2. Create an mpsc channel and register a subscription.
-/// 3. Spawn a task to forward events from the channel to the client.
+/// 2. Create an mpsc channel and register a subscription, replaying buffered
+///    events if the request has a `from_height`.
+/// 3. Spawn a task to forward events from the channel to the client, with an
+///    empty line as a heartbeat while there are none.
 /// 4. Also monitor the connection (for further commands or disconnection) so that when the client disconnects, the subscription is removed.
 async fn handle_tcp_connection(
     socket: TcpStream,
@@ -139,9 +273,24 @@ async fn handle_tcp_connection(
         sender: tx,
     };
     let sub_id = sub.id;
-    manager.register(sub).await;
+    let replay = match request.from_height {
+        Some(from_height) => manager.register_with_replay(sub, from_height).await,
+        None => {
+            manager.register(sub).await;
+            Vec::new()
+        }
+    };
     info!("Registered TCP subscription with id {}", sub_id);
 
+    // Replay missed events before streaming live ones.
+    for event in replay {
+        let json = serde_json::to_string(&event)?;
+        writer.write_all(json.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+    }
+
+    let mut heartbeat = manager.heartbeat();
+
     // Loop until the connection is closed.
     loop {
         tokio::select! {
@@ -152,13 +301,21 @@ async fn handle_tcp_connection(
                         let json = serde_json::to_string(&event)?;
                         writer.write_all(json.as_bytes()).await?;
                         writer.write_all(b"\n").await?;
+                        if let Some(heartbeat) = &mut heartbeat {
+                            heartbeat.reset();
+                        }
                     },
                     None => {
                         info!("Event channel closed for subscription {}", sub_id);
+                        writer.shutdown().await?;
                         break;
                     }
                 }
             }
+            // Clients skip empty lines, so one keeps an idle connection alive.
+            _ = heartbeat_tick(&mut heartbeat) => {
+                writer.write_all(b"\n").await?;
+            }
             // Also monitor the connection for any client input (to detect disconnect).
             result = reader.read_line(&mut buf) => {
                 match result {
@@ -183,3 +340,34 @@ async fn handle_tcp_connection(
     info!("Unregistered TCP subscription with id {}", sub_id);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tokio::io::AsyncReadExt};
+
+    #[tokio::test]
+    async fn sends_heartbeats_on_idle_connections() {
+        let manager = Arc::new(
+            TcpSubscriptionManager::new().with_heartbeat_interval(Duration::from_millis(50)),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            handle_tcp_connection(socket, manager).await.unwrap();
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"{\"subscribe\":[\"NewBlock\"]}\n")
+            .await
+            .unwrap();
+
+        let mut heartbeat = [0u8; 2];
+        timeout(Duration::from_secs(2), client.read_exact(&mut heartbeat))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&heartbeat, b"\n\n");
+    }
+}
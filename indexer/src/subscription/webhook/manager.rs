@@ -3,27 +3,179 @@ use {
         process_event,
         store::{Store, StoreError},
     },
+    bitcoin::hashes::{sha256, Hash},
     reqwest::Client,
-    std::sync::Arc,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, MutexGuard},
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
     titan_types::{Event, Subscription},
     uuid::Uuid,
 };
 
+/// How long the result of a request with an `Idempotency-Key` is kept.
+const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most `Idempotency-Key` results kept. The least recently used one is
+/// dropped to make room for a new one.
+const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+#[derive(Debug, Error)]
+#[error("Idempotency-Key was already used for another request")]
+pub struct IdempotencyKeyReused;
+
+struct IdempotentResult {
+    added: Instant,
+    used: Instant,
+    request: sha256::Hash,
+    /// Each subscription of the request as added, or `None` if it failed.
+    subscriptions: Vec<Option<Subscription>>,
+}
+
+/// Results of recent requests by their `Idempotency-Key`.
+#[derive(Default)]
+struct IdempotencyKeys {
+    results: HashMap<String, IdempotentResult>,
+}
+
+impl IdempotencyKeys {
+    fn get(
+        &mut self,
+        key: &str,
+        request: sha256::Hash,
+        now: Instant,
+    ) -> Result<Option<Vec<Option<Subscription>>>, IdempotencyKeyReused> {
+        self.results
+            .retain(|_, result| now.duration_since(result.added) < IDEMPOTENCY_KEY_TTL);
+        let Some(result) = self.results.get_mut(key) else {
+            return Ok(None);
+        };
+        if result.request != request {
+            return Err(IdempotencyKeyReused);
+        }
+        result.used = now;
+        Ok(Some(result.subscriptions.clone()))
+    }
+
+    /// Records the subscriptions added for `key`. A key that's already
+    /// recorded keeps its original expiry.
+    fn insert(
+        &mut self,
+        key: &str,
+        request: sha256::Hash,
+        now: Instant,
+        subscriptions: Vec<Option<Subscription>>,
+    ) {
+        if let Some(result) = self.results.get_mut(key) {
+            result.used = now;
+            result.subscriptions = subscriptions;
+            return;
+        }
+
+        if self.results.len() >= MAX_IDEMPOTENCY_KEYS {
+            if let Some(least_recently_used) = self
+                .results
+                .iter()
+                .min_by_key(|(_, result)| result.used)
+                .map(|(key, _)| key.clone())
+            {
+                self.results.remove(&least_recently_used);
+            }
+        }
+
+        self.results.insert(
+            key.to_string(),
+            IdempotentResult {
+                added: now,
+                used: now,
+                request,
+                subscriptions,
+            },
+        );
+    }
+}
+
+/// Identifies a request, so a key reused for another one is told apart from
+/// a retry.
+fn request_hash(subscriptions: &[Subscription]) -> sha256::Hash {
+    sha256::Hash::hash(&serde_json::to_vec(subscriptions).expect("subscriptions serialize"))
+}
+
 pub struct SubscriptionManager {
     store: Arc<dyn Store>,
     client: Client,
+    idempotency_keys: Mutex<IdempotencyKeys>,
+    writes: Mutex<()>,
 }
 
 impl SubscriptionManager {
     pub fn new(store: Arc<dyn Store>) -> Self {
         let client = Client::new();
-        Self { store, client }
+        Self {
+            store,
+            client,
+            idempotency_keys: Mutex::new(IdempotencyKeys::default()),
+            writes: Mutex::new(()),
+        }
+    }
+
+    /// Held while subscriptions are added, so requests checking the
+    /// subscription limit can't both pass it.
+    pub fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        self.writes.lock().unwrap()
     }
 
     pub fn add_subscription(&self, subscription: &Subscription) -> Result<(), StoreError> {
         self.store.set_subscription(subscription)
     }
 
+    /// Runs `add` on the `subscriptions` that a request with `key` didn't
+    /// already add within the TTL, and returns the earlier results for the
+    /// others. Failed subscriptions are retried with the same key. Retries
+    /// that race the original wait for it. Fails with `IdempotencyKeyReused`
+    /// if `key` was used for other subscriptions.
+    pub fn add_subscriptions_once<E: From<IdempotencyKeyReused>>(
+        &self,
+        key: &str,
+        subscriptions: Vec<Subscription>,
+        add: impl FnOnce(Vec<Subscription>) -> Vec<Result<Subscription, E>>,
+    ) -> Result<Vec<Result<Subscription, E>>, E> {
+        let request = request_hash(&subscriptions);
+        let mut idempotency_keys = self.idempotency_keys.lock().unwrap();
+        let mut added = idempotency_keys
+            .get(key, request, Instant::now())?
+            .unwrap_or_else(|| vec![None; subscriptions.len()]);
+
+        let to_add = subscriptions
+            .into_iter()
+            .zip(&added)
+            .filter(|(_, added)| added.is_none())
+            .map(|(subscription, _)| subscription)
+            .collect();
+        let mut new_results = add(to_add).into_iter();
+
+        let results = added
+            .iter_mut()
+            .map(|added| match added {
+                Some(subscription) => Ok(subscription.clone()),
+                None => {
+                    let result = new_results
+                        .next()
+                        .expect("one result per subscription to add");
+                    *added = result.as_ref().ok().cloned();
+                    result
+                }
+            })
+            .collect();
+
+        if added.iter().any(Option::is_some) {
+            idempotency_keys.insert(key, request, Instant::now(), added);
+        }
+        Ok(results)
+    }
+
     pub fn delete_subscription(&self, id: &Uuid) -> Result<(), StoreError> {
         self.store.delete_subscription(id)
     }
@@ -40,3 +192,155 @@ impl SubscriptionManager {
         process_event(&self.store, &self.client, event).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(endpoint: &str) -> Subscription {
+        serde_json::from_value(serde_json::json!({
+            "endpoint": endpoint,
+            "event_types": ["NewBlock"],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn idempotency_keys_expire() {
+        let subscription = subscription("https://example.com");
+        let request = request_hash(std::slice::from_ref(&subscription));
+
+        let now = Instant::now();
+        let mut keys = IdempotencyKeys::default();
+        keys.insert("key", request, now, vec![Some(subscription)]);
+
+        assert!(keys
+            .get("key", request, now + Duration::from_secs(60))
+            .unwrap()
+            .is_some());
+        assert!(keys.get("other", request, now).unwrap().is_none());
+        assert!(keys
+            .get("key", request, now + IDEMPOTENCY_KEY_TTL)
+            .unwrap()
+            .is_none());
+        assert!(keys.results.is_empty());
+    }
+
+    #[test]
+    fn idempotency_keys_reject_other_requests_and_drop_least_recently_used() {
+        let first = vec![subscription("https://example.com/first")];
+        let other = request_hash(&[subscription("https://example.com/other")]);
+        let added = vec![Some(first[0].clone())];
+
+        let now = Instant::now();
+        let mut keys = IdempotencyKeys::default();
+        keys.insert("first", request_hash(&first), now, added.clone());
+        assert!(keys.get("first", other, now).is_err());
+
+        for i in 1..MAX_IDEMPOTENCY_KEYS {
+            let later = now + Duration::from_millis(i as u64);
+            keys.insert(&i.to_string(), other, later, added.clone());
+        }
+        let later = now + Duration::from_secs(60);
+        assert!(keys
+            .get("first", request_hash(&first), later)
+            .unwrap()
+            .is_some());
+
+        keys.insert("new", other, later, added);
+        assert_eq!(keys.results.len(), MAX_IDEMPOTENCY_KEYS);
+        assert!(keys.results.contains_key("first"));
+        assert!(!keys.results.contains_key("1"));
+    }
+
+    type Results = Vec<Result<Subscription, Box<dyn std::error::Error>>>;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<Uuid, Subscription>>);
+
+    impl Store for MemoryStore {
+        fn set_subscription(&self, sub: &Subscription) -> Result<(), StoreError> {
+            self.0.lock().unwrap().insert(sub.id, sub.clone());
+            Ok(())
+        }
+
+        fn update_subscription_last_success(&self, _: &Uuid, _: u64) -> Result<(), StoreError> {
+            Ok(())
+        }
+
+        fn get_subscription(&self, id: &Uuid) -> Result<Subscription, StoreError> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(id)
+                .cloned()
+                .ok_or_else(|| StoreError::NotFound(id.to_string()))
+        }
+
+        fn get_subscriptions(&self) -> Result<Vec<Subscription>, StoreError> {
+            Ok(self.0.lock().unwrap().values().cloned().collect())
+        }
+
+        fn delete_subscription(&self, id: &Uuid) -> Result<(), StoreError> {
+            self.0.lock().unwrap().remove(id);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn retries_with_an_idempotency_key_only_add_what_failed() {
+        let manager = SubscriptionManager::new(Arc::new(MemoryStore::default()));
+        let subscriptions = vec![
+            subscription("https://example.com/first"),
+            subscription("https://example.com/second"),
+        ];
+        let add_first = |subscriptions: Vec<Subscription>| -> Results {
+            subscriptions
+                .into_iter()
+                .map(
+                    |mut subscription| match subscription.endpoint.ends_with("first") {
+                        true => {
+                            subscription.id = Uuid::new_v4();
+                            Ok(subscription)
+                        }
+                        false => Err(StoreError::NotFound(subscription.endpoint).into()),
+                    },
+                )
+                .collect()
+        };
+
+        let results = manager
+            .add_subscriptions_once("key", subscriptions.clone(), add_first)
+            .unwrap();
+        let first = results[0].as_ref().unwrap().id;
+        assert!(results[1].is_err());
+
+        let results = manager
+            .add_subscriptions_once("key", subscriptions.clone(), |subscriptions| -> Results {
+                assert_eq!(subscriptions.len(), 1);
+                subscriptions.into_iter().map(Ok).collect()
+            })
+            .unwrap();
+        assert_eq!(results[0].as_ref().unwrap().id, first);
+        assert_eq!(
+            results[1].as_ref().unwrap().endpoint,
+            "https://example.com/second"
+        );
+
+        let results = manager
+            .add_subscriptions_once("key", subscriptions, |subscriptions| -> Results {
+                assert!(subscriptions.is_empty());
+                Vec::new()
+            })
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert!(manager
+            .add_subscriptions_once(
+                "key",
+                vec![subscription("https://example.com")],
+                |_| -> Results { Vec::new() }
+            )
+            .is_err());
+    }
+}
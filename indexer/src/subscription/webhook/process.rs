@@ -1,12 +1,13 @@
 use {
     super::store::Store,
-    reqwest::Client,
+    bitcoin::hashes::{hmac, sha256, Hash, HashEngine},
+    reqwest::{header, Client},
     std::{
         sync::Arc,
         time::{Duration, SystemTime, UNIX_EPOCH},
     },
     thiserror::Error,
-    titan_types::{Event, EventType},
+    titan_types::{Event, EventType, Subscription},
     tokio::time::sleep,
     tracing::error,
 };
@@ -15,20 +16,43 @@ use {
 pub enum SendEventError {
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
     #[error("timeout after {attempts} retries")]
     Timeout { attempts: usize },
 }
 
+/// Header with the hex HMAC-SHA256 of the body, keyed with the subscription's
+/// secret, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "x-titan-signature";
+
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(body);
+    format!("sha256={}", hmac::Hmac::from_engine(engine))
+}
+
 // Helper to send event to endpoint with retry logic
 async fn send_event_with_retry(
     client: &Client,
-    endpoint: &str,
+    sub: &Subscription,
     event: &Event,
     max_retries: usize,
 ) -> Result<(), SendEventError> {
+    let endpoint = &sub.endpoint;
+    let body = serde_json::to_vec(event)?;
+    let signature = sub.secret.as_deref().map(|secret| signature(secret, &body));
+
     let mut attempt = 0;
     loop {
-        let res = client.post(endpoint).json(event).send().await;
+        let mut request = client
+            .post(endpoint)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let res = request.send().await;
 
         match res {
             Ok(response) if response.status().is_success() => {
@@ -57,6 +81,42 @@ async fn send_event_with_retry(
     }
 }
 
+/// Whether `event` passes the subscription's address and rune filters. Events
+/// without an address or rune aren't filtered.
+fn matches_filters(sub: &Subscription, event: &Event) -> bool {
+    match event {
+        Event::AddressModified { address, .. } => {
+            sub.addresses.is_empty() || sub.addresses.contains(address)
+        }
+        Event::RuneEtched { rune_id, .. }
+        | Event::RuneBurned { rune_id, .. }
+        | Event::RuneMinted { rune_id, .. }
+        | Event::RuneTransferred { rune_id, .. } => {
+            sub.runes.is_empty() || sub.runes.contains(rune_id)
+        }
+        // Pending transactions match either filter.
+        Event::PendingTransaction {
+            addresses, runes, ..
+        }
+        | Event::PendingTransactionConfirmed {
+            addresses, runes, ..
+        }
+        | Event::PendingTransactionDropped {
+            addresses, runes, ..
+        }
+        | Event::PendingTransactionReplaced {
+            addresses, runes, ..
+        } => {
+            (sub.addresses.is_empty() && sub.runes.is_empty())
+                || addresses
+                    .iter()
+                    .any(|address| sub.addresses.contains(address))
+                || runes.iter().any(|rune_id| sub.runes.contains(rune_id))
+        }
+        _ => true,
+    }
+}
+
 /// Process an event and send it to all interested webhook subscriptions
 pub async fn process_event(
     store: &Arc<dyn Store>,
@@ -68,20 +128,23 @@ pub async fn process_event(
 
     // Get all subscriptions from DB
     if let Ok(subscriptions) = store.get_subscriptions() {
-        // Filter subscriptions interested in this event type
+        // Filter subscriptions interested in this event type. Reorgs and
+        // reindexes invalidate earlier events, so every subscription gets them.
         let interested: Vec<_> = subscriptions
             .into_iter()
-            .filter(|sub| sub.event_types.contains(&event_type))
+            .filter(|sub| {
+                matches!(event_type, EventType::Reorg | EventType::Reindex)
+                    || (sub.event_types.contains(&event_type) && matches_filters(sub, event))
+            })
             .collect();
 
         // For each subscription, dispatch the event asynchronously
         for sub in interested {
             let client_clone = client.clone();
-            let endpoint = sub.endpoint.clone();
             let event_clone = event.clone();
             let store_clone = store.clone();
             tokio::spawn(async move {
-                let result = send_event_with_retry(&client_clone, &endpoint, &event_clone, 5).await;
+                let result = send_event_with_retry(&client_clone, &sub, &event_clone, 5).await;
 
                 if result.is_ok() {
                     // Mark subscription as successful
@@ -97,3 +160,44 @@ pub async fn process_event(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, bitcoin::hashes::Hash, ordinals::RuneId};
+
+    #[test]
+    fn pending_transactions_match_either_filter() {
+        let rune_id = RuneId { block: 1, tx: 2 };
+        let sub = |addresses: Vec<String>, runes| {
+            let mut subscription = Subscription::new(
+                "https://example.com".to_string(),
+                vec![EventType::PendingTransaction],
+            );
+            subscription.addresses = addresses;
+            subscription.runes = runes;
+            subscription
+        };
+        let event = Event::PendingTransaction {
+            txid: bitcoin::Txid::all_zeros(),
+            addresses: vec!["address".to_string()],
+            runes: vec![],
+        };
+
+        assert!(matches_filters(&sub(vec![], vec![]), &event));
+        assert!(matches_filters(
+            &sub(vec!["address".into()], vec![rune_id]),
+            &event
+        ));
+        assert!(!matches_filters(&sub(vec![], vec![rune_id]), &event));
+        assert!(!matches_filters(&sub(vec!["other".into()], vec![]), &event));
+    }
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use {
+    super::tcp_subscription::{heartbeat_tick, TcpSubscription, TcpSubscriptionManager},
+    axum::extract::ws::{Message, WebSocket},
+    std::{collections::HashSet, sync::Arc},
+    titan_types::{Event, EventType, TcpSubscriptionRequest},
+    tokio::sync::mpsc,
+    tracing::{error, info},
+    uuid::Uuid,
+};
+
+/// Handle a single WebSocket connection. The protocol mirrors the TCP one:
+/// 1. The first text message is a JSON `TcpSubscriptionRequest`.
+/// 2. A subscription is registered on the shared `TcpSubscriptionManager`,
+///    replaying buffered events if the request has a `from_height`.
+/// 3. Every matching `Event` is sent back to the client as a JSON text frame.
+/// 4. Idle connections get a ping as a heartbeat.
+/// 5. When the client disconnects, the subscription is removed.
+pub async fn handle_ws_connection(
+    mut socket: WebSocket,
+    manager: Arc<TcpSubscriptionManager>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Read the first text message containing the subscription request.
+    let request: TcpSubscriptionRequest = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => break serde_json::from_str(text.as_str().trim())?,
+            Some(Ok(Message::Close(_))) | None => {
+                return Err("Connection closed before sending subscription request".into());
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+        }
+    };
+    info!("Received WebSocket subscription request: {:?}", request);
+
+    let event_types: HashSet<EventType> = request.subscribe.into_iter().collect();
+
+    // Create an mpsc channel for delivering events to this connection.
+    let (tx, mut rx) = mpsc::channel::<Event>(100);
+    let sub = TcpSubscription {
+        id: Uuid::new_v4(),
+        event_types,
+        sender: tx,
+    };
+    let sub_id = sub.id;
+    let replay = match request.from_height {
+        Some(from_height) => manager.register_with_replay(sub, from_height).await,
+        None => {
+            manager.register(sub).await;
+            Vec::new()
+        }
+    };
+    info!("Registered WebSocket subscription with id {}", sub_id);
+
+    // Replay missed events before streaming live ones.
+    for event in replay {
+        let json = serde_json::to_string(&event)?;
+        socket.send(Message::Text(json.into())).await?;
+    }
+
+    let mut heartbeat = manager.heartbeat();
+
+    // Loop until the connection is closed.
+    loop {
+        tokio::select! {
+            // Send events received from the channel to the client.
+            maybe_event = rx.recv() => {
+                match maybe_event {
+                    Some(event) => {
+                        let json = match serde_json::to_string(&event) {
+                            Ok(json) => json,
+                            Err(e) => {
+                                error!("Failed to serialize event: {:?}", e);
+                                continue;
+                            }
+                        };
+
+                        if let Err(e) = socket.send(Message::Text(json.into())).await {
+                            error!("Error writing to WebSocket connection: {:?}", e);
+                            break;
+                        }
+                        if let Some(heartbeat) = &mut heartbeat {
+                            heartbeat.reset();
+                        }
+                    },
+                    None => {
+                        info!("Event channel closed for subscription {}", sub_id);
+                        if let Err(e) = socket.send(Message::Close(None)).await {
+                            error!("Error closing WebSocket connection: {:?}", e);
+                        }
+                        break;
+                    }
+                }
+            }
+            // Clients answer pings on their own, the application never sees them.
+            _ = heartbeat_tick(&mut heartbeat) => {
+                if let Err(e) = socket.send(Message::Ping(Default::default())).await {
+                    error!("Error writing to WebSocket connection: {:?}", e);
+                    break;
+                }
+            }
+            // Also monitor the connection to detect disconnects.
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Close(_))) | None => {
+                        info!("WebSocket client disconnected for subscription {}", sub_id);
+                        break;
+                    },
+                    Some(Ok(_)) => {
+                        // For simplicity, ignore any additional messages.
+                    },
+                    Some(Err(e)) => {
+                        error!("Error reading from WebSocket connection: {:?}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    manager.unregister(sub_id).await;
+    info!("Unregistered WebSocket subscription with id {}", sub_id);
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use {
     crate::{transaction::TransactionStatus, RuneAmount, SpentStatus, TxOutEntry},
     bitcoin::{OutPoint, Txid},
+    ordinals::{RuneId, SpacedRune},
     serde::{Deserialize, Serialize},
 };
 
@@ -9,6 +10,38 @@ pub struct AddressData {
     pub value: u64,
     pub runes: Vec<RuneAmount>,
     pub outputs: Vec<AddressTxOut>,
+    /// Total number of outputs for the address. `outputs` only holds the requested page.
+    #[serde(default)]
+    pub total_outputs: u64,
+    /// Offset of the first entry in `outputs`.
+    #[serde(default)]
+    pub offset: u64,
+}
+
+/// Totals of `/address/{address}/balance`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBalance {
+    /// Value of the outputs created in blocks and not spent in one.
+    pub confirmed_sats: u64,
+    /// Change by mempool transactions: outputs they create minus outputs they
+    /// spend. Negative while confirmed outputs are being spent.
+    pub unconfirmed_sats: i64,
+    /// Balances including mempool transactions, like in `AddressData`, by
+    /// rune id.
+    pub runes: Vec<RuneAmount>,
+}
+
+/// A rune an address holds, summed over its unspent outputs, as listed by
+/// `/address/{address}/runes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressRune {
+    pub rune_id: RuneId,
+    pub name: SpacedRune,
+    pub symbol: Option<char>,
+    pub divisibility: u8,
+    /// Including mempool transactions, like `AddressBalance::runes`.
+    #[serde(with = "crate::amount")]
+    pub amount: u128,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,3 +68,51 @@ impl From<(OutPoint, TxOutEntry, TransactionStatus)> for AddressTxOut {
         }
     }
 }
+
+/// An unspent output of an address, as returned by `/address/{address}/utxos`
+/// and `/script/{script}/outputs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressUtxo {
+    pub txid: Txid,
+    pub vout: u32,
+    pub value: u64,
+    pub runes: Vec<RuneAmount>,
+    pub risky_runes: Vec<RuneAmount>,
+    /// Whether a mempool transaction already spends this output.
+    pub pending_spent: bool,
+    pub status: TransactionStatus,
+}
+
+impl From<(OutPoint, TxOutEntry, TransactionStatus)> for AddressUtxo {
+    fn from((outpoint, tx_out, status): (OutPoint, TxOutEntry, TransactionStatus)) -> Self {
+        Self {
+            txid: outpoint.txid,
+            vout: outpoint.vout,
+            value: tx_out.value,
+            runes: tx_out.runes,
+            risky_runes: tx_out.risky_runes,
+            pending_spent: matches!(tx_out.spent, SpentStatus::Spent(_)),
+            status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AddressUtxosQuery {
+    /// Include outputs already spent by a mempool transaction, flagged as
+    /// `pending_spent`.
+    #[serde(default = "default_include_mempool")]
+    pub include_mempool: bool,
+}
+
+impl Default for AddressUtxosQuery {
+    fn default() -> Self {
+        Self {
+            include_mempool: default_include_mempool(),
+        }
+    }
+}
+
+fn default_include_mempool() -> bool {
+    true
+}
@@ -0,0 +1,95 @@
+//! Serde helpers for `u128` rune amounts, used with `#[serde(with = "amount")]`.
+//! Amounts often exceed 2^53, the largest integer a JavaScript number holds
+//! exactly, so by default they're written to JSON as decimal strings.
+
+use {
+    serde::{
+        de::{self, Visitor},
+        Deserialize, Deserializer, Serializer,
+    },
+    std::{
+        fmt,
+        sync::atomic::{AtomicBool, Ordering},
+    },
+};
+
+static AS_STRINGS: AtomicBool = AtomicBool::new(true);
+
+/// Whether human-readable formats like JSON get amounts as strings instead of
+/// numbers. Binary formats like CBOR always get numbers. The server sets this
+/// once from its config.
+pub fn set_as_strings(as_strings: bool) {
+    AS_STRINGS.store(as_strings, Ordering::Relaxed);
+}
+
+pub fn serialize<S: Serializer>(amount: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() && AS_STRINGS.load(Ordering::Relaxed) {
+        serializer.collect_str(amount)
+    } else {
+        serializer.serialize_u128(*amount)
+    }
+}
+
+/// Takes a string or a number. serde_json parses numbers above `u64::MAX` as
+/// floats, so those are rejected rather than rounded.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    if !deserializer.is_human_readable() {
+        return u128::deserialize(deserializer);
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+struct AmountVisitor;
+
+impl Visitor<'_> for AmountVisitor {
+    type Value = u128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned 128-bit integer or a string of one")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<u128, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<u128, E> {
+        Ok(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<u128, E> {
+        u128::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<u128, E> {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Balance {
+        #[serde(with = "super")]
+        amount: u128,
+    }
+
+    #[test]
+    fn amounts_are_strings_in_json() {
+        let balance = Balance { amount: u128::MAX };
+        let json = serde_json::to_string(&balance).unwrap();
+        assert_eq!(json, format!(r#"{{"amount":"{}"}}"#, u128::MAX));
+        assert_eq!(serde_json::from_str::<Balance>(&json).unwrap(), balance);
+
+        assert_eq!(
+            serde_json::from_str::<Balance>(r#"{"amount":42}"#).unwrap(),
+            Balance { amount: 42 }
+        );
+        assert!(serde_json::from_str::<Balance>(r#"{"amount":-1}"#).is_err());
+        assert!(serde_json::from_str::<Balance>(r#"{"amount":1e40}"#).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use {
     bitcoin::{
         block::{Header, Version},
         hashes::Hash,
-        BlockHash, CompactTarget, TxMerkleNode,
+        BlockHash, CompactTarget, TxMerkleNode, Txid,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     ordinals::RuneId,
@@ -18,6 +18,27 @@ pub struct Block {
     pub etched_runes: Vec<RuneId>,
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockTxids {
+    pub height: u64,
+    pub hash: BlockHash,
+    pub txids: Vec<Txid>,
+}
+
+/// Rune activity in a block. Counts are of transactions, so one that mints and
+/// transfers counts towards both.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockRunes {
+    pub height: u64,
+    pub hash: BlockHash,
+    pub etchings: u64,
+    pub mints: u64,
+    pub transfers: u64,
+    pub burns: u64,
+    /// Runes etched, minted, transferred or burned in the block, in id order.
+    pub rune_ids: Vec<RuneId>,
+}
+
 impl BorshSerialize for Block {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         // 1) Serialize `height`
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable reason for a failed request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    /// A `?cursor=` that wasn't returned by the server, or no longer points
+    /// into the items.
+    InvalidCursor,
+    NotFound,
+    NotAcceptable,
+    /// The node rejected the request, e.g. an invalid transaction broadcast.
+    RpcError,
+    IndexNotReady,
+    /// Nothing was found, but the index hasn't reached the height it could
+    /// be at yet (503). Not final, unlike `not_found`.
+    NotIndexedYet,
+    /// Missing or invalid API key.
+    Unauthorized,
+    /// The address or `network` query parameter is for another network than
+    /// the one the server indexes, or `expect_chain` names another one (409).
+    WrongNetwork,
+    RateLimited,
+    /// The server's `--max-subscriptions` are registered already.
+    SubscriptionLimit,
+    /// The request took longer than the server's `--request-timeout-secs`.
+    Timeout,
+    /// The route exists but doesn't take the request's method (405).
+    MethodNotAllowed,
+    /// A reindex of an overlapping range is already queued or running (409).
+    Conflict,
+    /// The `Idempotency-Key` was already used for a request with another body
+    /// (422).
+    IdempotencyKeyReused,
+    InternalError,
+    /// A code added by a newer server.
+    #[serde(other)]
+    Unknown,
+}
+
+/// JSON body of every error response. Details may be added for more codes,
+/// so it's created with `ErrorResponse::new`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub error: String,
+    /// Set for `wrong_network` errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkMismatch>,
+    /// Set for `not_indexed_yet` errors once a block is indexed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_height: Option<u64>,
+}
+
+impl ErrorResponse {
+    /// An error without details.
+    pub fn new(code: ErrorCode, error: String) -> Self {
+        Self {
+            code,
+            error,
+            network: None,
+            indexed_height: None,
+        }
+    }
+}
+
+/// Networks of a `wrong_network` error, named like the server's `--chain`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct NetworkMismatch {
+    /// Network the server indexes.
+    pub expected: String,
+    /// Network of the address, or the one passed as `network` or
+    /// `expect_chain`. Testnet
+    /// addresses are also valid on testnet4 and signet, so they're reported as
+    /// testnet.
+    pub parsed: String,
+}
+
+impl NetworkMismatch {
+    pub fn new(expected: String, parsed: String) -> Self {
+        Self { expected, parsed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_unknown_code() {
+        let response: ErrorResponse =
+            serde_json::from_str(r#"{"code":"not_found","error":"rune not found"}"#).unwrap();
+        assert_eq!(response.code, ErrorCode::NotFound);
+
+        let response: ErrorResponse =
+            serde_json::from_str(r#"{"code":"something_new","error":"?"}"#).unwrap();
+        assert_eq!(response.code, ErrorCode::Unknown);
+        assert_eq!(response.network, None);
+        assert_eq!(response.indexed_height, None);
+    }
+}
@@ -22,8 +22,15 @@ pub enum EventType {
     MempoolTransactionsAdded,
     MempoolTransactionsReplaced,
     MempoolEntriesUpdated,
+    PendingTransaction,
+    PendingTransactionConfirmed,
+    PendingTransactionDropped,
     NewBlock,
     Reorg,
+    Gap,
+    PendingTransactionReplaced,
+    Reindex,
+    ReindexFinished,
 }
 
 impl From<Event> for EventType {
@@ -40,8 +47,15 @@ impl From<Event> for EventType {
             Event::MempoolTransactionsAdded { .. } => EventType::MempoolTransactionsAdded,
             Event::MempoolTransactionsReplaced { .. } => EventType::MempoolTransactionsReplaced,
             Event::MempoolEntriesUpdated { .. } => EventType::MempoolEntriesUpdated,
+            Event::PendingTransaction { .. } => EventType::PendingTransaction,
+            Event::PendingTransactionConfirmed { .. } => EventType::PendingTransactionConfirmed,
+            Event::PendingTransactionDropped { .. } => EventType::PendingTransactionDropped,
+            Event::PendingTransactionReplaced { .. } => EventType::PendingTransactionReplaced,
             Event::NewBlock { .. } => EventType::NewBlock,
             Event::Reorg { .. } => EventType::Reorg,
+            Event::Gap { .. } => EventType::Gap,
+            Event::Reindex { .. } => EventType::Reindex,
+            Event::ReindexFinished { .. } => EventType::ReindexFinished,
         }
     }
 }
@@ -61,8 +75,15 @@ impl fmt::Display for EventType {
             EventType::MempoolTransactionsAdded => write!(f, "MempoolTransactionsAdded"),
             EventType::MempoolTransactionsReplaced => write!(f, "MempoolTransactionsReplaced"),
             EventType::MempoolEntriesUpdated => write!(f, "MempoolEntriesUpdated"),
+            EventType::PendingTransaction => write!(f, "PendingTransaction"),
+            EventType::PendingTransactionConfirmed => write!(f, "PendingTransactionConfirmed"),
+            EventType::PendingTransactionDropped => write!(f, "PendingTransactionDropped"),
+            EventType::PendingTransactionReplaced => write!(f, "PendingTransactionReplaced"),
             EventType::NewBlock => write!(f, "NewBlock"),
             EventType::Reorg => write!(f, "Reorg"),
+            EventType::Gap => write!(f, "Gap"),
+            EventType::Reindex => write!(f, "Reindex"),
+            EventType::ReindexFinished => write!(f, "ReindexFinished"),
         }
     }
 }
@@ -119,18 +140,21 @@ pub enum Event {
         txid: Txid,
     },
     RuneBurned {
+        #[serde(with = "crate::amount")]
         amount: u128,
         location: Location,
         rune_id: RuneId,
         txid: Txid,
     },
     RuneMinted {
+        #[serde(with = "crate::amount")]
         amount: u128,
         location: Location,
         rune_id: RuneId,
         txid: Txid,
     },
     RuneTransferred {
+        #[serde(with = "crate::amount")]
         amount: u128,
         location: Location,
         outpoint: OutPoint,
@@ -160,12 +184,88 @@ pub enum Event {
     MempoolEntriesUpdated {
         txids: Vec<(Txid, MempoolEntry)>,
     },
+    /// A transaction entered the mempool. `addresses` are those it pays to and,
+    /// with `--index-addresses`, those it spends from; `runes` the ones it
+    /// etches, mints, transfers or burns.
+    PendingTransaction {
+        txid: Txid,
+        addresses: Vec<String>,
+        runes: Vec<RuneId>,
+    },
+    /// A `PendingTransaction` was confirmed.
+    PendingTransactionConfirmed {
+        txid: Txid,
+        block_height: u64,
+        addresses: Vec<String>,
+        runes: Vec<RuneId>,
+    },
+    /// A `PendingTransaction` left the mempool unconfirmed, usually because it
+    /// was replaced by fee or conflicted with a confirmed transaction.
+    PendingTransactionDropped {
+        txid: Txid,
+        addresses: Vec<String>,
+        runes: Vec<RuneId>,
+    },
+    /// `new_txid` spends an input of the `PendingTransaction` `old_txid`, so it
+    /// replaced it by fee. Sent right after the `PendingTransaction` of
+    /// `new_txid`, and before the `PendingTransactionDropped` of `old_txid`.
+    /// `addresses` and `runes` are those of `old_txid`.
+    PendingTransactionReplaced {
+        old_txid: Txid,
+        new_txid: Txid,
+        addresses: Vec<String>,
+        runes: Vec<RuneId>,
+    },
     NewBlock {
         block_hash: BlockHash,
         block_height: u64,
     },
+    /// Blocks were rolled back. `old_blockhash` is the previously indexed tip at
+    /// `height - 1` and `new_blockhash` the block that replaces it. Always sent
+    /// before the events of the replacement blocks.
     Reorg {
         height: u64,
         depth: u64,
+        old_blockhash: BlockHash,
+        new_blockhash: BlockHash,
     },
+    /// Sent first when replaying from `from_height` and some of the events since
+    /// then are no longer buffered. `buffered_from_height` is the lowest height
+    /// the server can still replay completely, if any. Clients should resync.
+    Gap {
+        from_height: u64,
+        buffered_from_height: Option<u64>,
+    },
+    /// Blocks `from_height` to `to_height` were rolled back on request of
+    /// `POST /admin/reindex`. They're indexed again next, with a `NewBlock`
+    /// for each, and then `ReindexFinished` is sent.
+    Reindex {
+        from_height: u64,
+        to_height: u64,
+    },
+    /// The blocks of a `Reindex` were indexed again.
+    ReindexFinished {
+        from_height: u64,
+        to_height: u64,
+    },
+}
+
+/// Query for `GET /events`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EventsQuery {
+    /// Cursor returned by the previous poll. Omit to start from now.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Seconds to wait for new events when there are none yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsResponse {
+    pub events: Vec<Event>,
+    /// Pass as `from` on the next poll.
+    pub cursor: u64,
+    /// Some events since `from` are no longer buffered. Clients should resync.
+    pub gap: bool,
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// `GET /fees`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimates {
+    /// The node's `estimatesmartfee` at each confirmation target, soonest
+    /// first.
+    pub estimates: Vec<FeeEstimate>,
+    /// Indexed mempool transactions by fee rate, lowest first. Empty buckets
+    /// are left out.
+    pub mempool_histogram: Vec<FeeHistogramBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /// Blocks the transaction should confirm within.
+    pub target_blocks: u16,
+    /// `None` if the node doesn't have enough data for the target yet.
+    pub sat_per_vbyte: Option<f64>,
+}
+
+/// Transactions paying at least `min_sat_per_vbyte`, and less than the next
+/// bucket's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeHistogramBucket {
+    pub min_sat_per_vbyte: u64,
+    pub count: u64,
+    pub vsize: u64,
+}
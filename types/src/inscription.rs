@@ -0,0 +1,27 @@
+use {
+    crate::InscriptionId,
+    serde::{Deserialize, Serialize},
+};
+
+/// Children aren't included, since an inscription can have any number of
+/// them. `/inscription/{id}/children` pages through them.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct InscriptionMetadata {
+    pub id: InscriptionId,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_length: u64,
+    /// Height of the block that confirmed the reveal transaction, if known.
+    pub genesis_height: Option<u64>,
+    /// Parents named by the envelope, like `/inscription/{id}/parents`.
+    #[serde(default)]
+    pub parents: Vec<InscriptionId>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct InscriptionsQuery {
+    /// Only include inscriptions whose content type starts with this prefix, e.g. `image/`.
+    pub content_type: Option<String>,
+    pub from_height: Option<u64>,
+    pub to_height: Option<u64>,
+}
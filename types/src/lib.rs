@@ -1,25 +1,49 @@
 pub use {
-    address::{AddressData, AddressTxOut},
-    block::Block,
-    event::{Event, EventType, Location},
+    address::{
+        AddressBalance, AddressData, AddressRune, AddressTxOut, AddressUtxo, AddressUtxosQuery,
+    },
+    block::{Block, BlockRunes, BlockTxids},
+    error::{ErrorCode, ErrorResponse, NetworkMismatch},
+    event::{Event, EventType, EventsQuery, EventsResponse, Location},
+    fees::{FeeEstimate, FeeEstimates, FeeHistogramBucket},
+    inscription::{InscriptionMetadata, InscriptionsQuery},
     inscription_id::InscriptionId,
     mempool_entry::{MempoolEntry, MempoolEntryFee},
-    pagination::{Pagination, PaginationResponse},
-    rune::{MintResponse, RuneAmount, RuneResponse},
-    stats::{BlockTip, Status},
-    subscription::{Subscription, TcpSubscriptionRequest},
-    transaction::{Transaction, TransactionStatus, TxOut},
-    tx_out::{SpenderReference, SpentStatus, TxOutEntry},
+    pagination::{
+        CursorPage, CursorQuery, Order, OrderQuery, Pagination, PaginationBuilder,
+        PaginationResponse, RuneSearchQuery,
+    },
+    rune::{
+        ChartInterval, MintResponse, RuneAmount, RuneBalance, RuneChartBucket, RuneChartQuery,
+        RuneChartResponse, RuneEtchingResponse, RuneHolder, RuneMintsResponse, RuneResponse,
+        RuneSort, RuneTermsResponse, RuneTransactionKind, RuneTransactionsQuery, RunesQuery,
+    },
+    sat::SatResponse,
+    stats::{
+        BlockTip, ColumnFamilyStats, IndexStats, ReindexRange, ResponseMeta, Status, WithMeta,
+    },
+    subscription::{Subscription, SubscriptionBatchResult, TcpSubscriptionRequest},
+    transaction::{
+        DecodedTransaction, EnvelopeData, OpReturnData, Prevout, RunestoneResponse,
+        TestAcceptResponse, Transaction, TransactionData, TransactionQuery, TransactionStatus,
+        TransactionStatusResponse, TransactionWithPrevouts, TransactionsResponse, TxOut,
+    },
+    tx_out::{OutputsResponse, SpenderReference, SpentStatus, TxOutEntry, TxOutResponse},
 };
 
 mod address;
+pub mod amount;
 mod block;
+mod error;
 mod event;
+mod fees;
+mod inscription;
 mod inscription_id;
 mod mempool_entry;
 mod pagination;
 pub mod query;
 mod rune;
+mod sat;
 mod stats;
 mod subscription;
 mod transaction;
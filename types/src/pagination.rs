@@ -1,10 +1,10 @@
 use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Pagination {
     #[serde(default = "default_skip")]
     pub skip: u64,
-    #[serde(default = "default_limit", deserialize_with = "clamp_limit")]
+    #[serde(default = "default_limit")]
     pub limit: u64,
 }
 
@@ -17,6 +17,77 @@ impl Default for Pagination {
     }
 }
 
+impl Pagination {
+    pub fn builder() -> PaginationBuilder {
+        PaginationBuilder::default()
+    }
+
+    /// The `skip` and `limit` query parameters the paginated endpoints take.
+    pub fn to_query(&self) -> String {
+        format!("skip={}&limit={}", self.skip, self.limit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Start {
+    Page(u64),
+    Cursor(u64),
+}
+
+/// Builds a `Pagination` from a page number or from where a previous page
+/// ended, so clients don't compute `skip` by hand.
+#[derive(Debug, Clone, Copy)]
+pub struct PaginationBuilder {
+    start: Start,
+    limit: u64,
+}
+
+impl Default for PaginationBuilder {
+    fn default() -> Self {
+        Self {
+            start: Start::Page(0),
+            limit: default_limit(),
+        }
+    }
+}
+
+impl PaginationBuilder {
+    /// Zero-based page of `limit` items, whichever order the two are set in.
+    pub fn page(mut self, page: u64) -> Self {
+        self.start = Start::Page(page);
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Continues after a previous page, from its `PaginationResponse::offset`.
+    pub fn cursor(mut self, offset: u64) -> Self {
+        self.start = Start::Cursor(offset);
+        self
+    }
+
+    pub fn build(self) -> Pagination {
+        let skip = match self.start {
+            Start::Page(page) => page.saturating_mul(self.limit),
+            Start::Cursor(offset) => offset,
+        };
+
+        Pagination {
+            skip,
+            limit: self.limit,
+        }
+    }
+}
+
+impl From<PaginationBuilder> for Pagination {
+    fn from(builder: PaginationBuilder) -> Self {
+        builder.build()
+    }
+}
+
 fn default_skip() -> u64 {
     0
 }
@@ -50,8 +121,76 @@ impl Into<(u64, u64)> for Pagination {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct OrderQuery {
+    #[serde(default)]
+    pub order: Order,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneSearchQuery {
+    pub q: String,
+    #[serde(default = "default_search_limit", deserialize_with = "clamp_limit")]
+    pub limit: u64,
+}
+
+fn default_search_limit() -> u64 {
+    20
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaginationResponse<T> {
     pub items: Vec<T>,
     pub offset: u64,
 }
+
+/// `?cursor=` of the endpoints that can be paginated by key instead of by
+/// offset. Empty for the first page, then a previous page's `next_cursor`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CursorQuery {
+    pub cursor: Option<String>,
+}
+
+/// Page of an endpoint paginated with `?cursor=`. Unlike offsets, cursors
+/// aren't shifted by items added in the meantime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    /// Opaque cursor of the next page, `None` once there are no more items.
+    /// A full page can get one even if nothing follows it.
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_round_trips_through_the_query() {
+        let pagination = Pagination::builder().page(3).limit(20).build();
+        assert_eq!(
+            pagination,
+            Pagination {
+                skip: 60,
+                limit: 20
+            }
+        );
+        assert_eq!(Pagination::builder().limit(20).page(3).build(), pagination);
+
+        let pagination = Pagination::builder().limit(10).cursor(42).build();
+        assert_eq!(pagination.to_query(), "skip=42&limit=10");
+
+        for pagination in [pagination, Pagination::builder().build()] {
+            let parsed: Pagination = serde_urlencoded::from_str(&pagination.to_query()).unwrap();
+            assert_eq!(parsed, pagination);
+        }
+    }
+}
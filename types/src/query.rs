@@ -19,6 +19,8 @@ pub enum BlockParseError {
 pub enum Block {
     Height(u64),
     Hash(BlockHash),
+    /// `-N`, the Nth block counting back from the tip, which is `-1`.
+    FromTip(u64),
 }
 
 impl FromStr for Block {
@@ -27,6 +29,11 @@ impl FromStr for Block {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(if s.len() == 64 {
             Self::Hash(s.parse().map_err(|_| BlockParseError::InvalidHash)?)
+        } else if let Some(offset) = s.strip_prefix('-') {
+            match offset.parse() {
+                Ok(0) | Err(_) => return Err(BlockParseError::InvalidHeight),
+                Ok(offset) => Self::FromTip(offset),
+            }
         } else {
             Self::Height(s.parse().map_err(|_| BlockParseError::InvalidHeight)?)
         })
@@ -38,16 +45,14 @@ impl Display for Block {
         match self {
             Block::Height(height) => write!(f, "{}", height),
             Block::Hash(hash) => write!(f, "{}", hash),
+            Block::FromTip(offset) => write!(f, "-{}", offset),
         }
     }
 }
 
 impl Into<String> for Block {
     fn into(self) -> String {
-        match self {
-            Self::Height(height) => height.to_string(),
-            Self::Hash(hash) => hash.to_string(),
-        }
+        self.to_string()
     }
 }
 
@@ -96,3 +101,18 @@ impl Into<String> for Rune {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_offsets_from_the_tip() {
+        assert!(matches!("-1".parse(), Ok(Block::FromTip(1))));
+        assert!(matches!("-10".parse(), Ok(Block::FromTip(10))));
+        assert!(matches!("10".parse(), Ok(Block::Height(10))));
+        assert!("-0".parse::<Block>().is_err());
+        assert!("--1".parse::<Block>().is_err());
+        assert_eq!(Block::FromTip(2).to_string(), "-2");
+    }
+}
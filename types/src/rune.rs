@@ -1,10 +1,14 @@
 use {
     crate::inscription_id::InscriptionId,
-    bitcoin::Txid,
+    bitcoin::{address::NetworkUnchecked, Address, Txid},
     borsh::{BorshDeserialize, BorshSerialize},
-    ordinals::{RuneId, SpacedRune},
-    serde::{Deserialize, Serialize},
-    std::io::{Read, Result, Write},
+    ordinals::{Rune, RuneId, SpacedRune},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display},
+        io::{Read, Result, Write},
+        str::FromStr,
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,27 +16,97 @@ pub struct MintResponse {
     pub start: Option<u64>,
     pub end: Option<u64>,
     pub mintable: bool,
+    #[serde(with = "crate::amount")]
     pub cap: u128,
+    #[serde(with = "crate::amount")]
     pub amount: u128,
+    #[serde(with = "crate::amount")]
     pub mints: u128,
 }
 
+/// Minting progress of a rune, as returned by `/rune/{rune}/mints`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneMintsResponse {
+    #[serde(with = "crate::amount")]
+    pub cap: u128,
+    #[serde(with = "crate::amount")]
+    pub minted: u128,
+    #[serde(with = "crate::amount")]
+    pub remaining: u128,
+    #[serde(with = "crate::amount")]
+    pub mint_amount: u128,
+    pub mint_start_height: Option<u64>,
+    pub mint_end_height: Option<u64>,
+    /// Whether a mint broadcast now could be included in the next block.
+    pub mintable_now: bool,
+}
+
+/// Mint terms a rune was etched with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneTermsResponse {
+    #[serde(with = "crate::amount")]
+    pub amount: u128,
+    #[serde(with = "crate::amount")]
+    pub cap: u128,
+    pub height_start: Option<u64>,
+    pub height_end: Option<u64>,
+    /// Relative to the etching block.
+    pub offset_start: Option<u64>,
+    pub offset_end: Option<u64>,
+    /// First block mints are valid in, combining the height and offset windows.
+    pub start: Option<u64>,
+    /// Block mints stop being valid in.
+    pub end: Option<u64>,
+}
+
+/// What a rune was etched with, as returned by `/rune/{rune}/etching`. None
+/// of it changes after the etching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneEtchingResponse {
+    pub id: RuneId,
+    pub etching: Txid,
+    pub block: u64,
+    pub timestamp: u64,
+    pub number: u64,
+    pub spaced_rune: SpacedRune,
+    pub rune: Rune,
+    /// Bit `i` set means a spacer follows the `i`th letter of `rune`.
+    pub spacers: u32,
+    pub symbol: Option<char>,
+    pub divisibility: u8,
+    #[serde(with = "crate::amount")]
+    pub premine: u128,
+    pub terms: Option<RuneTermsResponse>,
+    pub turbo: bool,
+    pub inscription_id: Option<InscriptionId>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuneResponse {
     pub id: RuneId,
     pub block: u64,
+    #[serde(with = "crate::amount")]
     pub burned: u128,
     pub divisibility: u8,
     pub etching: Txid,
     pub number: u64,
+    #[serde(with = "crate::amount")]
     pub premine: u128,
+    #[serde(with = "crate::amount")]
     pub supply: u128,
+    /// `premine + minted - burned`.
+    #[serde(with = "crate::amount")]
+    pub circulating_supply: u128,
+    #[serde(with = "crate::amount")]
     pub max_supply: u128,
     pub spaced_rune: SpacedRune,
     pub symbol: Option<char>,
     pub mint: Option<MintResponse>,
+    #[serde(with = "crate::amount")]
     pub burns: u128,
+    #[serde(with = "crate::amount")]
     pub pending_burns: u128,
+    #[serde(with = "crate::amount")]
     pub pending_mints: u128,
     pub inscription_id: Option<InscriptionId>,
     pub timestamp: u64,
@@ -42,9 +116,193 @@ pub struct RuneResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuneAmount {
     pub rune_id: RuneId,
+    #[serde(with = "crate::amount")]
     pub amount: u128,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneBalance {
+    pub rune_id: RuneId,
+    #[serde(with = "crate::amount")]
+    pub amount: u128,
+    pub divisibility: u8,
+    pub symbol: Option<char>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneHolder {
+    /// Holder address, or `unknown` for scripts without an address.
+    pub address: String,
+    #[serde(with = "crate::amount")]
+    pub amount: u128,
+    pub output_count: u64,
+}
+
+/// Operation a transaction performed on a rune.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuneTransactionKind {
+    Etch,
+    Mint,
+    /// Moved an existing balance of the rune.
+    Transfer,
+    Burn,
+}
+
+impl Display for RuneTransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Etch => "etch",
+            Self::Mint => "mint",
+            Self::Transfer => "transfer",
+            Self::Burn => "burn",
+        })
+    }
+}
+
+impl FromStr for RuneTransactionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "etch" => Ok(Self::Etch),
+            "mint" => Ok(Self::Mint),
+            "transfer" => Ok(Self::Transfer),
+            "burn" => Ok(Self::Burn),
+            _ => Err(format!(
+                "invalid kind `{s}`, expected etch, mint, transfer or burn"
+            )),
+        }
+    }
+}
+
+/// Query for `GET /rune/{rune}/transactions`. More filters may be added, so
+/// start from `RuneTransactionsQuery::default()` and set the ones you need.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RuneTransactionsQuery {
+    #[serde(default)]
+    pub order: crate::Order,
+    /// Only include transactions that performed one of these operations on the
+    /// rune. Comma-separated in the query string, e.g. `kind=mint,transfer`.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_kinds",
+        deserialize_with = "deserialize_kinds"
+    )]
+    pub kind: Vec<RuneTransactionKind>,
+    /// Only include transactions involving this address. Requires the server
+    /// to index addresses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address<NetworkUnchecked>>,
+}
+
+/// What `GET /runes` is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuneSort {
+    /// Etching order, which is by etching height and then by position in the
+    /// block.
+    #[default]
+    Etching,
+    /// Premine and minted amount, including what was burned since.
+    Supply,
+    /// Height of the rune's latest confirmed transaction.
+    Activity,
+}
+
+/// Query for `GET /runes`. Without one, runes are listed newest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunesQuery {
+    #[serde(default)]
+    pub sort: RuneSort,
+    #[serde(default)]
+    pub order: crate::Order,
+}
+
+fn serialize_kinds<S>(
+    kinds: &[RuneTransactionKind],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let kinds: Vec<String> = kinds.iter().map(ToString::to_string).collect();
+    serializer.serialize_str(&kinds.join(","))
+}
+
+fn deserialize_kinds<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<RuneTransactionKind>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <String as Deserialize>::deserialize(deserializer)?
+        .split(',')
+        .filter(|kind| !kind.is_empty())
+        .map(|kind| kind.trim().parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Bucket width of `GET /rune/{rune}/chart`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChartInterval {
+    #[serde(rename = "1h")]
+    Hour,
+    #[default]
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "1w")]
+    Week,
+}
+
+impl ChartInterval {
+    pub fn as_secs(self) -> u64 {
+        match self {
+            Self::Hour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+            Self::Week => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// Query for `GET /rune/{rune}/chart`. `from` and `to` are Unix timestamps of
+/// block times, `to` being exclusive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuneChartQuery {
+    #[serde(default)]
+    pub interval: ChartInterval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+}
+
+/// Confirmed activity of a rune in blocks whose time falls in
+/// `[timestamp, timestamp + interval)`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuneChartBucket {
+    pub timestamp: u64,
+    pub mints: u64,
+    #[serde(with = "crate::amount")]
+    pub minted: u128,
+    pub transfers: u64,
+    /// Amount moved by transfers, not counting what the transactions minted.
+    #[serde(with = "crate::amount")]
+    pub transferred: u128,
+    /// Distinct addresses that received the rune.
+    pub unique_holders: u64,
+}
+
+/// Buckets are aligned to the interval since the Unix epoch and contiguous,
+/// so buckets without activity are included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneChartResponse {
+    pub interval: ChartInterval,
+    pub buckets: Vec<RuneChartBucket>,
+}
+
 impl From<(RuneId, u128)> for RuneAmount {
     fn from((rune_id, amount): (RuneId, u128)) -> Self {
         Self { rune_id, amount }
@@ -79,3 +337,52 @@ impl BorshDeserialize for RuneAmount {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions_query_kinds_are_comma_separated() {
+        let query: RuneTransactionsQuery = serde_json::from_str(r#"{"kind":"mint,burn"}"#).unwrap();
+        assert_eq!(
+            query.kind,
+            vec![RuneTransactionKind::Mint, RuneTransactionKind::Burn]
+        );
+        assert_eq!(
+            serde_json::to_string(&query.kind.first()).unwrap(),
+            r#""mint""#
+        );
+
+        assert!(serde_json::from_str::<RuneTransactionsQuery>(r#"{"kind":"mints"}"#).is_err());
+
+        let query: RuneTransactionsQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.kind.is_empty());
+    }
+
+    #[test]
+    fn chart_intervals() {
+        let query: RuneChartQuery = serde_json::from_str(r#"{"interval":"1w"}"#).unwrap();
+        assert_eq!(query.interval, ChartInterval::Week);
+        assert_eq!(query.interval.as_secs(), 604_800);
+
+        let query: RuneChartQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.interval, ChartInterval::Day);
+
+        assert!(serde_json::from_str::<RuneChartQuery>(r#"{"interval":"1m"}"#).is_err());
+    }
+
+    #[test]
+    fn runes_query_defaults_to_newest_first() {
+        let query: RunesQuery = serde_urlencoded::from_str("sort=supply&order=desc").unwrap();
+        assert_eq!(query.sort, RuneSort::Supply);
+        assert_eq!(query.order, crate::Order::Desc);
+
+        let query: RunesQuery = serde_urlencoded::from_str("").unwrap();
+        assert_eq!(query, RunesQuery::default());
+        assert_eq!(query.sort, RuneSort::Etching);
+        assert_eq!(query.order, crate::Order::Desc);
+
+        assert!(serde_urlencoded::from_str::<RunesQuery>("sort=holders").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use {
+    bitcoin::BlockHash,
+    ordinals::{Charm, Rarity, Sat},
+    serde::{Deserialize, Serialize},
+};
+
+/// Properties of a sat, as returned by `/sat/{sat}`. Everything but
+/// `block_hash` is derived from the ordinal number alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatResponse {
+    pub sat: u64,
+    pub name: String,
+    pub decimal: String,
+    pub degree: String,
+    pub percentile: String,
+    pub rarity: Rarity,
+    pub charms: Vec<Charm>,
+    /// Height of the block whose coinbase created the sat.
+    pub height: u32,
+    pub cycle: u32,
+    pub epoch: u32,
+    pub period: u32,
+    /// Position of the sat within its block's subsidy.
+    pub offset: u64,
+    /// Hash of the block at `height`, if it has been indexed.
+    pub block_hash: Option<BlockHash>,
+}
+
+impl From<Sat> for SatResponse {
+    fn from(sat: Sat) -> Self {
+        Self {
+            sat: sat.n(),
+            name: sat.name(),
+            decimal: sat.decimal().to_string(),
+            degree: sat.degree().to_string(),
+            percentile: sat.percentile(),
+            rarity: sat.rarity(),
+            charms: Charm::charms(sat.charms()),
+            height: sat.height().0,
+            cycle: sat.cycle(),
+            epoch: sat.epoch().0,
+            period: sat.period(),
+            offset: sat.third(),
+            block_hash: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sat() {
+        let sat = SatResponse::from(Sat(0));
+        assert_eq!(sat.name, "nvtdijuwxlp");
+        assert_eq!(sat.rarity, Rarity::Mythic);
+        assert_eq!(sat.height, 0);
+        assert_eq!(sat.degree, "0°0′0″0‴");
+        assert_eq!(sat.offset, 0);
+    }
+}
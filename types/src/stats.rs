@@ -6,9 +6,85 @@ pub struct BlockTip {
     pub hash: String,
 }
 
+/// Index tip a response was computed at, returned with `?with_meta=true`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMeta {
+    pub height: u64,
+    pub block_hash: String,
+    /// Unix time in seconds.
+    pub computed_at: u64,
+    /// The tip moved while the response was computed, so `data` may reflect
+    /// the next block too.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tip_changed: bool,
+}
+
+/// A response wrapped by `?with_meta=true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithMeta<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+/// `GET /status`. Fields may be added, so it can only be built with
+/// `Status::new`.
 #[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Status {
+    /// Chain the server indexes, named like its `--chain`.
+    pub chain: String,
+    /// Hash of the chain's genesis block.
+    pub genesis_hash: String,
     pub block_tip: BlockTip,
     pub runes_count: u64,
     pub mempool_tx_count: u64,
 }
+
+impl Status {
+    pub fn new(
+        chain: String,
+        genesis_hash: String,
+        block_tip: BlockTip,
+        runes_count: u64,
+        mempool_tx_count: u64,
+    ) -> Self {
+        Self {
+            chain,
+            genesis_hash,
+            block_tip,
+            runes_count,
+            mempool_tx_count,
+        }
+    }
+}
+
+/// `GET /debug/index-stats`. Key counts and sizes are RocksDB's estimates.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexStats {
+    /// `None` until the first block is indexed.
+    pub indexed_height: Option<u64>,
+    /// Reorgs handled since startup.
+    pub reorg_count: u64,
+    /// When indexed data was last written to the database, `None` if it
+    /// hasn't been since startup.
+    pub last_flush_epoch_secs: Option<u64>,
+    /// Size of the files in the database directory.
+    pub disk_size_bytes: u64,
+    pub column_families: Vec<ColumnFamilyStats>,
+}
+
+/// Body and response of `POST /admin/reindex`: blocks `from_height` up to the
+/// indexed tip `to_height` are rolled back and indexed again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReindexRange {
+    pub from_height: u64,
+    pub to_height: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnFamilyStats {
+    pub name: String,
+    pub estimated_keys: u64,
+    pub sst_files_bytes: u64,
+    pub memtables_bytes: u64,
+}
@@ -1,22 +1,267 @@
 use {
-    super::EventType,
+    super::{ErrorCode, EventType},
     borsh::{BorshDeserialize, BorshSerialize},
+    ordinals::RuneId,
     serde::{Deserialize, Serialize},
+    std::io::{Error, ErrorKind, Read, Result, Write},
     uuid::Uuid,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+/// Most entries preallocated for a decoded list, whose length could be
+/// anything in a corrupted entry.
+const MAX_PREALLOCATED: u32 = 1024;
+
+/// A webhook subscription. `POST /subscription` assigns `id` when it's
+/// omitted, sets `created_at_epoch_secs` and answers with the subscription as
+/// stored. Options may be added, so it's created with `Subscription::new`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Subscription {
+    #[serde(default)]
     pub id: Uuid,
     pub endpoint: String,
     pub event_types: Vec<EventType>,
+    #[serde(default)]
     pub last_success_epoch_secs: u64,
+    /// Only forward address events for these addresses. Empty means all.
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Only forward rune events for these runes. Empty means all.
+    #[serde(default)]
+    pub runes: Vec<RuneId>,
+    /// Key of the `X-Titan-Signature` HMAC-SHA256 of each delivered body.
+    /// Never returned by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Set by the server when the subscription is first added. 0 for
+    /// subscriptions added before it was recorded.
+    #[serde(default)]
+    pub created_at_epoch_secs: u64,
+}
+
+impl Subscription {
+    /// A subscription to `event_types` without filters or a secret. Its id
+    /// is nil, so the server assigns one.
+    pub fn new(endpoint: String, event_types: Vec<EventType>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            endpoint,
+            event_types,
+            last_success_epoch_secs: 0,
+            addresses: Vec::new(),
+            runes: Vec::new(),
+            secret: None,
+            created_at_epoch_secs: 0,
+        }
+    }
+}
+
+impl BorshSerialize for Subscription {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        BorshSerialize::serialize(&self.id, writer)?;
+        BorshSerialize::serialize(&self.endpoint, writer)?;
+        BorshSerialize::serialize(&self.event_types, writer)?;
+        BorshSerialize::serialize(&self.last_success_epoch_secs, writer)?;
+        BorshSerialize::serialize(&self.addresses, writer)?;
+
+        // Borsh doesn't know about `RuneId`, so write out (block, tx) ourselves.
+        // With a u32 length, like Borsh's own.
+        let runes_len = u32::try_from(self.runes.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "too many runes"))?;
+        BorshSerialize::serialize(&runes_len, writer)?;
+        for rune_id in &self.runes {
+            BorshSerialize::serialize(&rune_id.block, writer)?;
+            BorshSerialize::serialize(&rune_id.tx, writer)?;
+        }
+
+        BorshSerialize::serialize(&self.secret, writer)?;
+        BorshSerialize::serialize(&self.created_at_epoch_secs, writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Subscription {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let id = Uuid::deserialize_reader(reader)?;
+        let endpoint = String::deserialize_reader(reader)?;
+        let event_types = Vec::<EventType>::deserialize_reader(reader)?;
+        let last_success_epoch_secs = u64::deserialize_reader(reader)?;
+
+        // Subscriptions stored before filters were added end here, so a
+        // missing addresses length means no filters.
+        let mut len = [0u8; 4];
+        let addresses = match reader.read(&mut len[..1])? {
+            0 => None,
+            _ => {
+                reader.read_exact(&mut len[1..])?;
+                let len = u32::from_le_bytes(len);
+                let mut addresses = Vec::with_capacity(len.min(MAX_PREALLOCATED) as usize);
+                for _ in 0..len {
+                    addresses.push(String::deserialize_reader(reader)?);
+                }
+                Some(addresses)
+            }
+        };
+
+        let (addresses, runes, secret, created_at_epoch_secs) = match addresses {
+            Some(addresses) => {
+                let runes_len = u32::deserialize_reader(reader)?;
+                let mut runes = Vec::with_capacity(runes_len.min(MAX_PREALLOCATED) as usize);
+                for _ in 0..runes_len {
+                    let block = u64::deserialize_reader(reader)?;
+                    let tx = u32::deserialize_reader(reader)?;
+                    runes.push(RuneId { block, tx });
+                }
+                // Subscriptions stored before secrets were added end here,
+                // and those stored before creation times right after it.
+                let mut tag = [0u8; 1];
+                let (secret, created_at_epoch_secs) = match reader.read(&mut tag)? {
+                    0 => (None, 0),
+                    _ => {
+                        let secret = match tag[0] {
+                            0 => None,
+                            _ => Some(String::deserialize_reader(reader)?),
+                        };
+                        let mut created_at = [0u8; 8];
+                        let created_at_epoch_secs = match reader.read(&mut created_at[..1])? {
+                            0 => 0,
+                            _ => {
+                                reader.read_exact(&mut created_at[1..])?;
+                                u64::from_le_bytes(created_at)
+                            }
+                        };
+                        (secret, created_at_epoch_secs)
+                    }
+                };
+                (addresses, runes, secret, created_at_epoch_secs)
+            }
+            None => (Vec::new(), Vec::new(), None, 0),
+        };
+
+        Ok(Self {
+            id,
+            endpoint,
+            event_types,
+            last_success_epoch_secs,
+            addresses,
+            runes,
+            secret,
+            created_at_epoch_secs,
+        })
+    }
+}
+
+/// Outcome of one subscription of `POST /subscriptions/batch`, in the order
+/// they were sent. `code` is the HTTP status `POST /subscription` would have
+/// answered with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SubscriptionBatchResult {
+    Success {
+        subscription: Subscription,
+    },
+    Error {
+        code: u16,
+        error_code: ErrorCode,
+        error: String,
+    },
 }
 
 /// The expected subscription request from the TCP client.
 /// For example, the client should send:
 ///   {"subscribe": ["RuneEtched", "RuneMinted"]}
+/// Set `from_height` to first replay the buffered events since that block.
+/// Options may be added, so it's created with `TcpSubscriptionRequest::new`.
 #[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct TcpSubscriptionRequest {
     pub subscribe: Vec<EventType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_height: Option<u64>,
+}
+
+impl TcpSubscriptionRequest {
+    /// A request for live `subscribe` events, without a replay.
+    pub fn new(subscribe: Vec<EventType>) -> Self {
+        Self {
+            subscribe,
+            from_height: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(BorshSerialize)]
+    struct LegacySubscription {
+        id: Uuid,
+        endpoint: String,
+        event_types: Vec<EventType>,
+        last_success_epoch_secs: u64,
+    }
+
+    #[test]
+    fn borsh_round_trip() {
+        let subscription = Subscription {
+            id: Uuid::new_v4(),
+            endpoint: "http://localhost:8080".to_string(),
+            event_types: vec![EventType::RuneMinted],
+            last_success_epoch_secs: 1,
+            addresses: vec!["bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string()],
+            runes: vec![RuneId {
+                block: 840000,
+                tx: 1,
+            }],
+            secret: Some("secret".to_string()),
+            created_at_epoch_secs: 2,
+        };
+
+        let bytes = borsh::to_vec(&subscription).unwrap();
+        let decoded = Subscription::try_from_slice(&bytes).unwrap();
+
+        assert_eq!(decoded.addresses, subscription.addresses);
+        assert_eq!(decoded.runes, subscription.runes);
+        assert_eq!(decoded.secret, subscription.secret);
+        assert_eq!(decoded.created_at_epoch_secs, 2);
+    }
+
+    #[test]
+    fn borsh_decodes_subscriptions_without_filters() {
+        let bytes = borsh::to_vec(&LegacySubscription {
+            id: Uuid::new_v4(),
+            endpoint: "http://localhost:8080".to_string(),
+            event_types: vec![EventType::NewBlock],
+            last_success_epoch_secs: 1,
+        })
+        .unwrap();
+
+        let decoded = Subscription::try_from_slice(&bytes).unwrap();
+
+        assert_eq!(decoded.event_types, vec![EventType::NewBlock]);
+        assert!(decoded.addresses.is_empty());
+        assert!(decoded.runes.is_empty());
+    }
+
+    #[test]
+    fn borsh_rejects_lengths_past_the_end() {
+        let legacy = borsh::to_vec(&LegacySubscription {
+            id: Uuid::new_v4(),
+            endpoint: "http://localhost:8080".to_string(),
+            event_types: vec![EventType::NewBlock],
+            last_success_epoch_secs: 1,
+        })
+        .unwrap();
+
+        let mut addresses = legacy.clone();
+        addresses.extend(u32::MAX.to_le_bytes());
+        assert!(Subscription::try_from_slice(&addresses).is_err());
+
+        let mut runes = legacy;
+        runes.extend(0u32.to_le_bytes());
+        runes.extend(u32::MAX.to_le_bytes());
+        assert!(Subscription::try_from_slice(&runes).is_err());
+    }
 }
@@ -3,8 +3,12 @@ use {
     crate::tx_out::SpentStatus,
     bitcoin::{BlockHash, ScriptBuf, TxIn, Txid},
     borsh::{BorshDeserialize, BorshSerialize},
+    ordinals::Artifact,
     serde::{Deserialize, Serialize},
-    std::io::{Read, Result, Write},
+    std::{
+        collections::HashMap,
+        io::{Read, Result, Write},
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +71,136 @@ impl From<(bitcoin::Transaction, TransactionStatus)> for Transaction {
     }
 }
 
+/// Returned by `/tx/{txid}/status`. A transaction the index doesn't know is
+/// neither confirmed nor in the mempool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStatusResponse {
+    #[serde(flatten)]
+    pub status: TransactionStatus,
+    pub in_mempool: bool,
+}
+
+/// The output spent by a transaction input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prevout {
+    pub value: u64,
+    pub script_pubkey: ScriptBuf,
+    /// `None` for scripts without an address.
+    pub address: Option<String>,
+    pub runes: Vec<RuneAmount>,
+    pub risky_runes: Vec<RuneAmount>,
+}
+
+/// A transaction as returned by `/tx/{txid}?prevouts=true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionWithPrevouts {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    /// One entry per input. `None` for the coinbase input and for prevouts
+    /// that couldn't be resolved.
+    pub prevouts: Vec<Option<Prevout>>,
+    /// Input value minus output value, set when every prevout was resolved.
+    pub fee: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TransactionQuery {
+    /// Inline the output spent by each input.
+    #[serde(default)]
+    pub prevouts: bool,
+}
+
+/// A raw transaction decoded by `POST /tx/decode`, with the rune balances its
+/// outputs would receive if it were broadcast now.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodedTransaction {
+    pub transaction: Transaction,
+    /// The runestone or cenotaph in the transaction, if any.
+    pub artifact: Option<Artifact>,
+    /// Rune balances of the spent outputs known to the index.
+    pub input_runes: Vec<RuneAmount>,
+    pub minted: Option<RuneAmount>,
+    pub burned: Vec<RuneAmount>,
+}
+
+/// Result of `POST /tx/test-accept`: whether the node would accept the
+/// transaction into its mempool. Nothing is broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestAcceptResponse {
+    pub txid: Txid,
+    pub allowed: bool,
+    /// Why the node rejected the transaction, e.g. `min relay fee not met`.
+    pub reject_reason: Option<String>,
+    /// Only known if the transaction is allowed.
+    pub vsize: Option<u64>,
+    /// Fee in sats, only known if the transaction is allowed.
+    pub fee: Option<u64>,
+    /// What the transaction would do to runes that's likely unintended, like
+    /// burning the input runes with a cenotaph. The node doesn't check runes,
+    /// so these don't affect `allowed`.
+    pub rune_warnings: Vec<String>,
+}
+
+/// Data embedded in a transaction, undecoded, as returned by `/tx/{txid}/data`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionData {
+    /// Inscription envelopes in the inputs' tapscripts, in order.
+    pub envelopes: Vec<EnvelopeData>,
+    pub op_returns: Vec<OpReturnData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeData {
+    pub input: u32,
+    /// Position among the envelopes of the input.
+    pub offset: u32,
+    /// Hex of each push after the `ord` protocol id: tag and value pairs,
+    /// then the empty push that starts the body and the body chunks.
+    pub payload: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpReturnData {
+    pub vout: u32,
+    /// Hex of the output script after `OP_RETURN`, push opcodes included.
+    pub data: String,
+    /// Whether this is the output runes are read from: the first one whose
+    /// data starts with `OP_PUSHNUM_13`.
+    pub runestone: bool,
+}
+
+/// The runestone of a transaction as the indexer parsed it, returned by
+/// `/tx/{txid}/runestone`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunestoneResponse {
+    pub artifact: Artifact,
+    pub cenotaph: bool,
+    /// Why the runestone is a cenotaph, e.g. `unrecognized even tag`.
+    pub flaw: Option<String>,
+}
+
+impl From<Artifact> for RunestoneResponse {
+    fn from(artifact: Artifact) -> Self {
+        let (cenotaph, flaw) = match &artifact {
+            Artifact::Cenotaph(cenotaph) => (true, cenotaph.flaw.map(|flaw| flaw.to_string())),
+            Artifact::Runestone(_) => (false, None),
+        };
+
+        Self {
+            artifact,
+            cenotaph,
+            flaw,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionsResponse {
+    pub transactions: HashMap<Txid, Transaction>,
+    pub missing: Vec<Txid>,
+    pub errors: HashMap<Txid, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TxOut {
     pub value: u64,
@@ -109,3 +243,46 @@ impl BorshDeserialize for TxOut {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoin::hashes::Hash,
+        ordinals::{Cenotaph, Flaw},
+    };
+
+    #[test]
+    fn transaction_with_prevouts_is_flattened() {
+        let transaction = TransactionWithPrevouts {
+            transaction: Transaction {
+                txid: Txid::all_zeros(),
+                version: 2,
+                lock_time: 0,
+                input: vec![],
+                output: vec![],
+                status: TransactionStatus::unconfirmed(),
+            },
+            prevouts: vec![None],
+            fee: None,
+        };
+
+        let json = serde_json::to_value(&transaction).unwrap();
+        assert_eq!(json["version"], 2);
+        assert_eq!(json["prevouts"], serde_json::json!([null]));
+
+        let decoded: TransactionWithPrevouts = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.transaction.version, 2);
+    }
+
+    #[test]
+    fn cenotaph_runestone_response() {
+        let response = RunestoneResponse::from(Artifact::Cenotaph(Cenotaph {
+            flaw: Some(Flaw::UnrecognizedEvenTag),
+            ..Default::default()
+        }));
+
+        assert!(response.cenotaph);
+        assert_eq!(response.flaw.as_deref(), Some("unrecognized even tag"));
+    }
+}
@@ -1,9 +1,12 @@
 use {
-    crate::rune::RuneAmount,
-    bitcoin::{hashes::Hash, Txid},
+    crate::rune::{RuneAmount, RuneBalance},
+    bitcoin::{hashes::Hash, OutPoint, Txid},
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
-    std::io::{Read, Result, Write},
+    std::{
+        collections::HashMap,
+        io::{Read, Result, Write},
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,3 +94,86 @@ impl TxOutEntry {
         !self.runes.is_empty()
     }
 }
+
+/// `GET /output/{outpoint}`. Fields may be added, so it's created with
+/// `TxOutResponse::new`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TxOutResponse {
+    pub runes: Vec<RuneBalance>,
+    pub risky_runes: Vec<RuneBalance>,
+    pub value: u64,
+    pub spent: SpentStatus,
+    /// Transaction spending the output, confirmed or in the mempool.
+    #[serde(default)]
+    pub spending_txid: Option<Txid>,
+    /// Height of the block that confirmed the spending transaction. Only known
+    /// when bitcoin transactions are indexed.
+    #[serde(default)]
+    pub spending_height: Option<u64>,
+    /// Whether the spending transaction is still in the mempool.
+    #[serde(default)]
+    pub spent_in_mempool: bool,
+}
+
+impl TxOutResponse {
+    /// An output without details on its spending transaction.
+    pub fn new(
+        runes: Vec<RuneBalance>,
+        risky_runes: Vec<RuneBalance>,
+        value: u64,
+        spent: SpentStatus,
+    ) -> Self {
+        Self {
+            runes,
+            risky_runes,
+            value,
+            spent,
+            spending_txid: None,
+            spending_height: None,
+            spent_in_mempool: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputsResponse {
+    pub outputs: HashMap<OutPoint, TxOutResponse>,
+    pub missing: Vec<OutPoint>,
+    pub errors: HashMap<OutPoint, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[test]
+    fn outputs_response_json_roundtrip() {
+        let outpoint = OutPoint::from_str(
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0",
+        )
+        .unwrap();
+
+        let mut response = OutputsResponse::default();
+        response.outputs.insert(
+            outpoint,
+            TxOutResponse {
+                runes: vec![],
+                risky_runes: vec![],
+                value: 5_000_000_000,
+                spent: SpentStatus::Unspent,
+                spending_txid: None,
+                spending_height: None,
+                spent_in_mempool: false,
+            },
+        );
+        response.errors.insert(outpoint, "error".to_string());
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains(&format!("\"{outpoint}\":")));
+
+        let response: OutputsResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(response.outputs[&outpoint].value, 5_000_000_000);
+        assert_eq!(response.errors[&outpoint], "error");
+    }
+}